@@ -0,0 +1,161 @@
+use crate::config::{self, Config};
+use crate::usage;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+const FILE_PREFIX: &str = "audit-";
+const FILE_SUFFIX: &str = ".jsonl";
+
+// Copied from the config so request code does not need it; see `configure`
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static RETENTION_DAYS: AtomicU64 = AtomicU64::new(90);
+
+/// Serializes appends; holds the date of the last file written so each new day's first
+/// entry also trims old files.
+static LAST_DATE: Mutex<String> = Mutex::new(String::new());
+
+/// One request sent to OpenRouter and what came back, as one line of the audit file.
+#[derive(Serialize)]
+struct Entry<'a> {
+    /// Unix milliseconds when the response or failure arrived.
+    timestamp: u64,
+    app_version: &'static str,
+    endpoint: &'a str,
+    /// The request body exactly as sent (after redaction, if that is on).
+    request: Value,
+    duration_ms: u64,
+    ok: bool,
+    /// The response body, parsed when it is JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Applies `audit_log` and `audit_retention_days`.
+pub fn configure(config: &Config) {
+    ENABLED.store(config.audit_log, Ordering::Relaxed);
+    RETENTION_DAYS.store(config.audit_retention_days, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn dir() -> Result<PathBuf> {
+    Ok(config::app_dir()?.join("audit"))
+}
+
+/// Appends a request and its outcome (the response body, or the error) to today's
+/// audit file when the audit log is on. Failures are logged, never returned, so auditing
+/// cannot break a translation.
+pub fn record(
+    endpoint: &str,
+    request: &impl Serialize,
+    outcome: Result<&str, &anyhow::Error>,
+    duration: Duration,
+) {
+    if !enabled() {
+        return;
+    }
+    let request = serde_json::to_value(request).unwrap_or(Value::Null);
+    let (response, error) = match outcome {
+        Ok(body) => (
+            Some(serde_json::from_str(body).unwrap_or_else(|_| Value::String(body.to_string()))),
+            None,
+        ),
+        Err(e) => (None, Some(format!("{:#}", e))),
+    };
+    let entry = Entry {
+        timestamp: now().as_millis() as u64,
+        app_version: env!("CARGO_PKG_VERSION"),
+        endpoint,
+        request,
+        duration_ms: duration.as_millis() as u64,
+        ok: error.is_none(),
+        response,
+        error,
+    };
+    if let Err(e) = append(&entry) {
+        warn!(error = %e, "Audit log write failed");
+    }
+}
+
+fn append(entry: &Entry) -> Result<()> {
+    let mut last_date = LAST_DATE.lock().unwrap();
+    let dir = dir()?;
+    fs::create_dir_all(&dir).context("create audit directory")?;
+    let date = today();
+    let mut line = serde_json::to_string(entry).context("serialize audit entry")?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(format!("{}{}{}", FILE_PREFIX, date, FILE_SUFFIX)))
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .context("append audit file")?;
+    if *last_date != date {
+        *last_date = date;
+        cleanup();
+    }
+    Ok(())
+}
+
+/// Deletes audit files older than `audit_retention_days`; 0 keeps them all. Runs with the
+/// scheduler's log cleanup and on the first entry of each day.
+pub fn cleanup() {
+    let retention_days = RETENTION_DAYS.load(Ordering::Relaxed);
+    if retention_days == 0 {
+        return;
+    }
+    let Ok(entries) = dir().and_then(|dir| fs::read_dir(dir).context("read audit directory"))
+    else {
+        return;
+    };
+    let cutoff = date_string(
+        now()
+            .as_secs()
+            .saturating_sub(retention_days * 24 * 60 * 60),
+    );
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(date) = name
+            .to_str()
+            .and_then(|name| name.strip_prefix(FILE_PREFIX))
+            .and_then(|name| name.strip_suffix(FILE_SUFFIX))
+        else {
+            continue;
+        };
+        // ISO dates compare correctly as strings
+        if date < cutoff.as_str() && fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    if removed > 0 {
+        debug!(removed, retention_days, "Old audit files deleted");
+    }
+}
+
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+fn today() -> String {
+    date_string(now().as_secs())
+}
+
+fn date_string(secs: u64) -> String {
+    let (year, month, day) = usage::civil_date(secs);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
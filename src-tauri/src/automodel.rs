@@ -0,0 +1,88 @@
+use crate::config::Config;
+use crate::mask;
+use regex::Regex;
+use std::sync::LazyLock;
+use tracing::info;
+
+/// The `model` value that picks a model per request.
+pub const AUTO: &str = "auto";
+
+/// Lines that read as source code rather than prose: statement endings, braces, common
+/// keywords, arrows, paths and comments.
+static CODE_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?x)
+        [;{}]\s*$
+        | ^\s*(?:fn|def|let|const|var|class|import|from|return|if|for|while|pub|use|\#include|SELECT|INSERT)\b
+        | =>|->|::|==|!=|&&|\|\|
+        | ^\s*(?://|\#\s|/\*|\*\s|--\s)
+        | ^\s*[$>]\s",
+    )
+    .expect("valid code line regex")
+});
+
+/// Why a model was chosen, for the log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Reason {
+    Short,
+    Long,
+    Technical,
+}
+
+pub fn is_auto(model: &str) -> bool {
+    model.trim().eq_ignore_ascii_case(AUTO)
+}
+
+/// Replaces `model: "auto"` with the fast or strong model of `auto_model` for `text`:
+/// technical or long text gets the strong model, anything else the fast one. Other
+/// models are left alone.
+pub fn resolve(config: &mut Config, text: &str) {
+    if !is_auto(&config.model) {
+        return;
+    }
+    let settings = &config.auto_model;
+    let input_len = text.chars().count();
+    let technical_percent = technical_percent(text);
+    let reason = if technical_percent >= settings.technical_min_percent {
+        Reason::Technical
+    } else if input_len > settings.short_max_chars {
+        Reason::Long
+    } else {
+        Reason::Short
+    };
+    let (preferred, other) = match reason {
+        Reason::Short => (&settings.fast_model, &settings.strong_model),
+        Reason::Long | Reason::Technical => (&settings.strong_model, &settings.fast_model),
+    };
+    // One empty slot falls back to the other so "auto" still sends a real model
+    let model = if preferred.trim().is_empty() {
+        other
+    } else {
+        preferred
+    };
+    let model = model.trim().to_string();
+    info!(
+        model = %model,
+        reason = ?reason,
+        input_len,
+        technical_percent,
+        "Model chosen automatically"
+    );
+    config.model = model;
+}
+
+/// How much of `text` is code, URLs or markup, in percent: the larger of the share of
+/// code-like lines and the share of bytes `mask` would protect.
+fn technical_percent(text: &str) -> u8 {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if lines.is_empty() {
+        return 0;
+    }
+    let code_lines = lines.iter().filter(|line| CODE_LINE.is_match(line)).count();
+    let by_lines = code_lines * 100 / lines.len();
+    let by_bytes = mask::mask(text).protected_len() * 100 / text.len().max(1);
+    by_lines.max(by_bytes).min(100) as u8
+}
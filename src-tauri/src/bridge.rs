@@ -0,0 +1,328 @@
+use crate::config::Config;
+use crate::i18n::{self, Msg};
+use crate::requests::ActiveRequest;
+use crate::{languages, openrouter, shutdown, Action, AppState};
+use anyhow::{Context, Result};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, error, info, warn};
+
+/// How often a running request reports its elapsed time.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+/// Random bytes in a generated `bridge_token`, which is written as hex.
+const TOKEN_BYTES: usize = 16;
+/// Browser origins allowed to connect. Any web page may open a socket to localhost, so
+/// page origins are refused; clients without an `Origin` header are not browsers.
+const EXTENSION_ORIGINS: [&str; 3] = [
+    "chrome-extension://",
+    "moz-extension://",
+    "safari-web-extension://",
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Translate {
+        id: String,
+        text: String,
+        #[serde(default)]
+        target_language: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Started {
+        id: String,
+        model: String,
+        target_language: String,
+    },
+    Progress {
+        id: String,
+        elapsed_ms: u64,
+    },
+    Result {
+        id: String,
+        translation: String,
+        duration_ms: u64,
+    },
+    Error {
+        id: Option<String>,
+        message: String,
+    },
+}
+
+/// The settings the listener depends on; `apply_config` restarts it when they change.
+pub fn settings(config: &Config) -> (bool, u16, &str) {
+    (
+        config.bridge_enabled,
+        config.bridge_port,
+        &config.bridge_token,
+    )
+}
+
+/// Gives an enabled bridge a random `bridge_token` when it has none, so only an
+/// extension the user pasted the token into can connect. Returns whether one was made.
+pub fn ensure_token(config: &mut Config) -> bool {
+    if !config.bridge_enabled || !config.bridge_token.is_empty() {
+        return false;
+    }
+    let mut bytes = [0u8; TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    config.bridge_token = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    info!("Browser bridge token created");
+    true
+}
+
+/// Stops the running listener, if any, and starts a new one when the bridge is enabled.
+/// Open connections keep running until their client disconnects.
+pub fn restart(app: &AppHandle, config: &Config) {
+    let state = app.state::<AppState>();
+    let mut task = state.bridge.lock().unwrap();
+    if let Some(task) = task.take() {
+        task.abort();
+        info!("Browser bridge stopped");
+    }
+    if !config.bridge_enabled {
+        return;
+    }
+
+    let (port, token) = (config.bridge_port, config.bridge_token.clone());
+    let app = app.clone();
+    *task = Some(tauri::async_runtime::spawn(async move {
+        if let Err(e) = listen(app, port, token).await {
+            error!(error = %e, port, "Browser bridge failed");
+        }
+    }));
+}
+
+async fn listen(app: AppHandle, port: u16, token: String) -> Result<()> {
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))
+        .await
+        .with_context(|| format!("bind 127.0.0.1:{}", port))?;
+    info!(port, "Browser bridge listening");
+    loop {
+        let (stream, peer) = listener.accept().await.context("accept connection")?;
+        let app = app.clone();
+        let token = token.clone();
+        tauri::async_runtime::spawn(async move {
+            match serve(app, stream, &token).await {
+                Ok(()) => debug!(peer = %peer, "Bridge client disconnected"),
+                Err(e) => debug!(error = %e, peer = %peer, "Bridge connection closed"),
+            }
+        });
+    }
+}
+
+// The handshake callback's error type is fixed by tungstenite
+#[allow(clippy::result_large_err)]
+async fn serve(app: AppHandle, stream: TcpStream, token: &str) -> Result<()> {
+    let socket = tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response| {
+        authorize(request, response, token)
+    })
+    .await
+    .context("WebSocket handshake")?;
+    debug!("Bridge client connected");
+
+    let (mut sink, mut incoming) = socket.split();
+    let (tx, mut rx) = mpsc::unbounded_channel::<ServerMessage>();
+    let writer = tauri::async_runtime::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            let Ok(text) = serde_json::to_string(&message) else {
+                continue;
+            };
+            if sink.send(Message::Text(text.into())).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Some(message) = incoming.next().await {
+        match message.context("read message")? {
+            Message::Text(text) => match serde_json::from_str(text.as_str()) {
+                Ok(ClientMessage::Translate {
+                    id,
+                    text,
+                    target_language,
+                }) => {
+                    tauri::async_runtime::spawn(translate(
+                        app.clone(),
+                        tx.clone(),
+                        id,
+                        text,
+                        target_language,
+                    ));
+                }
+                Err(e) => {
+                    let _ = tx.send(ServerMessage::Error {
+                        id: None,
+                        message: format!("Invalid request: {}", e),
+                    });
+                }
+            },
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    writer.abort();
+    Ok(())
+}
+
+#[allow(clippy::result_large_err)]
+fn authorize(
+    request: &Request,
+    response: Response,
+    token: &str,
+) -> Result<Response, ErrorResponse> {
+    let origin = request
+        .headers()
+        .get("origin")
+        .and_then(|origin| origin.to_str().ok());
+    let origin_allowed = origin.is_none_or(|origin| {
+        EXTENSION_ORIGINS
+            .iter()
+            .any(|prefix| origin.starts_with(prefix))
+    });
+    let expected = format!("token={}", token);
+    let token_valid = token.is_empty()
+        || request
+            .uri()
+            .query()
+            .is_some_and(|query| query.split('&').any(|pair| pair == expected));
+    if origin_allowed && token_valid {
+        return Ok(response);
+    }
+
+    warn!(
+        origin = ?origin,
+        origin_allowed,
+        token_valid,
+        "Bridge connection refused"
+    );
+    let mut refused = ErrorResponse::new(Some("Forbidden".to_string()));
+    *refused.status_mut() = StatusCode::FORBIDDEN;
+    Err(refused)
+}
+
+async fn translate(
+    app: AppHandle,
+    tx: UnboundedSender<ServerMessage>,
+    id: String,
+    text: String,
+    target_language: Option<String>,
+) {
+    let state = app.state::<AppState>();
+    let mut config = Config::clone(&state.config());
+    if let Some(language) = target_language.filter(|language| !language.trim().is_empty()) {
+        config.target_language = languages::normalize(&language);
+    }
+    if config.fast_mode {
+        crate::apply_fast_mode(&mut config, &state.latency);
+    }
+    let length = text.chars().count();
+    // There is nobody to confirm an oversized text, so it is refused outright
+    let refused = if shutdown::in_progress() {
+        Some(i18n::t(Msg::Quitting))
+    } else if *state.paused.lock().unwrap() {
+        Some(i18n::t(Msg::Paused))
+    } else if text.trim().is_empty() {
+        Some(i18n::t(Msg::TextEmpty))
+    } else if config.target_language.trim().is_empty() {
+        Some(i18n::t(Msg::TargetLanguageNotSet))
+    } else if config.max_input_chars > 0 && length > config.max_input_chars {
+        Some(i18n::t(Msg::InputTooLong(length, config.max_input_chars)))
+    } else {
+        None
+    };
+    if let Some(message) = refused {
+        debug!(reason = %message, input_chars = length, "Bridge request refused");
+        let _ = tx.send(ServerMessage::Error {
+            id: Some(id),
+            message,
+        });
+        return;
+    }
+
+    // Counted with hotkey requests, so `max_concurrent_requests` and the quit guard see it
+    let Some(_in_flight) = state.requests.begin(
+        config.max_concurrent_requests,
+        ActiveRequest {
+            request_id: crate::next_request_id(),
+            action: Action::Translate.name(),
+            model: config.model.clone(),
+            target_language: config.target_language.clone(),
+            input_len: text.len(),
+            elapsed_ms: 0,
+        },
+    ) else {
+        let _ = tx.send(ServerMessage::Error {
+            id: Some(id),
+            message: i18n::t(Msg::RequestsInProgress(state.requests.len())),
+        });
+        return;
+    };
+
+    let _ = tx.send(ServerMessage::Started {
+        id: id.clone(),
+        model: config.model.clone(),
+        target_language: config.target_language.clone(),
+    });
+    let started = Instant::now();
+    let ticker = {
+        let (tx, id) = (tx.clone(), id.clone());
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(PROGRESS_INTERVAL).await;
+                let progress = ServerMessage::Progress {
+                    id: id.clone(),
+                    elapsed_ms: started.elapsed().as_millis() as u64,
+                };
+                if tx.send(progress).is_err() {
+                    break;
+                }
+            }
+        })
+    };
+    let result = openrouter::translate(&state.http(), &config, &text).await;
+    ticker.abort();
+
+    let elapsed_ms = started.elapsed().as_millis();
+    state
+        .metrics
+        .record(&config.model, elapsed_ms, result.is_ok(), text.len());
+    let reply = match result {
+        Ok(translation) => {
+            state.latency.record(&config.model, elapsed_ms);
+            info!(
+                input_len = text.len(),
+                translated_len = translation.len(),
+                elapsed_ms = elapsed_ms as u64,
+                "Bridge request translated"
+            );
+            ServerMessage::Result {
+                id,
+                translation,
+                duration_ms: elapsed_ms as u64,
+            }
+        }
+        Err(e) => {
+            warn!(error = %e, "Bridge request failed");
+            ServerMessage::Error {
+                id: Some(id),
+                message: e.to_string(),
+            }
+        }
+    };
+    let _ = tx.send(reply);
+}
@@ -0,0 +1,169 @@
+use crate::config::{self, Config};
+use anyhow::{anyhow, Context, Result};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+const FORMAT: &str = "thirdspace-settings";
+const BUNDLE_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+
+/// A settings archive: one JSON file, with its contents either inline or sealed with a
+/// passphrase.
+#[derive(Serialize, Deserialize)]
+struct Bundle {
+    format: String,
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    contents: Option<Contents>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encrypted: Option<Sealed>,
+}
+
+/// Everything that moves between machines. Custom action prompt templates live in the
+/// config.
+#[derive(Serialize, Deserialize)]
+struct Contents {
+    config: Value,
+}
+
+/// ChaCha20-Poly1305 ciphertext of the serialized `Contents`, keyed by Argon2id.
+#[derive(Serialize, Deserialize)]
+struct Sealed {
+    kdf: String,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Writes the settings bundle. Without a passphrase the secrets (`SECRET_FIELDS`) are
+/// left out, so a plain-text export is safe to share.
+pub fn export(config: &Config, path: &Path, passphrase: Option<&str>) -> Result<()> {
+    let mut document = serde_json::to_value(config).context("serialize config")?;
+    if passphrase.is_none() {
+        config::redact_secrets(&mut document);
+    }
+    let contents = Contents { config: document };
+
+    let bundle = match passphrase {
+        Some(passphrase) => Bundle {
+            format: FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            contents: None,
+            encrypted: Some(seal(&contents, passphrase)?),
+        },
+        None => Bundle {
+            format: FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            contents: Some(contents),
+            encrypted: None,
+        },
+    };
+    let data = serde_json::to_string_pretty(&bundle).context("serialize settings bundle")?;
+    fs::write(path, data).with_context(|| format!("write {}", path.display()))?;
+    info!(
+        path = %path.display(),
+        encrypted = passphrase.is_some(),
+        "Settings exported"
+    );
+    Ok(())
+}
+
+/// Reads a settings bundle and returns the config it holds, upgraded to the current
+/// schema. Secrets the bundle leaves out keep `current`'s values.
+pub fn import(path: &Path, passphrase: Option<&str>, current: &Config) -> Result<Config> {
+    let data = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    let bundle: Bundle = serde_json::from_str(&data).context("parse settings bundle")?;
+    if bundle.format != FORMAT {
+        return Err(anyhow!("Not a ThirdSpace settings bundle"));
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(anyhow!(
+            "Settings bundle version {} is newer than this app supports",
+            bundle.version
+        ));
+    }
+
+    let contents = match (bundle.contents, bundle.encrypted) {
+        (_, Some(sealed)) => {
+            let passphrase = passphrase
+                .ok_or_else(|| anyhow!("Settings bundle is encrypted; a passphrase is needed"))?;
+            open(&sealed, passphrase)?
+        }
+        (Some(contents), None) => contents,
+        (None, None) => return Err(anyhow!("Settings bundle is empty")),
+    };
+
+    let mut document = contents.config;
+    let map = document
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("Settings bundle config is not a JSON object"))?;
+    let current = serde_json::to_value(current).context("serialize current config")?;
+    for field in config::SECRET_FIELDS {
+        if !map.contains_key(field) {
+            if let Some(value) = current.get(field) {
+                map.insert(field.to_string(), value.clone());
+            }
+        }
+    }
+    let (config, from) = config::from_document(document)?;
+    info!(
+        path = %path.display(),
+        config_version = from,
+        "Settings imported"
+    );
+    Ok(config)
+}
+
+fn seal(contents: &Contents, passphrase: &str) -> Result<Sealed> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let plaintext = serde_json::to_vec(contents).context("serialize settings")?;
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_| anyhow!("Failed to encrypt settings"))?;
+    Ok(Sealed {
+        kdf: "argon2id".to_string(),
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn open(sealed: &Sealed, passphrase: &str) -> Result<Contents> {
+    if sealed.kdf != "argon2id" {
+        return Err(anyhow!("Unsupported key derivation: {}", sealed.kdf));
+    }
+    let salt = BASE64.decode(&sealed.salt).context("decode salt")?;
+    let nonce = BASE64.decode(&sealed.nonce).context("decode nonce")?;
+    let ciphertext = BASE64
+        .decode(&sealed.ciphertext)
+        .context("decode ciphertext")?;
+    if nonce.len() != 12 {
+        return Err(anyhow!("Settings bundle nonce is damaged"));
+    }
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| anyhow!("Wrong passphrase or damaged settings bundle"))?;
+    serde_json::from_slice(&plaintext).context("parse decrypted settings")
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
@@ -0,0 +1,40 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Results of the most recent requests, newest first, so that repeating one (pressing
+/// the hotkey twice on the same text) is answered without another API call.
+pub struct ResultCache<K, V> {
+    entries: Mutex<VecDeque<(K, V)>>,
+}
+
+impl<K, V> Default for ResultCache<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+impl<K: PartialEq, V: Clone> ResultCache<K, V> {
+    /// The stored result for `key`, moved to the front so it is kept the longest.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap();
+        let idx = entries.iter().position(|(existing, _)| existing == key)?;
+        let entry = entries.remove(idx)?;
+        let value = entry.1.clone();
+        entries.push_front(entry);
+        Some(value)
+    }
+
+    /// Stores a result, dropping the oldest beyond `capacity`; 0 stores nothing.
+    pub fn insert(&self, key: K, value: V, capacity: usize) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|(existing, _)| *existing != key);
+        entries.push_front((key, value));
+        entries.truncate(capacity);
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
@@ -0,0 +1,199 @@
+use crate::clipboard::Input;
+use crate::config::Config;
+use crate::error::CommandError;
+use crate::i18n::{self, Msg};
+use crate::toast::show_toast;
+use crate::{openrouter, Action, AppState, Delivery};
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::time::Instant;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use tracing::{debug, error, info, warn};
+use xcap::image::{imageops, ImageFormat, RgbaImage};
+use xcap::Monitor;
+
+const WINDOW: &str = "capture";
+/// Selections narrower or shorter than this many pixels are treated as stray clicks.
+const MIN_REGION: u32 = 8;
+
+/// A drag-selected rectangle, in screenshot pixels.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The frozen screen the overlay shows while the user selects a region.
+#[derive(Debug, Serialize)]
+pub struct Screenshot {
+    /// PNG data URL.
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Screenshots the monitor under the cursor and opens the selection overlay on it.
+pub fn start(app: &AppHandle) {
+    if app.get_webview_window(WINDOW).is_some() {
+        debug!("Region capture already open");
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let cursor = app.cursor_position().unwrap_or_default();
+        let (x, y) = (cursor.x as i32, cursor.y as i32);
+        let started = Instant::now();
+        let captured = tauri::async_runtime::spawn_blocking(move || grab(x, y))
+            .await
+            .map_err(|e| anyhow!("capture task: {}", e))
+            .and_then(|captured| captured);
+        let screenshot = match captured {
+            Ok(screenshot) => screenshot,
+            Err(e) => {
+                error!(error = %e, "Screen capture failed");
+                show_toast(&app, "error", &i18n::t(Msg::CaptureFailed));
+                return;
+            }
+        };
+        info!(
+            width = screenshot.width(),
+            height = screenshot.height(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Screen captured"
+        );
+        *app.state::<AppState>().capture.lock().unwrap() = Some(screenshot);
+        open_overlay(&app, x, y);
+    });
+}
+
+fn grab(x: i32, y: i32) -> Result<RgbaImage> {
+    let monitor = Monitor::from_point(x, y).context("find monitor under cursor")?;
+    monitor.capture_image().context("capture monitor")
+}
+
+/// Covers the monitor under the cursor with the selection overlay. The window stays
+/// hidden until the page has drawn the screenshot.
+fn open_overlay(app: &AppHandle, x: i32, y: i32) {
+    let mut builder =
+        WebviewWindowBuilder::new(app, WINDOW, WebviewUrl::App("capture.html".into()))
+            .title("ThirdSpace Capture")
+            .decorations(false)
+            .resizable(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .visible(false);
+    if let Ok(Some(monitor)) = app.monitor_from_point(x as f64, y as f64) {
+        let scale = monitor.scale_factor();
+        let position = monitor.position().to_logical::<f64>(scale);
+        let size = monitor.size().to_logical::<f64>(scale);
+        builder = builder
+            .position(position.x, position.y)
+            .inner_size(size.width, size.height);
+    } else {
+        builder = builder.fullscreen(true);
+    }
+
+    if let Err(e) = builder.build() {
+        error!(error = %e, "Capture window failed");
+        app.state::<AppState>().capture.lock().unwrap().take();
+        show_toast(app, "error", &i18n::t(Msg::CaptureFailed));
+    }
+}
+
+fn close_overlay(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(WINDOW) {
+        let _ = window.close();
+    }
+}
+
+/// The screenshot waiting for a selection, for the overlay to draw.
+pub fn screenshot(app: &AppHandle) -> Result<Screenshot> {
+    let state = app.state::<AppState>();
+    let capture = state.capture.lock().unwrap();
+    let image = capture.as_ref().context("no capture in progress")?;
+    Ok(Screenshot {
+        image: format!(
+            "data:image/png;base64,{}",
+            BASE64.encode(encode_png(image)?)
+        ),
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+/// Drops the screenshot and closes the overlay without translating.
+pub fn cancel(app: &AppHandle) {
+    app.state::<AppState>().capture.lock().unwrap().take();
+    close_overlay(app);
+    debug!("Region capture cancelled");
+}
+
+/// Reads the text in the selected region with the OCR model and translates it like a
+/// hotkey press, with the result on the clipboard.
+pub async fn translate_region(app: &AppHandle, region: Region) -> Result<(), CommandError> {
+    close_overlay(app);
+    let state = app.state::<AppState>();
+    let Some(screenshot) = state.capture.lock().unwrap().take() else {
+        return Err(i18n::t(Msg::NoCaptureInProgress).into());
+    };
+
+    let x = region.x.min(screenshot.width());
+    let y = region.y.min(screenshot.height());
+    let width = region.width.min(screenshot.width() - x);
+    let height = region.height.min(screenshot.height() - y);
+    if width < MIN_REGION || height < MIN_REGION {
+        debug!(width, height, "Capture selection too small");
+        show_toast(app, "error", &i18n::t(Msg::SelectionTooSmall));
+        return Err(i18n::t(Msg::SelectionIsTooSmall).into());
+    }
+    let cropped = imageops::crop_imm(&screenshot, x, y, width, height).to_image();
+    let png = encode_png(&cropped).map_err(|e| e.to_string())?;
+
+    let mut config = Config::clone(&state.config());
+    if !config.ocr_model.trim().is_empty() {
+        config.model = config.ocr_model.trim().to_string();
+    }
+    show_toast(app, "processing", "");
+    let started = Instant::now();
+    let text = match openrouter::recognize_text(&state.http(), &config, &png).await {
+        Ok(text) => text,
+        Err(e) => {
+            warn!(error = %e, model = %config.model, "Text recognition failed");
+            show_toast(app, "error", &i18n::t(Msg::OcrFailed));
+            return Err(e.into());
+        }
+    };
+    info!(
+        width,
+        height,
+        text_len = text.len(),
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "Region text recognized"
+    );
+
+    crate::admit_input(app, &state, Action::Translate, false, &text)?;
+    crate::execute_action(
+        app.clone(),
+        state,
+        Action::Translate,
+        false,
+        Input::from(text),
+        None,
+        Delivery::Output,
+    )
+    .await
+}
+
+fn encode_png(image: &RgbaImage) -> Result<Vec<u8>> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .context("encode PNG")?;
+    Ok(png)
+}
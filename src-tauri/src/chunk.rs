@@ -0,0 +1,180 @@
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// Opens and closes fenced code blocks, which `mask` protects as a whole.
+const FENCE: &str = "```";
+
+/// `%%` separator lines, or blank lines between paragraphs.
+static PARAGRAPH_BREAK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\n\s*%%[ \t]*(?:\r?\n\s*|$)|\r?\n[ \t]*\r?\n\s*").expect("valid paragraph regex")
+});
+
+/// A slice of the input plus the separator that followed it in the original text.
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    pub text: String,
+    pub separator: String,
+}
+
+pub fn needs_split(input: &str, max_chars: usize) -> bool {
+    max_chars > 0 && input.chars().count() > max_chars
+}
+
+/// Splits `input` into chunks of at most `max_chars` characters, breaking on paragraph
+/// boundaries first, then lines, then words. Paragraphs are never reordered. A fenced code
+/// block is never broken, even when it alone is longer than `max_chars`, so each chunk
+/// still masks it whole.
+pub fn split(input: &str, max_chars: usize) -> Vec<Chunk> {
+    let max_chars = max_chars.max(1);
+    let fences = fences(input);
+    let mut units = Vec::new();
+    for (paragraph, separator) in split_keeping(input, &PARAGRAPH_BREAK, &fences) {
+        if paragraph.contains(FENCE) {
+            units.push((paragraph, separator));
+        } else {
+            push_units(&mut units, paragraph, separator, max_chars);
+        }
+    }
+
+    let mut chunks = Vec::new();
+    let mut text = String::new();
+    let mut text_chars = 0;
+    let mut pending = String::new();
+    for (unit, separator) in units {
+        let unit_chars = unit.chars().count();
+        if !text.is_empty() {
+            let joined = text_chars + pending.chars().count() + unit_chars;
+            if joined > max_chars {
+                chunks.push(Chunk {
+                    text: std::mem::take(&mut text),
+                    separator: std::mem::take(&mut pending),
+                });
+                text_chars = 0;
+            } else {
+                text_chars += pending.chars().count();
+                text.push_str(&std::mem::take(&mut pending));
+            }
+        }
+        text.push_str(unit);
+        text_chars += unit_chars;
+        pending = separator.to_string();
+    }
+    if !text.is_empty() || !pending.is_empty() {
+        chunks.push(Chunk {
+            text,
+            separator: pending,
+        });
+    }
+    chunks
+}
+
+/// Reassembles translated chunks with the original separators between them.
+pub fn join(chunks: &[Chunk], translated: &[String]) -> String {
+    let mut output = String::new();
+    for (chunk, text) in chunks.iter().zip(translated) {
+        output.push_str(text);
+        output.push_str(&chunk.separator);
+    }
+    output.trim_end().to_string()
+}
+
+/// Non-empty paragraphs of `text`, split on `%%` lines and blank lines.
+pub fn paragraphs(text: &str) -> Vec<&str> {
+    split_keeping(text, &PARAGRAPH_BREAK, &fences(text))
+        .into_iter()
+        .map(|(paragraph, _)| paragraph.trim())
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect()
+}
+
+/// Breaks an oversized paragraph into lines, then words, then raw characters.
+fn push_units<'a>(
+    units: &mut Vec<(&'a str, &'a str)>,
+    text: &'a str,
+    separator: &'a str,
+    max_chars: usize,
+) {
+    if text.chars().count() <= max_chars {
+        units.push((text, separator));
+        return;
+    }
+
+    let pieces: Vec<(&str, &str)> = if text.contains('\n') {
+        split_on(text, "\n")
+    } else if text.contains(' ') {
+        split_on(text, " ")
+    } else {
+        let mut pieces = Vec::new();
+        let mut rest = text;
+        while !rest.is_empty() {
+            let end = rest
+                .char_indices()
+                .nth(max_chars)
+                .map_or(rest.len(), |(idx, _)| idx);
+            pieces.push((&rest[..end], ""));
+            rest = &rest[end..];
+        }
+        pieces
+    };
+
+    let last = pieces.len() - 1;
+    for (idx, (piece, piece_separator)) in pieces.into_iter().enumerate() {
+        let piece_separator = if idx == last {
+            separator
+        } else {
+            piece_separator
+        };
+        push_units(units, piece, piece_separator, max_chars);
+    }
+}
+
+fn split_on<'a>(text: &'a str, separator: &'a str) -> Vec<(&'a str, &'a str)> {
+    let mut pieces: Vec<(&str, &str)> = text
+        .split(separator)
+        .map(|piece| (piece, separator))
+        .collect();
+    if let Some(last) = pieces.last_mut() {
+        last.1 = "";
+    }
+    pieces
+}
+
+/// Byte ranges of fenced code blocks; an unclosed fence runs to the end of `input`.
+fn fences(input: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut open = None;
+    let mut offset = 0;
+    for line in input.split_inclusive('\n') {
+        if line.trim_start().starts_with(FENCE) {
+            match open.take() {
+                Some(start) => ranges.push(start..offset + line.len()),
+                None => open = Some(offset),
+            }
+        }
+        offset += line.len();
+    }
+    if let Some(start) = open {
+        ranges.push(start..input.len());
+    }
+    ranges
+}
+
+/// Splits `input` on `pattern`, except where a match starts inside one of `fences`.
+fn split_keeping<'a>(
+    input: &'a str,
+    pattern: &Regex,
+    fences: &[Range<usize>],
+) -> Vec<(&'a str, &'a str)> {
+    let mut parts = Vec::new();
+    let mut last = 0;
+    for found in pattern.find_iter(input) {
+        if fences.iter().any(|fence| fence.contains(&found.start())) {
+            continue;
+        }
+        parts.push((&input[last..found.start()], found.as_str()));
+        last = found.end();
+    }
+    parts.push((&input[last..], ""));
+    parts
+}
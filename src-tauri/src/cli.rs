@@ -0,0 +1,169 @@
+use crate::config;
+use crate::languages;
+use crate::openrouter;
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+
+/// The `commit -v` scissors line, after the comment prefix.
+const SCISSORS: &str = " ------------------------ >8 ------------------------";
+/// Characters git tries, in order, for `core.commentChar = auto`.
+const AUTO_COMMENT_CHARS: &str = "#;@!$%^&|:";
+
+const USAGE: &str = "Usage: thirdspace commit-msg <file>
+       thirdspace --translate <text> [--lang <language>]
+       thirdspace --stdin [--lang <language>]";
+
+/// Dispatches command-line subcommands. Returns `None` for a normal launch.
+pub fn run(args: &[String]) -> Option<i32> {
+    let command: fn(&[String]) -> Result<()> = match args.get(1).map(String::as_str) {
+        // Suitable as a git `commit-msg` hook: `thirdspace commit-msg "$1"`
+        Some("commit-msg") => |args| commit_msg(args.get(2)),
+        // Prints the translation to stdout, for scripts and editor integrations
+        Some("--translate" | "--stdin" | "--lang") => |args| translate(&args[1..]),
+        _ => return None,
+    };
+    attach_console();
+    Some(exit_code(command(args)))
+}
+
+/// Release builds use the Windows GUI subsystem and start without a console, so output
+/// and errors would go nowhere. Attaches to the console of the shell or git that ran us.
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    // Fails harmlessly when there is no parent console or one is already attached
+    unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+fn exit_code(result: Result<()>) -> i32 {
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("thirdspace: {:#}", e);
+            1
+        }
+    }
+}
+
+fn commit_msg(path: Option<&String>) -> Result<()> {
+    let path = Path::new(path.ok_or_else(|| anyhow!(USAGE))?);
+    let message = fs::read_to_string(path).context("read commit message")?;
+    let message = strip_comments(&message, &comment_prefix(&message));
+    if message.trim().is_empty() {
+        return Ok(());
+    }
+
+    let config = config::load()?;
+    openrouter::set_log_content(config.log_content);
+    openrouter::set_api_base(&config.api_base_url);
+    let client = openrouter::http_client(&config)?;
+    let translated = tauri::async_runtime::block_on(openrouter::translate_commit_message(
+        &client, &config, &message,
+    ))?;
+
+    fs::write(path, format!("{}\n", translated)).context("write commit message")?;
+    Ok(())
+}
+
+/// What git keeps of a commit message: the text above the `commit -v` scissors line,
+/// without comment lines. Only that is translated; git would discard the rest anyway.
+fn strip_comments(message: &str, prefix: &str) -> String {
+    let scissors = format!("{}{}", prefix, SCISSORS);
+    message
+        .lines()
+        .take_while(|line| *line != scissors)
+        .filter(|line| !line.starts_with(prefix))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// What git starts comment lines with: `core.commentString` or `core.commentChar`,
+/// else `#`.
+fn comment_prefix(message: &str) -> String {
+    let configured = ["core.commentString", "core.commentChar"]
+        .into_iter()
+        .find_map(git_config);
+    match configured.as_deref() {
+        None => "#".to_string(),
+        Some("auto") => auto_comment_char(message),
+        Some(prefix) => prefix.to_string(),
+    }
+}
+
+/// With `auto`, git picks a character no line of the message starts with and appends
+/// its comment block at the end, so the last non-empty line shows which one it chose.
+fn auto_comment_char(message: &str) -> String {
+    message
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .and_then(|line| {
+            let mut chars = line.chars();
+            let first = chars.next()?;
+            (AUTO_COMMENT_CHARS.contains(first) && matches!(chars.next(), None | Some(' ')))
+                .then(|| first.to_string())
+        })
+        .unwrap_or_else(|| "#".to_string())
+}
+
+/// A git config value as the hook sees it; git runs hooks inside the repository.
+fn git_config(key: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "--get", key])
+        .output()
+        .ok()?;
+    let value = String::from_utf8(output.stdout).ok()?;
+    let value = value.trim_end_matches(['\r', '\n']);
+    (output.status.success() && !value.is_empty()).then(|| value.to_string())
+}
+
+fn translate(args: &[String]) -> Result<()> {
+    let (mut text, mut stdin, mut language) = (None, false, None);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--translate" => text = Some(args.next().ok_or_else(|| anyhow!(USAGE))?.clone()),
+            "--stdin" => stdin = true,
+            "--lang" => language = Some(args.next().ok_or_else(|| anyhow!(USAGE))?.clone()),
+            other => return Err(anyhow!("Unknown argument: {}\n{}", other, USAGE)),
+        }
+    }
+    let text = match (text, stdin) {
+        (Some(text), false) => text,
+        (None, true) => {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .context("read stdin")?;
+            input
+        }
+        _ => return Err(anyhow!(USAGE)),
+    };
+    if text.trim().is_empty() {
+        return Err(anyhow!("Nothing to translate"));
+    }
+
+    let mut config = config::load()?;
+    if let Some(language) = language {
+        config.target_language = languages::normalize(&language);
+    }
+    if config.target_language.trim().is_empty() {
+        return Err(anyhow!("Target language not set; pass --lang"));
+    }
+    openrouter::set_log_content(config.log_content);
+    openrouter::set_api_base(&config.api_base_url);
+    let client = openrouter::http_client(&config)?;
+    let translated =
+        tauri::async_runtime::block_on(openrouter::translate(&client, &config, &text))?;
+    println!("{}", translated);
+    Ok(())
+}
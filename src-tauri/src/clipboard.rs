@@ -0,0 +1,460 @@
+use crate::config::Config;
+use crate::mask::{self, Masked};
+use crate::{rtf, typing, AppState};
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tracing::debug;
+
+static INVISIBLE_BLOCKS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<style\b.*?</style>|<script\b.*?</script>|<!--.*?-->")
+        .expect("valid invisible-block regex")
+});
+static LINE_BREAKS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)<br\s*/?>|</(?:p|div|li|tr|h[1-6]|blockquote|pre)>")
+        .expect("valid line-break regex")
+});
+static TAGS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").expect("valid tag regex"));
+static BLANK_RUNS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\n[ \t]*\n(?:[ \t]*\n)+").expect("valid blank-run regex"));
+
+/// How long `copy_selection` waits for the focused application to update the clipboard.
+const SELECTION_COPY_TIMEOUT: Duration = Duration::from_millis(500);
+const SELECTION_POLL_INTERVAL: Duration = Duration::from_millis(25);
+/// How often `watch` re-reads the config while paused or while no subscriber is on.
+const WATCH_IDLE_POLL: Duration = Duration::from_secs(5);
+
+/// Clipboard contents captured when an action starts.
+#[derive(Debug, Clone, Default)]
+pub struct Input {
+    pub text: String,
+    /// The rich flavor, when the source application provided one.
+    pub formatted: Option<Formatted>,
+}
+
+impl From<String> for Input {
+    fn from(text: String) -> Self {
+        Self {
+            text,
+            formatted: None,
+        }
+    }
+}
+
+/// A rich clipboard flavor whose markup is kept while its text is translated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Formatted {
+    Html(String),
+    Rtf(String),
+}
+
+impl Formatted {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Formatted::Html(_) => "html",
+            Formatted::Rtf(_) => "rtf",
+        }
+    }
+
+    pub fn mask(&self) -> Masked {
+        match self {
+            Formatted::Html(html) => mask::mask_html(html),
+            Formatted::Rtf(rtf) => rtf::mask(rtf),
+        }
+    }
+
+    /// Rebuilds the document around the translated text, together with its plain-text flavor.
+    pub fn restore(&self, masked: &Masked, output: &str) -> (Formatted, String) {
+        match self {
+            Formatted::Html(_) => {
+                let html = masked.restore(output);
+                let text = html_to_text(&html);
+                (Formatted::Html(html), text)
+            }
+            Formatted::Rtf(_) => {
+                let text = masked.strip(output).trim().to_string();
+                (Formatted::Rtf(rtf::restore(masked, output)), text)
+            }
+        }
+    }
+}
+
+/// Reads the clipboard as plain text. Under Wayland this goes through `wl-paste` when it
+/// is installed, since the XWayland clipboard can be stale or empty there.
+pub fn read_text(app: &AppHandle) -> Result<String> {
+    #[cfg(target_os = "linux")]
+    if linux::wayland() {
+        match linux::wl_paste(false) {
+            Some(Ok(text)) => return Ok(text),
+            Some(Err(e)) => debug!(error = %e, "wl-paste failed, using the toolkit clipboard"),
+            None => {}
+        }
+    }
+    Ok(app.clipboard().read_text()?)
+}
+
+/// A feature that `watch` tells about new clipboard text.
+pub struct Subscriber {
+    /// How often the feature needs the clipboard sampled; `None` while it is off.
+    pub poll: fn(&Config) -> Option<Duration>,
+    pub on_change: fn(&AppHandle, &Change<'_>),
+}
+
+/// New non-blank text that the app did not write itself.
+pub struct Change<'a> {
+    pub text: &'a str,
+    /// Set for the first sample after the watcher starts, resumes or gains a subscriber,
+    /// whose text may have been on the clipboard all along.
+    pub first: bool,
+}
+
+/// Samples the clipboard in the background, as often as the most demanding active
+/// subscriber asks, and passes each change to every active subscriber. Idle while
+/// paused.
+pub fn watch(app: AppHandle, subscribers: &'static [Subscriber]) {
+    tauri::async_runtime::spawn(async move {
+        // What the clipboard held at the last sample; `None` until the first one
+        let mut last: Option<String> = None;
+        let mut active = vec![false; subscribers.len()];
+        loop {
+            let state = app.state::<AppState>();
+            let config = state.config();
+            let polls: Vec<_> = subscribers
+                .iter()
+                .map(|subscriber| (subscriber.poll)(&config))
+                .collect();
+            let now_active: Vec<_> = polls.iter().map(Option::is_some).collect();
+            if now_active != active {
+                active = now_active;
+                last = None;
+            }
+            let poll = polls.into_iter().flatten().min();
+            let Some(poll) = poll.filter(|_| !*state.paused.lock().unwrap()) else {
+                last = None;
+                tokio::time::sleep(WATCH_IDLE_POLL).await;
+                continue;
+            };
+
+            // Non-text content (images, files) leaves the last sample as it was
+            let text = match read_text(&app) {
+                Ok(text) => text,
+                Err(e) => {
+                    debug!(error = %e, "Clipboard sample skipped");
+                    tokio::time::sleep(poll).await;
+                    continue;
+                }
+            };
+            if last.as_deref() != Some(text.as_str()) {
+                let change = Change {
+                    text: &text,
+                    first: last.is_none(),
+                };
+                if !text.trim().is_empty() && !state.clipboard_history.is_own_write(&text) {
+                    for (subscriber, _) in subscribers.iter().zip(&active).filter(|(_, on)| **on) {
+                        (subscriber.on_change)(&app, &change);
+                    }
+                }
+                last = Some(text);
+            }
+            tokio::time::sleep(poll).await;
+        }
+    });
+}
+
+/// Reads the text an action works on: the PRIMARY selection (the last highlighted text)
+/// when `primary_selection` is set, else the clipboard. On Linux an empty or unreadable
+/// clipboard falls back to PRIMARY. Other platforms have no PRIMARY selection.
+pub fn read_for_action(app: &AppHandle, primary_selection: bool) -> Result<String> {
+    #[cfg(target_os = "linux")]
+    {
+        if primary_selection {
+            return linux::read_primary();
+        }
+        match read_text(app) {
+            Ok(text) if !text.trim().is_empty() => Ok(text),
+            clipboard => match linux::read_primary() {
+                Ok(text) if !text.trim().is_empty() => {
+                    debug!("Clipboard empty, using the PRIMARY selection");
+                    Ok(text)
+                }
+                _ => clipboard,
+            },
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = primary_selection;
+        read_text(app)
+    }
+}
+
+/// Copies the selection of the focused application and returns it. When the clipboard
+/// has not changed after `SELECTION_COPY_TIMEOUT`, the selection is taken to be what was
+/// already there (or nothing is selected, which the caller sees as empty input only if
+/// the clipboard was empty too).
+pub async fn copy_selection(app: &AppHandle) -> Result<String> {
+    let before = read_text(app).unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(typing::press_copy)
+        .await
+        .map_err(|e| anyhow!("copy task failed: {}", e))??;
+    let started = Instant::now();
+    loop {
+        tokio::time::sleep(SELECTION_POLL_INTERVAL).await;
+        let text = read_text(app)?;
+        let changed = text != before;
+        if changed || started.elapsed() >= SELECTION_COPY_TIMEOUT {
+            debug!(
+                changed,
+                waited_ms = started.elapsed().as_millis() as u64,
+                "Selection copied"
+            );
+            return Ok(text);
+        }
+    }
+}
+
+/// Puts plain text on the clipboard, through `wl-copy` under Wayland when it is installed.
+pub fn write_text(app: &AppHandle, text: &str) -> Result<()> {
+    #[cfg(target_os = "linux")]
+    if linux::wayland() {
+        match linux::wl_copy(text) {
+            Some(Ok(())) => return Ok(()),
+            Some(Err(e)) => debug!(error = %e, "wl-copy failed, using the toolkit clipboard"),
+            None => {}
+        }
+    }
+    Ok(app.clipboard().write_text(text)?)
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use anyhow::{anyhow, Context, Result};
+    use std::io::{ErrorKind, Write};
+    use std::process::{Command, Stdio};
+
+    pub fn wayland() -> bool {
+        std::env::var_os("WAYLAND_DISPLAY").is_some()
+    }
+
+    /// The PRIMARY selection, via `wl-paste --primary` under Wayland, else X11.
+    pub fn read_primary() -> Result<String> {
+        if wayland() {
+            if let Some(text) = wl_paste(true) {
+                return text;
+            }
+        }
+        use arboard::{GetExtLinux, LinuxClipboardKind};
+        arboard::Clipboard::new()?
+            .get()
+            .clipboard(LinuxClipboardKind::Primary)
+            .text()
+            .context("read PRIMARY selection")
+    }
+
+    /// Runs `wl-paste` from wl-clipboard; `None` when it is not installed.
+    pub fn wl_paste(primary: bool) -> Option<Result<String>> {
+        let mut command = Command::new("wl-paste");
+        command.args(["--no-newline", "--type", "text"]);
+        if primary {
+            command.arg("--primary");
+        }
+        let output = match command.stdin(Stdio::null()).output() {
+            Ok(output) => output,
+            Err(e) if e.kind() == ErrorKind::NotFound => return None,
+            Err(e) => return Some(Err(anyhow!("run wl-paste: {}", e))),
+        };
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Some(Err(anyhow!("wl-paste: {}", stderr.trim())));
+        }
+        Some(String::from_utf8(output.stdout).context("wl-paste returned invalid UTF-8"))
+    }
+
+    /// Hands `text` to `wl-copy`, which keeps serving it in the background; `None` when
+    /// it is not installed.
+    pub fn wl_copy(text: &str) -> Option<Result<()>> {
+        let mut child = match Command::new("wl-copy")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) if e.kind() == ErrorKind::NotFound => return None,
+            Err(e) => return Some(Err(anyhow!("run wl-copy: {}", e))),
+        };
+        let written = child
+            .stdin
+            .take()
+            .context("wl-copy stdin")
+            .and_then(|mut stdin| stdin.write_all(text.as_bytes()).context("write to wl-copy"));
+        let status = child.wait().context("wait for wl-copy");
+        Some(written.and(status).and_then(|status| {
+            if status.success() {
+                Ok(())
+            } else {
+                Err(anyhow!("wl-copy exited with {}", status))
+            }
+        }))
+    }
+}
+
+/// Captures what the clipboard holds right now, with its rich flavor, so a later write
+/// can be undone. Images and other flavors are not kept.
+pub fn snapshot(app: &AppHandle) -> Input {
+    let text = read_text(app)
+        .map_err(|e| debug!(error = %e, "Clipboard unreadable for snapshot"))
+        .unwrap_or_default();
+    Input {
+        text,
+        formatted: read_formatted(),
+    }
+}
+
+/// Reads the richest clipboard flavor available: HTML first, then RTF.
+pub fn read_formatted() -> Option<Formatted> {
+    read_html()
+        .map(Formatted::Html)
+        .or_else(|| read_rtf().map(Formatted::Rtf))
+}
+
+/// Writes the formatted flavor together with its plain-text fallback.
+pub fn write_formatted(formatted: &Formatted, text: &str) -> Result<()> {
+    match formatted {
+        Formatted::Html(html) => {
+            arboard::Clipboard::new()?
+                .set()
+                .html(html.as_str(), Some(text))?;
+            Ok(())
+        }
+        Formatted::Rtf(rtf) => write_rtf(rtf, text),
+    }
+}
+
+/// Reads the HTML flavor of the clipboard; `None` when only plain text is available.
+fn read_html() -> Option<String> {
+    let html = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get().html())
+        .map_err(|e| debug!(error = %e, "No HTML on clipboard"))
+        .ok()?;
+    if html.trim().is_empty() {
+        None
+    } else {
+        Some(html)
+    }
+}
+
+#[cfg(windows)]
+const RTF_FORMAT: &str = "Rich Text Format";
+
+/// Reads the RTF flavor. Only Windows exposes it here; other platforms return `None`.
+#[cfg(windows)]
+fn read_rtf() -> Option<String> {
+    let format = clipboard_win::register_format(RTF_FORMAT)?.get();
+    let _clipboard = clipboard_win::Clipboard::new_attempts(10).ok()?;
+    if !clipboard_win::is_format_avail(format) {
+        return None;
+    }
+    let mut data = Vec::new();
+    clipboard_win::raw::get_vec(format, &mut data)
+        .map_err(|e| debug!(error = %e, "RTF clipboard read failed"))
+        .ok()?;
+    // RTF is 7-bit; a trailing NUL terminator is common
+    let rtf = String::from_utf8_lossy(&data)
+        .trim_end_matches('\0')
+        .to_string();
+    if rtf.starts_with("{\\rtf") {
+        Some(rtf)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(windows))]
+fn read_rtf() -> Option<String> {
+    None
+}
+
+#[cfg(windows)]
+fn write_rtf(rtf: &str, text: &str) -> Result<()> {
+    use anyhow::anyhow;
+
+    let format = clipboard_win::register_format(RTF_FORMAT)
+        .ok_or_else(|| anyhow!("register RTF clipboard format"))?
+        .get();
+    let _clipboard =
+        clipboard_win::Clipboard::new_attempts(10).map_err(|e| anyhow!("open clipboard: {}", e))?;
+    // set_string empties the clipboard first, so the RTF flavor goes second
+    clipboard_win::raw::set_string(text).map_err(|e| anyhow!("write text flavor: {}", e))?;
+    clipboard_win::raw::set_without_clear(format, rtf.as_bytes())
+        .map_err(|e| anyhow!("write RTF flavor: {}", e))?;
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn write_rtf(_rtf: &str, _text: &str) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "RTF clipboard is only supported on Windows"
+    ))
+}
+
+/// Derives the plain-text flavor written alongside translated HTML.
+fn html_to_text(html: &str) -> String {
+    let text = INVISIBLE_BLOCKS.replace_all(html, "");
+    let text = text.replace(['\r', '\n'], " ");
+    let text = LINE_BREAKS.replace_all(&text, "$0\n");
+    let text = TAGS.replace_all(&text, "");
+    let text = decode_entities(&text);
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    BLANK_RUNS
+        .replace_all(&lines.join("\n"), "\n\n")
+        .trim()
+        .to_string()
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end]).map(|ch| (ch, end)));
+        match entity {
+            Some((ch, end)) => {
+                decoded.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let code = name.strip_prefix('#')?;
+            let value = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(value)
+        }
+    }
+}
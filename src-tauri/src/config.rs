@@ -1,29 +1,585 @@
+use crate::policy;
+use crate::prompt::Tone;
+use crate::secret;
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+/// Schema version written by this build; `load` upgrades older files step by step.
+pub const CONFIG_VERSION: u32 = 3;
+
+/// Prefix of environment variables that override config.json fields at load time, as in
+/// `THIRDSPACE_API_KEY` or `THIRDSPACE_TARGET_LANGUAGE`.
+const ENV_PREFIX: &str = "THIRDSPACE_";
+
+/// Fields the last `load` took from the environment or the policy, each with the value
+/// config.json had for it (`None` when absent); `save` writes that value back instead.
+static OVERRIDES: Mutex<Vec<(String, Option<Value>)>> = Mutex::new(Vec::new());
+
+/// Fields that hold credentials, left out of backups and exports; see `redact_secrets`.
+pub const SECRET_FIELDS: [&str; 2] = ["api_key", "bridge_token"];
+
+/// Translate hotkey for new configs, on the modifiers each platform's own shortcuts use.
+#[cfg(target_os = "macos")]
+const DEFAULT_HOTKEY: &str = "Cmd+Option+T";
+#[cfg(not(target_os = "macos"))]
+const DEFAULT_HOTKEY: &str = "Ctrl+Alt+T";
+
+/// `MIGRATIONS[n]` upgrades a version `n` document to version `n + 1`.
+const MIGRATIONS: [fn(&mut Map<String, Value>); CONFIG_VERSION as usize] =
+    [migrate_v0_to_v1, migrate_v1_to_v2, migrate_v2_to_v3];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Config {
+    /// Schema version; see `CONFIG_VERSION`.
+    pub version: u32,
     pub api_key: String,
+    /// Write `api_key` to config.json encrypted for this user and machine (`secret::seal`)
+    /// instead of as plaintext.
+    pub encrypt_api_key: bool,
     pub model: String,
+    /// Models preferred by fast mode; the one with the lowest observed latency wins.
+    pub favorite_models: Vec<String>,
+    /// Models and thresholds used when `model` is `"auto"`.
+    pub auto_model: AutoModel,
     pub target_language: String,
-    pub reasoning_enabled: bool,
+    /// Target languages in tray and cycle order.
+    pub languages: Vec<String>,
+    /// Two or more languages here make translate produce all of them at once, labeled,
+    /// instead of `target_language`.
+    pub multi_target_languages: Vec<String>,
+    /// Translate requests this many alternatives (2 to 5) and offers them in a chooser
+    /// popup; 0 or 1 turns it off.
+    pub translation_candidates: usize,
+    /// Recent translations (same target language, last 15 minutes) sent along as context so
+    /// a conversation translated piece by piece stays consistent; 0 turns it off.
+    pub conversation_context: usize,
+    /// Optional hotkey that copies the selection in the focused application, then
+    /// translates it; `hotkey` translates what is already on the clipboard.
+    pub translate_selection_hotkey: String,
+    /// Optional hotkey that switches the target to the next entry of `languages`.
+    pub cycle_language_hotkey: String,
+    /// Optional hotkey that puts back the clipboard contents the last translation replaced.
+    pub undo_hotkey: String,
+    /// Optional hotkey that opens the quick translate popup.
+    pub quick_translate_hotkey: String,
+    /// Optional hotkey that starts a screen region capture for OCR translation.
+    pub capture_hotkey: String,
+    /// Vision model that reads text in captured regions; empty uses `model`.
+    pub ocr_model: String,
+    /// Optional push-to-talk hotkey: hold to record, release to transcribe and translate.
+    pub dictation_hotkey: String,
+    /// Audio model that transcribes dictation; empty uses `model`.
+    pub transcription_model: String,
+    /// Longest dictation recorded per key press, in seconds; 0 = no limit.
+    pub dictation_max_secs: u64,
+    /// Optional hotkey that pauses or resumes all of ThirdSpace.
+    pub toggle_pause_hotkey: String,
+    /// What a left click on the tray icon does.
+    pub tray_click: TrayClick,
+    /// Show a small translate button next to the cursor whenever another application
+    /// copies text.
+    pub copy_button: bool,
+    /// How long the copy button stays up, in milliseconds.
+    pub copy_button_ms: u64,
+    pub reasoning: ReasoningEffort,
+    /// Sampling temperature; `None` leaves the model default. 0 gives the most repeatable output.
+    pub temperature: Option<f32>,
+    /// Nucleus sampling cutoff; `None` leaves the model default.
+    pub top_p: Option<f32>,
+    /// Cap on generated tokens; `None` leaves the model default.
+    pub max_tokens: Option<u32>,
+    /// Ask for translations as a JSON object (`response_format: json_schema`) instead of
+    /// marker-wrapped text, on models the models list says support it.
+    pub structured_output: bool,
+    /// Language of toasts, the tray menu and error messages (`en`, `zh`, `ja`, `de`);
+    /// empty follows the system locale.
+    pub ui_language: String,
+    /// Include input, translation and response body previews in the logs. When off, they
+    /// are logged as a length and hash only.
+    pub log_content: bool,
+    /// Days to keep log files; 0 keeps them until `log_max_total_mb` removes them.
+    pub log_retention_days: u64,
+    /// A log file reaching this many MB is set aside and a new one started; 0 for no limit.
+    pub log_max_file_mb: u64,
+    /// Oldest log files are deleted once the log directory exceeds this many MB; 0 for no
+    /// limit.
+    pub log_max_total_mb: u64,
+    /// Record every OpenRouter request and its response in full, one JSON object per line,
+    /// in `~/.thirdspace/audit/`. Kept apart from the logs and unaffected by `log_content`.
+    pub audit_log: bool,
+    /// Days to keep audit files; 0 keeps them forever.
+    pub audit_retention_days: u64,
+    /// Seconds to wait for a connection to OpenRouter; 0 waits indefinitely.
+    pub connect_timeout_secs: u64,
+    /// Seconds a whole request may take, response included; 0 waits indefinitely.
+    pub request_timeout_secs: u64,
+    /// PEM file of extra root certificates to trust, such as the CA of a TLS-inspecting
+    /// proxy; empty adds none.
+    pub ca_bundle: String,
+    /// Also trust the operating system's certificate store, where corporate proxy CAs are
+    /// usually installed.
+    pub use_system_certificates: bool,
+    /// Connect to OpenRouter directly, ignoring the system and environment proxy settings.
+    pub bypass_proxy: bool,
+    /// Proxy for every OpenRouter request, such as `http://proxy:8080`; empty uses the
+    /// system and environment settings. Ignored when `bypass_proxy` is on.
+    pub proxy: String,
+    /// OpenRouter-compatible API that requests go to, such as a company gateway.
+    pub api_base_url: String,
+    /// Requests that may run at once; another hotkey press beyond this gets "Busy".
+    pub max_concurrent_requests: usize,
+    /// Serve translations over a WebSocket on 127.0.0.1 for the browser extension.
+    pub bridge_enabled: bool,
+    pub bridge_port: u16,
+    /// Bridge clients must connect with `?token=<bridge_token>`; `bridge::ensure_token`
+    /// creates one when the bridge is enabled without it.
+    pub bridge_token: String,
+    /// Offer to translate `thirdspace://translate` links opened by other apps or web pages.
+    /// Each link is confirmed first and its result shown in the result window.
+    pub deep_links: bool,
+    /// Keep clipboard snapshots whose request could not connect and send them once
+    /// OpenRouter is reachable again.
+    pub offline_queue: bool,
+    /// Seconds between reachability probes while snapshots are queued.
+    pub offline_probe_secs: u64,
+    /// Also ask for pinyin/romaji/transliteration and show it in the result window.
+    pub romanization: bool,
+    /// Look up single words and short phrases as dictionary entries.
+    pub dictionary_lookup: bool,
+    /// Inputs with at most this many words count as short phrases.
+    pub dictionary_max_words: usize,
+    /// Inputs longer than this many characters are translated in chunks; 0 disables chunking.
+    pub chunk_max_chars: usize,
+    /// How many chunks may be in flight at once.
+    pub chunk_concurrency: usize,
+    /// Clipboard text longer than this needs confirmation before it is sent; 0 disables the guard.
+    pub max_input_chars: usize,
+    /// Translate the HTML clipboard flavor when present and write formatted text back.
+    /// Off by default: when the model loses markup, the plain text is sent again.
+    pub preserve_formatting: bool,
+    /// Linux: actions read the PRIMARY selection (highlighted text) instead of the clipboard.
+    pub primary_selection: bool,
+    /// Clipboard snapshots kept for the "Translate Recent" tray menu; 0 (the default)
+    /// disables the history and its clipboard polling.
+    pub clipboard_history_size: usize,
+    /// Finished translations kept in `translations.jsonl` for `search_history`; 0 (the
+    /// default) disables the journal, which stores source and translation in plain text.
+    pub translation_history_size: usize,
+    /// Recent results kept in memory; repeating one of them exactly (same action, text,
+    /// language and model) is answered from here without a request. 0 turns it off.
+    pub translation_cache_size: usize,
+    /// Show the estimated cost of each request in the processing toast.
+    pub show_cost_estimate: bool,
+    /// Requests estimated to cost more than this (USD) need the hotkey pressed again;
+    /// 0 disables the check.
+    pub confirm_cost_above: f64,
+    /// Spend (USD) allowed per calendar month across all requests; 0 means no limit.
+    pub monthly_budget: f64,
+    /// Tokens allowed per calendar month across all requests; 0 means no limit.
+    pub monthly_token_limit: u64,
+    /// What happens to requests once a monthly limit is reached.
+    pub budget_action: BudgetAction,
+    /// Warn with a toast once remaining OpenRouter credits (USD) drop below this; 0 disables.
+    pub low_credits_warning: f64,
+    /// Routes every request through fast mode (fastest favorite, no reasoning).
+    pub fast_mode: bool,
     pub hotkey: String,
+    /// Extra modifier that, held with the hotkey, runs a single fast-mode request.
+    pub fast_mode_modifier: String,
+    /// Modifier (Ctrl, Shift, Alt or Win) that translates when tapped twice on its own,
+    /// as an alternative to `hotkey`; empty turns it off.
+    pub double_tap_modifier: String,
+    /// Optional hotkey for the summarize action; empty means tray only.
+    pub summarize_hotkey: String,
+    /// Optional hotkey for the proofread action; empty means tray only.
+    pub proofread_hotkey: String,
+    /// Optional hotkey that rewrites the clipboard in `rewrite_tone`.
+    pub rewrite_hotkey: String,
+    pub rewrite_tone: Tone,
+    /// User-defined prompt actions, available from the tray and their own hotkeys.
+    pub actions: Vec<CustomAction>,
     pub autostart: bool,
+    pub notification_mode: NotificationMode,
+    /// Register for translations; `None` mirrors the source text.
+    pub translation_tone: Option<Tone>,
+    /// Overrides for specific foreground applications; the first match applies.
+    pub app_rules: Vec<AppRule>,
+    /// Where clipboard actions deliver their result.
+    pub output_mode: OutputMode,
+    /// Keep the original next to translations instead of replacing it.
+    pub output_layout: OutputLayout,
+    pub post_format: PostFormat,
+    pub post_hooks: PostHooks,
+    /// Replace emails, phone numbers and card numbers with tokens before anything is sent
+    /// to OpenRouter, and put them back in the response.
+    pub redact_personal_data: bool,
+    /// Show each translation in an editable review window; the clipboard only gets it on
+    /// Accept.
+    pub review_translations: bool,
+    pub update_channel: UpdateChannel,
+    /// Ask GitHub once a week whether a newer release is out and say so in a toast; works
+    /// in builds without the updater signing key too.
+    pub weekly_version_check: bool,
+    /// Typing speed for `OutputMode::Type`; 0 types the whole result at once.
+    pub typing_chars_per_sec: u32,
+    pub toast_duration_ms: u64,
+    /// Per-kind durations ("success", "error", "processing") overriding `toast_duration_ms`.
+    pub toast_duration_overrides: HashMap<String, u64>,
+    /// Suppresses success and processing toasts; errors are still shown.
+    pub silent_mode: bool,
+    /// Play a short sound when a translation finishes, and a different one when it fails.
+    pub completion_sound: bool,
+    pub toast_corner: ToastCorner,
+    /// Distance from the work-area edge, in logical pixels.
+    pub toast_margin: f64,
+    /// Show the toast on the monitor under the cursor instead of the primary one.
+    pub toast_follow_cursor: bool,
+    pub schedule: Schedule,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomAction {
+    pub name: String,
+    /// Prompt template; `{input}` and `{target_language}` are substituted.
+    pub prompt: String,
+    /// Model to use instead of `Config::model`; empty keeps the default.
+    pub model: String,
+    /// Optional global hotkey, in the same format as `Config::hotkey`.
+    pub shortcut: String,
+}
+
+/// Settings applied when a matching application is in the foreground as an action starts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppRule {
+    /// Application name or executable name (without extension), case-insensitive.
+    pub app: String,
+    /// Target language; empty keeps the configured one.
+    pub target_language: String,
+    /// Translation register; `None` keeps `Config::translation_tone`.
+    pub tone: Option<Tone>,
+    /// Model; empty keeps the configured one.
+    pub model: String,
+}
+
+/// How `model: "auto"` picks a model for each request (see `automodel`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoModel {
+    /// Model for short, plain text.
+    pub fast_model: String,
+    /// Model for long or technical text.
+    pub strong_model: String,
+    /// Inputs up to this many characters count as short.
+    pub short_max_chars: usize,
+    /// Inputs at least this percent code, URLs or markup count as technical.
+    pub technical_min_percent: u8,
+}
+
+impl Default for AutoModel {
+    fn default() -> Self {
+        Self {
+            fast_model: "google/gemini-3-flash-preview".to_string(),
+            strong_model: "google/gemini-3-pro-preview".to_string(),
+            short_max_chars: 500,
+            technical_min_percent: 30,
+        }
+    }
+}
+
+/// Clean-up applied to every extracted translation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostFormat {
+    /// Remove headings, bold, inline code and code fences the source did not have.
+    pub strip_markdown: bool,
+    /// Turn curly quotes, dashes and ellipses into their ASCII forms.
+    pub plain_punctuation: bool,
+    /// Join the lines of each paragraph, keeping paragraph breaks.
+    pub collapse_line_breaks: bool,
+    /// Drop "Here is the translation:" lead-ins and trailing translator's notes.
+    pub trim_commentary: bool,
+}
+
+/// User fixups run on each result before it is delivered: the find/replace rules in
+/// order, then `command` with the text on stdin, whose output replaces it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostHooks {
+    pub rules: Vec<ReplaceRule>,
+    /// Program to run; empty runs none. Its arguments go in `args`.
+    pub command: String,
+    pub args: Vec<String>,
+    /// How long `command` may run before the result is delivered without it.
+    pub timeout_secs: u64,
+}
+
+impl Default for PostHooks {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            command: String::new(),
+            args: Vec::new(),
+            timeout_secs: 10,
+        }
+    }
+}
+
+impl PostHooks {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.command.trim().is_empty()
+    }
+}
+
+/// A regular expression and its replacement, which may use `$1` or `${name}` groups.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplaceRule {
+    pub find: String,
+    pub replace: String,
+}
+
+/// Intervals for background maintenance tasks, in minutes. 0 disables a task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Schedule {
+    pub log_cleanup_minutes: u64,
+    pub models_cache_ttl_minutes: u64,
+    pub models_refresh_minutes: u64,
+    pub config_backup_minutes: u64,
+    pub credits_check_minutes: u64,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            log_cleanup_minutes: 24 * 60,
+            models_cache_ttl_minutes: 6 * 60,
+            models_refresh_minutes: 0,
+            config_backup_minutes: 24 * 60,
+            credits_check_minutes: 60,
+        }
+    }
+}
+
+/// How much the model may think before answering. Serialized as `"off"`, `"low"`,
+/// `"medium"`, `"high"` or `{"budget": tokens}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasoningEffort {
+    Off,
+    Low,
+    #[default]
+    Medium,
+    High,
+    /// Maximum reasoning tokens, for models that take a budget rather than an effort.
+    Budget(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationMode {
+    /// The custom transparent webview toast.
+    #[default]
+    Toast,
+    /// The OS notification center.
+    Native,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    /// Replace the clipboard contents with the result.
+    #[default]
+    Clipboard,
+    /// Type the result into the focused field with simulated keystrokes, leaving the
+    /// clipboard untouched.
+    Type,
+}
+
+/// What a request does after `monthly_budget` or `monthly_token_limit` is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetAction {
+    /// Requests are refused until the month ends.
+    #[default]
+    Block,
+    /// Each request is sent only when its action is repeated.
+    Confirm,
+}
+
+/// How a translation and its original are arranged in the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputLayout {
+    /// The translation replaces the original.
+    #[default]
+    Replace,
+    /// The original, a separator line, then the translation.
+    Append,
+    /// The translation above the original quoted with `> `, as in an email reply.
+    Quote,
+}
+
+/// Which releases the updater offers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    /// Pre-releases as well as stable releases, whichever is newer.
+    Beta,
+}
+
+impl UpdateChannel {
+    pub fn name(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+        }
+    }
+}
+
+/// Left-click behavior of the tray icon. Linux does not report tray clicks, so there the
+/// menu always opens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayClick {
+    /// Open the tray menu, like a right click.
+    #[default]
+    Menu,
+    /// Translate the clipboard.
+    Translate,
+    /// Open the quick translate popup.
+    QuickTranslate,
+    /// Open the settings window.
+    Settings,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToastCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
             api_key: String::new(),
+            encrypt_api_key: true,
             model: "google/gemini-3-flash-preview".to_string(),
+            favorite_models: Vec::new(),
+            auto_model: AutoModel::default(),
             target_language: "English".to_string(),
-            reasoning_enabled: true,
-            hotkey: "Ctrl+Alt+T".to_string(),
+            translate_selection_hotkey: String::new(),
+            cycle_language_hotkey: String::new(),
+            undo_hotkey: String::new(),
+            quick_translate_hotkey: String::new(),
+            capture_hotkey: String::new(),
+            ocr_model: String::new(),
+            dictation_hotkey: String::new(),
+            transcription_model: String::new(),
+            dictation_max_secs: 120,
+            toggle_pause_hotkey: String::new(),
+            tray_click: TrayClick::default(),
+            copy_button: false,
+            copy_button_ms: 2500,
+            languages: vec![
+                "English".to_string(),
+                "Chinese".to_string(),
+                "Japanese".to_string(),
+            ],
+            multi_target_languages: Vec::new(),
+            translation_candidates: 0,
+            conversation_context: 0,
+            reasoning: ReasoningEffort::default(),
+            temperature: None,
+            top_p: None,
+            max_tokens: None,
+            structured_output: false,
+            ui_language: String::new(),
+            log_content: false,
+            log_retention_days: 14,
+            log_max_file_mb: 10,
+            log_max_total_mb: 100,
+            audit_log: false,
+            audit_retention_days: 90,
+            connect_timeout_secs: 10,
+            request_timeout_secs: 120,
+            ca_bundle: String::new(),
+            use_system_certificates: true,
+            bypass_proxy: false,
+            proxy: String::new(),
+            api_base_url: crate::openrouter::DEFAULT_API_BASE.to_string(),
+            max_concurrent_requests: 2,
+            bridge_enabled: false,
+            bridge_port: 47631,
+            bridge_token: String::new(),
+            deep_links: false,
+            offline_queue: false,
+            offline_probe_secs: 30,
+            romanization: false,
+            dictionary_lookup: false,
+            dictionary_max_words: 3,
+            chunk_max_chars: 8000,
+            chunk_concurrency: 2,
+            max_input_chars: 20_000,
+            preserve_formatting: false,
+            primary_selection: false,
+            clipboard_history_size: 0,
+            translation_history_size: 0,
+            translation_cache_size: 20,
+            show_cost_estimate: false,
+            confirm_cost_above: 0.0,
+            monthly_budget: 0.0,
+            monthly_token_limit: 0,
+            budget_action: BudgetAction::default(),
+            low_credits_warning: 1.0,
+            fast_mode: false,
+            hotkey: DEFAULT_HOTKEY.to_string(),
+            fast_mode_modifier: "Shift".to_string(),
+            double_tap_modifier: String::new(),
+            summarize_hotkey: String::new(),
+            proofread_hotkey: String::new(),
+            rewrite_hotkey: String::new(),
+            rewrite_tone: Tone::default(),
+            actions: Vec::new(),
             autostart: false,
+            notification_mode: NotificationMode::default(),
+            translation_tone: None,
+            app_rules: Vec::new(),
+            output_mode: OutputMode::default(),
+            output_layout: OutputLayout::default(),
+            post_format: PostFormat::default(),
+            post_hooks: PostHooks::default(),
+            redact_personal_data: false,
+            review_translations: false,
+            update_channel: UpdateChannel::default(),
+            weekly_version_check: false,
+            typing_chars_per_sec: 60,
+            toast_duration_ms: 2200,
+            toast_duration_overrides: HashMap::from([("error".to_string(), 4000)]),
+            silent_mode: false,
+            completion_sound: false,
+            toast_corner: ToastCorner::default(),
+            toast_margin: 16.0,
+            toast_follow_cursor: false,
+            schedule: Schedule::default(),
         }
     }
 }
@@ -37,6 +593,10 @@ pub fn logs_dir() -> Result<PathBuf> {
     Ok(app_dir()?.join("logs"))
 }
 
+pub fn backups_dir() -> Result<PathBuf> {
+    Ok(app_dir()?.join("backups"))
+}
+
 pub fn config_path() -> Result<PathBuf> {
     Ok(app_dir()?.join("config.json"))
 }
@@ -44,23 +604,274 @@ pub fn config_path() -> Result<PathBuf> {
 pub fn load() -> Result<Config> {
     let path = config_path()?;
     if !path.exists() {
-        return Ok(Config::default());
+        let mut document = serde_json::to_value(Config::default()).context("serialize config")?;
+        apply_env_overrides(&mut document);
+        let policy = apply_policy(&mut document);
+        let mut config = serde_json::from_value(document).context("parse config")?;
+        policy.enforce(&mut config);
+        return Ok(config);
     }
     let data = fs::read_to_string(&path).context("read config.json")?;
-    let config: Config = serde_json::from_str(&data).context("parse config.json")?;
+    let mut document: Value = serde_json::from_str(&data).context("parse config.json")?;
+    let from = migrate(&mut document)?;
+    apply_env_overrides(&mut document);
+    let policy = apply_policy(&mut document);
+    let mut config: Config = serde_json::from_value(document).context("parse config")?;
+    policy.enforce(&mut config);
+
+    let sealed = secret::is_sealed(&config.api_key);
+    if sealed {
+        match secret::open(&config.api_key) {
+            Ok(key) => config.api_key = key,
+            Err(e) => {
+                // Copied from another machine or account; the key has to be entered again
+                warn!(error = %e, "API key in config.json cannot be decrypted");
+                config.api_key.clear();
+            }
+        }
+    }
+
+    if from < CONFIG_VERSION {
+        // Keep the pre-migration file around in case an upgrade step loses something,
+        // without its secrets and outside the rotation of `backup`
+        let dir = backups_dir()?;
+        fs::create_dir_all(&dir).context("create backups directory")?;
+        let original = dir.join(format!("pre-migration-v{}.json", from));
+        let mut document: Value = serde_json::from_str(&data).context("parse config.json")?;
+        redact_secrets(&mut document);
+        let data = serde_json::to_string_pretty(&document).context("serialize config backup")?;
+        fs::write(&original, data).context("keep pre-migration config")?;
+        save(&config)?;
+        info!(
+            from = from,
+            to = CONFIG_VERSION,
+            original = %original.display(),
+            "Config migrated"
+        );
+    } else if !config.api_key.is_empty()
+        && sealed != config.encrypt_api_key
+        && !is_overridden("api_key")
+    {
+        save(&config)?;
+        info!(
+            encrypted = config.encrypt_api_key,
+            "API key rewritten in config.json"
+        );
+    }
     Ok(config)
 }
 
+/// Replaces fields of `document` with matching `THIRDSPACE_<FIELD>` environment variables
+/// and remembers what the file had, so the overrides are never saved. Values are parsed as
+/// JSON, except that string fields take the text as is unless it is a JSON object. An
+/// override the field cannot take is ignored with a warning.
+fn apply_env_overrides(document: &mut Value) {
+    let mut overrides = Vec::new();
+    let defaults = serde_json::to_value(Config::default()).unwrap_or_default();
+    if let Some(map) = document.as_object_mut() {
+        for (name, raw) in std::env::vars_os() {
+            let (Some(name), Some(raw)) = (name.to_str(), raw.to_str()) else {
+                continue;
+            };
+            let Some(field) = name.strip_prefix(ENV_PREFIX).map(str::to_ascii_lowercase) else {
+                continue;
+            };
+            let Some(default) = defaults.get(&field).filter(|_| field != "version") else {
+                continue;
+            };
+            let value = match serde_json::from_str::<Value>(raw) {
+                Ok(parsed) if !default.is_string() || parsed.is_object() => parsed,
+                _ => Value::from(raw),
+            };
+            let original = map.insert(field.clone(), value);
+            if let Err(e) = serde_json::from_value::<Config>(Value::Object(map.clone())) {
+                warn!(variable = name, error = %e, "Ignoring invalid config override");
+                match original {
+                    Some(original) => map.insert(field, original),
+                    None => map.remove(&field),
+                };
+                continue;
+            }
+            overrides.push((field, original));
+        }
+    }
+    if !overrides.is_empty() {
+        // Field names only; the values may include the API key
+        let fields: Vec<&str> = overrides.iter().map(|(field, _)| field.as_str()).collect();
+        info!(fields = ?fields, "Config overridden from the environment");
+    }
+    *OVERRIDES.lock().unwrap() = overrides;
+}
+
+/// Rereads the policy and writes its locked values over `document`, remembering what the
+/// file had like `apply_env_overrides` does. The policy wins over the environment.
+fn apply_policy(document: &mut Value) -> Arc<policy::Policy> {
+    let policy = policy::reload();
+    let Some(map) = document.as_object_mut() else {
+        return policy;
+    };
+    let mut overrides = OVERRIDES.lock().unwrap();
+    for (field, value) in &policy.settings {
+        let original = map.insert(field.clone(), value.clone());
+        if !overrides.iter().any(|(overridden, _)| overridden == field) {
+            overrides.push((field.clone(), original));
+        }
+    }
+    policy
+}
+
+/// Whether the last `load` took `field` from the environment or the policy.
+fn is_overridden(field: &str) -> bool {
+    OVERRIDES
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(overridden, _)| overridden == field)
+}
+
+/// Upgrades a config document of any known version and deserializes it. Also returns the
+/// version the document started at.
+pub fn from_document(mut document: Value) -> Result<(Config, u32)> {
+    let from = migrate(&mut document)?;
+    let config = serde_json::from_value(document).context("parse config")?;
+    Ok((config, from))
+}
+
+/// Runs every migration between the document's version and `CONFIG_VERSION`, returning
+/// the version it started at. Files without a `version` predate versioning and count as 0.
+fn migrate(document: &mut Value) -> Result<u32> {
+    let map = document
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("config.json is not a JSON object"))?;
+    let from = match map.get("version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| anyhow!("config.json has an invalid version: {}", version))?,
+    };
+    if from > CONFIG_VERSION {
+        warn!(
+            version = from,
+            supported = CONFIG_VERSION,
+            "Config was written by a newer version"
+        );
+        return Ok(from);
+    }
+
+    for (version, step) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+        step(map);
+        map.insert("version".to_string(), Value::from(version as u32 + 1));
+    }
+    Ok(from)
+}
+
+/// Version 1 only introduces the `version` field; every older key still deserializes.
+fn migrate_v0_to_v1(_config: &mut Map<String, Value>) {}
+
+/// Version 2 replaces `reasoning_enabled` with a `reasoning` effort; enabled maps to
+/// medium, which is what OpenRouter used for a plain `enabled: true`.
+fn migrate_v1_to_v2(config: &mut Map<String, Value>) {
+    if let Some(enabled) = config.remove("reasoning_enabled") {
+        let effort = if enabled.as_bool() == Some(false) {
+            "off"
+        } else {
+            "medium"
+        };
+        config.insert("reasoning".to_string(), Value::from(effort));
+    }
+}
+
+/// Version 3 adds `log_content`, off for new installs; existing installs keep the
+/// content previews they were already logging.
+fn migrate_v2_to_v3(config: &mut Map<String, Value>) {
+    config
+        .entry("log_content")
+        .or_insert_with(|| Value::from(true));
+}
+
+/// Writes `config` to config.json. Values the policy does not permit are refused rather
+/// than written, and overridden fields keep what the file had.
 pub fn save(config: &Config) -> Result<()> {
+    if let Some(violation) = policy::current().violations(config).first() {
+        return Err(anyhow!("{}", violation));
+    }
     let path = config_path()?;
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).context("create config directory")?;
     }
-    let data = serde_json::to_string_pretty(config).context("serialize config")?;
+    let mut document = serde_json::to_value(config).context("serialize config")?;
+    if config.encrypt_api_key && !config.api_key.is_empty() {
+        let sealed = secret::seal(&config.api_key).context("encrypt api_key")?;
+        document["api_key"] = Value::from(sealed);
+    }
+    // Overrides stay out of the file; it keeps what it had for those fields
+    if let Some(map) = document.as_object_mut() {
+        for (field, original) in OVERRIDES.lock().unwrap().iter() {
+            match original {
+                Some(original) => map.insert(field.clone(), original.clone()),
+                None => map.remove(field),
+            };
+        }
+    }
+    let data = serde_json::to_string_pretty(&document).context("serialize config")?;
     fs::write(&path, data).context("write config.json")?;
     Ok(())
 }
 
+/// Removes `SECRET_FIELDS` from a config document.
+pub fn redact_secrets(document: &mut Value) {
+    if let Some(fields) = document.as_object_mut() {
+        for field in SECRET_FIELDS {
+            fields.remove(field);
+        }
+    }
+}
+
+/// Copies config.json, without its secrets, into the backups directory, keeping the
+/// newest `keep` timestamped copies. Other files there are left alone.
+pub fn backup(keep: usize) -> Result<Option<PathBuf>> {
+    let source = config_path()?;
+    if !source.exists() {
+        return Ok(None);
+    }
+    let dir = backups_dir()?;
+    fs::create_dir_all(&dir).context("create backups directory")?;
+
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let target = dir.join(format!("config-{}.json", stamp));
+    let data = fs::read_to_string(&source).context("read config.json")?;
+    let mut document: Value = serde_json::from_str(&data).context("parse config.json")?;
+    redact_secrets(&mut document);
+    let data = serde_json::to_string_pretty(&document).context("serialize config backup")?;
+    fs::write(&target, data).context("write config backup")?;
+
+    let mut backups: Vec<PathBuf> = fs::read_dir(&dir)
+        .context("read backups directory")?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("config-"))
+                .and_then(|name| name.strip_suffix(".json"))
+                .is_some_and(|stamp| {
+                    !stamp.is_empty() && stamp.bytes().all(|byte| byte.is_ascii_digit())
+                })
+        })
+        .collect();
+    backups.sort();
+    let excess = backups.len().saturating_sub(keep);
+    for old in backups.into_iter().take(excess) {
+        let _ = fs::remove_file(old);
+    }
+
+    Ok(Some(target))
+}
+
 pub fn migrate_legacy_data() -> Result<()> {
     let new_base = app_dir()?;
     fs::create_dir_all(&new_base).context("create new data directory")?;
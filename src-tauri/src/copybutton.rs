@@ -0,0 +1,103 @@
+use crate::clipboard::{Change, Subscriber};
+use crate::AppState;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+};
+use tracing::{debug, error};
+
+const LABEL: &str = "copy-button";
+const BUTTON_SIZE: f64 = 32.0;
+/// Gap between the cursor and the button's top-left corner, in logical pixels.
+const CURSOR_OFFSET: f64 = 14.0;
+/// How often the clipboard is sampled while the button is enabled.
+const POLL: Duration = Duration::from_millis(400);
+
+/// Incremented each time the button appears, so the hide timer of an earlier copy never
+/// hides a later one.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Shows the button near the cursor whenever other applications put new text on the
+/// clipboard, fed by `clipboard::watch` while `copy_button` is on.
+pub const SUBSCRIBER: Subscriber = Subscriber {
+    poll: |config| config.copy_button.then_some(POLL),
+    on_change: copied,
+};
+
+fn copied(app: &AppHandle, change: &Change<'_>) {
+    // Text already there when the watcher started or resumed was not just copied
+    if change.first {
+        return;
+    }
+    let duration = app.state::<AppState>().config().copy_button_ms;
+    show(app, Duration::from_millis(duration));
+}
+
+/// Shows the button next to the cursor for `duration`.
+fn show(app: &AppHandle, duration: Duration) {
+    let window = match app.get_webview_window(LABEL) {
+        Some(window) => window,
+        None => {
+            match WebviewWindowBuilder::new(app, LABEL, WebviewUrl::App("copybutton.html".into()))
+                .title("Translate")
+                .decorations(false)
+                .transparent(true)
+                .shadow(false)
+                .always_on_top(true)
+                .skip_taskbar(true)
+                .resizable(false)
+                // Clicking must not pull focus from the application the text came from
+                .focusable(false)
+                .inner_size(BUTTON_SIZE, BUTTON_SIZE)
+                .visible(false)
+                .build()
+            {
+                Ok(window) => window,
+                Err(e) => {
+                    error!(error = %e, "Failed to create copy button window");
+                    return;
+                }
+            }
+        }
+    };
+    if !position_near_cursor(&window) {
+        return;
+    }
+    let token = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let _ = window.show();
+    debug!("Copy button shown");
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(duration).await;
+        if GENERATION.load(Ordering::SeqCst) != token {
+            return;
+        }
+        if let Some(window) = app.get_webview_window(LABEL) {
+            let _ = window.hide();
+        }
+    });
+}
+
+/// Puts the button just below and right of the cursor, kept inside the monitor's work
+/// area. Returns false when the cursor position is unknown.
+fn position_near_cursor(window: &WebviewWindow) -> bool {
+    let Ok(cursor) = window.cursor_position() else {
+        return false;
+    };
+    let Some(monitor) = window.monitor_from_point(cursor.x, cursor.y).ok().flatten() else {
+        return false;
+    };
+    let area = monitor.work_area();
+    let scale = monitor.scale_factor();
+    let size = BUTTON_SIZE * scale;
+    let offset = CURSOR_OFFSET * scale;
+    let max_x = area.position.x as f64 + area.size.width as f64 - size;
+    let max_y = area.position.y as f64 + area.size.height as f64 - size;
+    let x = (cursor.x + offset).min(max_x).max(area.position.x as f64);
+    let y = (cursor.y + offset).min(max_y).max(area.position.y as f64);
+    window
+        .set_position(PhysicalPosition::new(x as i32, y as i32))
+        .is_ok()
+}
@@ -0,0 +1,246 @@
+use crate::metrics::ModelStats;
+use crate::{clipboard, config, logs, openrouter, AppState};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::Write;
+use std::net::ToSocketAddrs;
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::GlobalShortcutExt;
+use tracing::info;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Log files included in a diagnostic bundle, newest first.
+const BUNDLE_LOG_FILES: usize = 3;
+/// Error lines collected into the bundle's `errors.txt`.
+const BUNDLE_ERROR_LINES: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub message: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    /// False when any check failed; warnings do not count.
+    pub ok: bool,
+    pub checks: Vec<Check>,
+}
+
+fn check(name: &'static str, started: Instant, (status, message): (Status, String)) -> Check {
+    Check {
+        name,
+        status,
+        message,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// Runs every check in order; later network checks still run when an earlier one fails
+/// so the report shows everything at once.
+pub async fn run(app: &AppHandle) -> Report {
+    let state = app.state::<AppState>();
+    let client = state.http();
+    let api_key = state.config().api_key.clone();
+
+    let started = Instant::now();
+    let dns = check("dns", started, resolve().await);
+
+    let started = Instant::now();
+    let tls = check(
+        "tls",
+        started,
+        match openrouter::ping(&client).await {
+            Ok(status) => (Status::Ok, format!("Connected, HTTP {}", status)),
+            Err(e) => (Status::Fail, format!("{:#}", e)),
+        },
+    );
+
+    let started = Instant::now();
+    let key = check(
+        "api_key",
+        started,
+        if api_key.trim().is_empty() {
+            (Status::Fail, "API key is not set".to_string())
+        } else {
+            match openrouter::check_key(&client, api_key.trim()).await {
+                Ok(openrouter::KeyCheck::Valid(_)) => (Status::Ok, "Key accepted".to_string()),
+                Ok(openrouter::KeyCheck::Rejected(reason)) => (Status::Fail, reason),
+                Err(e) => (Status::Fail, format!("{:#}", e)),
+            }
+        },
+    );
+
+    let started = Instant::now();
+    let clipboard = check("clipboard", started, clipboard_readable(app));
+
+    let started = Instant::now();
+    let hotkey = check("hotkey", started, hotkey_registered(app));
+
+    let checks = vec![dns, tls, key, clipboard, hotkey];
+    let ok = checks.iter().all(|check| check.status != Status::Fail);
+    info!(
+        ok,
+        failed = checks
+            .iter()
+            .filter(|check| check.status == Status::Fail)
+            .count(),
+        "Diagnostics finished"
+    );
+    Report { ok, checks }
+}
+
+async fn resolve() -> (Status, String) {
+    let address = openrouter::api_address();
+    let target = address.clone();
+    let lookup =
+        tokio::task::spawn_blocking(move || target.to_socket_addrs().map(|addrs| addrs.count()))
+            .await;
+    match lookup {
+        Ok(Ok(0)) => (
+            Status::Fail,
+            format!("{} resolved to no addresses", address),
+        ),
+        Ok(Ok(count)) => (
+            Status::Ok,
+            format!("{} resolved ({} addresses)", address, count),
+        ),
+        Ok(Err(e)) => (Status::Fail, format!("Lookup failed: {}", e)),
+        Err(e) => (Status::Fail, format!("Lookup did not finish: {}", e)),
+    }
+}
+
+/// Reads the clipboard without writing to it: a write would drop every flavor but the
+/// one written, such as images or the rich text of a copied document.
+fn clipboard_readable(app: &AppHandle) -> (Status, String) {
+    match clipboard::read_text(app) {
+        Ok(text) => {
+            let flavor = clipboard::read_formatted()
+                .map(|formatted| format!(", with {}", formatted.kind().to_uppercase()))
+                .unwrap_or_default();
+            (
+                Status::Ok,
+                format!("Read {} characters{}", text.chars().count(), flavor),
+            )
+        }
+        // Images and files are not readable as text
+        Err(e) => (Status::Warn, format!("Could not read text: {}", e)),
+    }
+}
+
+fn hotkey_registered(app: &AppHandle) -> (Status, String) {
+    let state = app.state::<AppState>();
+    let Some(shortcut) = *state.current_shortcut.lock().unwrap() else {
+        return (Status::Fail, "Hotkey is not registered".to_string());
+    };
+    if app.global_shortcut().is_registered(shortcut) {
+        (
+            Status::Ok,
+            format!("{} is registered", shortcut.into_string()),
+        )
+    } else {
+        (
+            Status::Fail,
+            format!(
+                "{} is not registered; another app may own it",
+                shortcut.into_string()
+            ),
+        )
+    }
+}
+
+/// `info.json` in a diagnostic bundle.
+#[derive(Debug, Serialize)]
+struct BundleInfo {
+    version: &'static str,
+    tauri_version: &'static str,
+    webview_version: Option<String>,
+    os: &'static str,
+    arch: &'static str,
+    /// Unix seconds.
+    exported_at: u64,
+    api_key_set: bool,
+    active_requests: usize,
+    metrics: Vec<ModelStats>,
+}
+
+/// Zips version and platform info, the config without secrets, the newest log files and
+/// their last error lines into `path`, for attaching to a bug report.
+pub fn export(app: &AppHandle, path: &Path) -> Result<()> {
+    let state = app.state::<AppState>();
+    let config = state.config();
+    let log_files = logs::recent_files(&config::logs_dir()?, BUNDLE_LOG_FILES);
+
+    let mut zip = ZipWriter::new(File::create(path).context("create diagnostics file")?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let info = BundleInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        tauri_version: tauri::VERSION,
+        webview_version: tauri::webview_version().ok(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        exported_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default(),
+        api_key_set: !config.api_key.trim().is_empty(),
+        active_requests: state.requests.len(),
+        metrics: state.metrics.stats(),
+    };
+    zip.start_file("info.json", options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&info).context("serialize info")?)?;
+
+    let mut document = serde_json::to_value(&*config).context("serialize config")?;
+    config::redact_secrets(&mut document);
+    zip.start_file("config.json", options)?;
+    zip.write_all(&serde_json::to_vec_pretty(&document).context("serialize config")?)?;
+
+    // Oldest first, so the error lines end with the most recent
+    let mut errors = VecDeque::with_capacity(BUNDLE_ERROR_LINES);
+    for log_file in log_files.iter().rev() {
+        let Some(name) = log_file.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let data = fs::read(log_file).with_context(|| format!("read {}", name))?;
+        for line in String::from_utf8_lossy(&data)
+            .lines()
+            .filter(|line| line.contains(" ERROR "))
+        {
+            if errors.len() == BUNDLE_ERROR_LINES {
+                errors.pop_front();
+            }
+            errors.push_back(line.to_string());
+        }
+        zip.start_file(format!("logs/{}", name), options)?;
+        zip.write_all(&data)?;
+    }
+    zip.start_file("errors.txt", options)?;
+    for line in &errors {
+        writeln!(zip, "{}", line)?;
+    }
+
+    zip.finish().context("finish diagnostics file")?;
+    info!(
+        path = %path.display(),
+        log_files = log_files.len(),
+        errors = errors.len(),
+        "Diagnostics exported"
+    );
+    Ok(())
+}
@@ -0,0 +1,257 @@
+use crate::clipboard::Input;
+use crate::config::Config;
+use crate::error::CommandError;
+use crate::i18n::{self, Msg};
+use crate::toast::show_toast;
+use crate::{openrouter, Action, AppState, Delivery};
+use anyhow::{anyhow, Context, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SizedSample, Stream, StreamConfig};
+use std::io::Cursor;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, error, info, warn};
+
+/// Recordings shorter than this are treated as an accidental tap.
+const MIN_RECORDING: Duration = Duration::from_millis(300);
+/// Speech is downsampled towards this rate to keep uploads small.
+const TARGET_SAMPLE_RATE: u32 = 16_000;
+
+/// Mono samples the microphone callback appends to.
+type Samples = Arc<Mutex<Vec<f32>>>;
+
+/// Microphone audio, downmixed to mono.
+struct Audio {
+    samples: Vec<f32>,
+    sample_rate: u32,
+}
+
+impl Audio {
+    fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.samples.len() as f64 / self.sample_rate.max(1) as f64)
+    }
+}
+
+/// The microphone stream is not `Send`, so it lives on its own thread until told to stop.
+struct Recording {
+    stop: Sender<()>,
+    thread: JoinHandle<Result<Audio>>,
+    started: Instant,
+}
+
+/// The push-to-talk recording in progress, if any.
+#[derive(Default)]
+pub struct Dictation {
+    recording: Mutex<Option<Recording>>,
+}
+
+/// Starts recording from the default microphone when the dictation hotkey goes down.
+/// Recording ends when the key is released, or after `dictation_max_secs`.
+pub fn start(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let mut recording = state.dictation.recording.lock().unwrap();
+    // Held keys repeat their press events
+    if recording.is_some() {
+        return;
+    }
+
+    let max_secs = state.config().dictation_max_secs;
+    let (stop, stopped) = mpsc::channel();
+    let (ready, opened) = mpsc::channel();
+    let thread = std::thread::spawn(move || record(stopped, max_secs, ready));
+    match opened.recv() {
+        Ok(Ok(())) => {
+            info!(max_secs, "Dictation started");
+            show_toast(app, "processing", &i18n::t(Msg::Listening));
+            *recording = Some(Recording {
+                stop,
+                thread,
+                started: Instant::now(),
+            });
+        }
+        Ok(Err(e)) => {
+            error!(error = %e, "Microphone failed");
+            show_toast(app, "error", &i18n::t(Msg::MicrophoneFailed));
+        }
+        Err(_) => {
+            error!("Microphone thread exited");
+            show_toast(app, "error", &i18n::t(Msg::MicrophoneFailed));
+        }
+    }
+}
+
+/// Stops recording when the dictation hotkey is released, then transcribes and
+/// translates what was said.
+pub fn stop(app: &AppHandle) {
+    let Some(recording) = app
+        .state::<AppState>()
+        .dictation
+        .recording
+        .lock()
+        .unwrap()
+        .take()
+    else {
+        return;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = finish(&app, recording).await;
+    });
+}
+
+async fn finish(app: &AppHandle, recording: Recording) -> Result<(), CommandError> {
+    let held_ms = recording.started.elapsed().as_millis() as u64;
+    let _ = recording.stop.send(());
+    let audio = tauri::async_runtime::spawn_blocking(move || recording.thread.join())
+        .await
+        .map_err(|e| anyhow!("join recorder: {}", e))
+        .and_then(|joined| joined.map_err(|_| anyhow!("recorder thread panicked"))?);
+    let audio = match audio {
+        Ok(audio) => audio,
+        Err(e) => {
+            error!(error = %e, "Dictation recording failed");
+            show_toast(app, "error", &i18n::t(Msg::MicrophoneFailed));
+            return Err(e.into());
+        }
+    };
+    if audio.duration() < MIN_RECORDING {
+        debug!(held_ms, "Dictation too short");
+        show_toast(app, "error", &i18n::t(Msg::TooShort));
+        return Err(i18n::t(Msg::RecordingTooShort).into());
+    }
+    let wav = encode_wav(&audio).map_err(|e| e.to_string())?;
+
+    let state = app.state::<AppState>();
+    let mut config = Config::clone(&state.config());
+    if !config.transcription_model.trim().is_empty() {
+        config.model = config.transcription_model.trim().to_string();
+    }
+    show_toast(app, "processing", &i18n::t(Msg::Transcribing));
+    let started = Instant::now();
+    let transcript = match openrouter::transcribe(&state.http(), &config, &wav).await {
+        Ok(transcript) => transcript,
+        Err(e) => {
+            warn!(error = %e, model = %config.model, "Transcription failed");
+            show_toast(app, "error", &i18n::t(Msg::TranscriptionFailed));
+            return Err(e.into());
+        }
+    };
+    info!(
+        audio_ms = audio.duration().as_millis() as u64,
+        transcript_len = transcript.len(),
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "Speech transcribed"
+    );
+
+    crate::admit_input(app, &state, Action::Translate, false, &transcript)?;
+    crate::execute_action(
+        app.clone(),
+        state,
+        Action::Translate,
+        false,
+        Input::from(transcript),
+        None,
+        Delivery::Output,
+    )
+    .await
+}
+
+/// Runs on the recorder thread: opens the microphone, reports whether that worked, then
+/// collects samples until stopped or `max_secs` pass (0 = no limit).
+fn record(
+    stopped: Receiver<()>,
+    max_secs: u64,
+    ready: Sender<Result<(), String>>,
+) -> Result<Audio> {
+    let (stream, samples, sample_rate) = match open_stream() {
+        Ok(opened) => {
+            let _ = ready.send(Ok(()));
+            opened
+        }
+        Err(e) => {
+            let _ = ready.send(Err(format!("{:#}", e)));
+            return Err(e);
+        }
+    };
+    if max_secs == 0 {
+        let _ = stopped.recv();
+    } else if let Err(RecvTimeoutError::Timeout) =
+        stopped.recv_timeout(Duration::from_secs(max_secs))
+    {
+        info!(max_secs, "Dictation reached its time limit");
+    }
+    drop(stream);
+
+    let samples = std::mem::take(&mut *samples.lock().unwrap());
+    Ok(Audio {
+        samples,
+        sample_rate,
+    })
+}
+
+fn open_stream() -> Result<(Stream, Samples, u32)> {
+    let device = cpal::default_host()
+        .default_input_device()
+        .context("no microphone found")?;
+    let supported = device
+        .default_input_config()
+        .context("read microphone config")?;
+    let sample_rate = supported.sample_rate().0;
+    let format = supported.sample_format();
+    let config: StreamConfig = supported.into();
+    let samples = Arc::new(Mutex::new(Vec::new()));
+
+    let stream = match format {
+        cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config, samples.clone()),
+        cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config, samples.clone()),
+        cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config, samples.clone()),
+        other => Err(anyhow!("unsupported sample format {:?}", other)),
+    }?;
+    stream.play().context("start microphone")?;
+    Ok((stream, samples, sample_rate))
+}
+
+fn build_stream<T>(device: &cpal::Device, config: &StreamConfig, samples: Samples) -> Result<Stream>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let channels = config.channels.max(1) as usize;
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                let mono = data.chunks(channels).map(|frame| {
+                    frame.iter().map(|&s| s.to_sample::<f32>()).sum::<f32>() / frame.len() as f32
+                });
+                samples.lock().unwrap().extend(mono);
+            },
+            |e| warn!(error = %e, "Microphone stream error"),
+            None,
+        )
+        .context("open microphone")
+}
+
+/// 16-bit mono WAV, averaged down by a whole factor towards `TARGET_SAMPLE_RATE`.
+fn encode_wav(audio: &Audio) -> Result<Vec<u8>> {
+    let factor = (audio.sample_rate / TARGET_SAMPLE_RATE).max(1) as usize;
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: audio.sample_rate / factor as u32,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut wav = Vec::new();
+    let mut writer = hound::WavWriter::new(Cursor::new(&mut wav), spec).context("start WAV")?;
+    for chunk in audio.samples.chunks(factor) {
+        let sample = chunk.iter().sum::<f32>() / chunk.len() as f32;
+        writer
+            .write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .context("write WAV")?;
+    }
+    writer.finalize().context("finish WAV")?;
+    Ok(wav)
+}
@@ -0,0 +1,118 @@
+use crate::config::Config;
+use crate::i18n::{self, Msg};
+use crate::permissions::{self, Permission};
+use crate::toast::show_toast;
+use crate::{Action, AppState};
+use rdev::{EventType, Key};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::Modifiers;
+use tracing::{error, info};
+
+/// Both taps, and the pause between them, must be shorter than this.
+const TAP_WINDOW: Duration = Duration::from_millis(400);
+
+/// The listener hooks the keyboard for the life of the process; there is only ever one.
+static LISTENING: AtomicBool = AtomicBool::new(false);
+
+/// Starts the low-level keyboard listener once `double_tap_modifier` is first set.
+/// It cannot be unhooked, so afterwards it stays up and ignores keys while the
+/// trigger is cleared or ThirdSpace is paused.
+pub fn ensure_listening(app: &AppHandle, config: &Config) {
+    if config.double_tap_modifier.trim().is_empty() || LISTENING.load(Ordering::SeqCst) {
+        return;
+    }
+    // Saving the settings again retries once the permission is granted
+    if !permissions::ensure(Permission::InputMonitoring) {
+        show_toast(app, "error", &i18n::t(Msg::AllowInputMonitoring));
+        return;
+    }
+    if LISTENING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        info!("Keyboard listener started for double tap");
+        let handle = app.clone();
+        let mut taps = Taps::default();
+        let result = rdev::listen(move |event| {
+            let Some(watched) = watched_modifier(&handle) else {
+                taps = Taps::default();
+                return;
+            };
+            if taps.feed(event.event_type, watched) {
+                info!(modifier = ?watched, "Double tap triggered");
+                crate::spawn_action(&handle, Action::Translate);
+            }
+        });
+        if let Err(e) = result {
+            error!(error = ?e, "Keyboard listener failed");
+            LISTENING.store(false, Ordering::SeqCst);
+            show_toast(&app, "error", &i18n::t(Msg::DoubleTapUnavailable));
+        }
+    });
+}
+
+fn watched_modifier(app: &AppHandle) -> Option<Modifiers> {
+    let state = app.state::<AppState>();
+    if *state.paused.lock().unwrap() {
+        return None;
+    }
+    let config = state.config();
+    crate::parse_modifier(&config.double_tap_modifier.trim().to_ascii_lowercase())
+}
+
+fn modifier_of(key: Key) -> Option<Modifiers> {
+    match key {
+        Key::ControlLeft | Key::ControlRight => Some(Modifiers::CONTROL),
+        Key::ShiftLeft | Key::ShiftRight => Some(Modifiers::SHIFT),
+        Key::Alt | Key::AltGr => Some(Modifiers::ALT),
+        Key::MetaLeft | Key::MetaRight => Some(Modifiers::SUPER),
+        _ => None,
+    }
+}
+
+/// Tracks taps of the watched modifier. A tap only counts when no other key or mouse
+/// button was pressed with it, so chords like Ctrl+C never add up to a double tap.
+#[derive(Default)]
+struct Taps {
+    /// When the modifier went down, while it is held on its own.
+    pressed: Option<Instant>,
+    /// When the previous clean tap was released.
+    last_tap: Option<Instant>,
+}
+
+impl Taps {
+    /// Feeds one input event; true when it completes a double tap.
+    fn feed(&mut self, event: EventType, watched: Modifiers) -> bool {
+        match event {
+            EventType::KeyPress(key) if modifier_of(key) == Some(watched) => {
+                // Held keys repeat their press events
+                self.pressed.get_or_insert_with(Instant::now);
+                false
+            }
+            EventType::KeyRelease(key) if modifier_of(key) == Some(watched) => {
+                let Some(pressed) = self.pressed.take() else {
+                    return false;
+                };
+                if pressed.elapsed() > TAP_WINDOW {
+                    self.last_tap = None;
+                    return false;
+                }
+                match self.last_tap.take() {
+                    Some(last) if last.elapsed() <= TAP_WINDOW => true,
+                    _ => {
+                        self.last_tap = Some(Instant::now());
+                        false
+                    }
+                }
+            }
+            EventType::KeyPress(_) | EventType::ButtonPress(_) => {
+                *self = Self::default();
+                false
+            }
+            _ => false,
+        }
+    }
+}
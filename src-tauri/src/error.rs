@@ -0,0 +1,157 @@
+use crate::i18n::{self, Msg};
+use crate::openrouter::{self, ExtractionError, MissingApiKey, StatusError};
+use crate::usage::BudgetExceeded;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// Why a command failed. The frontend receives `{ code, message }` (plus `status` for
+/// provider errors), so windows can react to `code` instead of matching `message`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    ClipboardEmpty,
+    ApiKeyMissing,
+    /// OpenRouter answered 429.
+    RateLimited,
+    /// The connect or request timeout ran out.
+    Timeout,
+    /// Any other transport failure, such as no connection.
+    Network(String),
+    /// OpenRouter answered with another error status.
+    ProviderError {
+        status: u16,
+        message: String,
+    },
+    /// The response arrived without the expected output in it.
+    ExtractionFailed(String),
+    /// This month's `monthly_budget` or `monthly_token_limit` is used up.
+    BudgetExceeded(String),
+    Other(String),
+}
+
+impl CommandError {
+    /// Machine-readable name, also sent with `translation-failed`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CommandError::ClipboardEmpty => "clipboard_empty",
+            CommandError::ApiKeyMissing => "api_key_missing",
+            CommandError::RateLimited => "rate_limited",
+            CommandError::Timeout => "timeout",
+            CommandError::Network(_) => "network",
+            CommandError::ProviderError { .. } => "provider_error",
+            CommandError::ExtractionFailed(_) => "extraction_failed",
+            CommandError::BudgetExceeded(_) => "budget_exceeded",
+            CommandError::Other(_) => "failed",
+        }
+    }
+
+    /// What the error toast says; empty for the generic failure text.
+    pub fn toast(&self) -> String {
+        match self {
+            CommandError::ClipboardEmpty => i18n::t(Msg::ClipboardEmpty),
+            CommandError::ApiKeyMissing => i18n::t(Msg::ApiKeyNotConfigured),
+            CommandError::RateLimited => i18n::t(Msg::RateLimited),
+            CommandError::Timeout => i18n::t(Msg::TimedOut),
+            CommandError::BudgetExceeded(_) => i18n::t(Msg::BudgetReached),
+            CommandError::Network(_)
+            | CommandError::ProviderError { .. }
+            | CommandError::ExtractionFailed(_)
+            | CommandError::Other(_) => String::new(),
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::ClipboardEmpty => f.write_str(&i18n::t(Msg::ClipboardIsEmpty)),
+            CommandError::ApiKeyMissing => f.write_str(&i18n::t(Msg::ApiKeyNotConfigured)),
+            CommandError::RateLimited => f.write_str(&i18n::t(Msg::RateLimited)),
+            CommandError::Timeout => f.write_str(&i18n::t(Msg::TimedOut)),
+            CommandError::Network(message)
+            | CommandError::ProviderError { message, .. }
+            | CommandError::ExtractionFailed(message)
+            | CommandError::BudgetExceeded(message)
+            | CommandError::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Wire {
+            code: &'static str,
+            message: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            status: Option<u16>,
+        }
+        let status = match self {
+            CommandError::RateLimited => Some(429),
+            CommandError::ProviderError { status, .. } => Some(*status),
+            _ => None,
+        };
+        Wire {
+            code: self.code(),
+            message: self.to_string(),
+            status,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::Other(message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        CommandError::Other(message.to_string())
+    }
+}
+
+/// Sorts a failed request by the typed cause anywhere in its chain.
+impl From<&anyhow::Error> for CommandError {
+    fn from(error: &anyhow::Error) -> Self {
+        if let Some(status) = error.chain().find_map(|e| e.downcast_ref::<StatusError>()) {
+            if status.status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return CommandError::RateLimited;
+            }
+            return CommandError::ProviderError {
+                status: status.status.as_u16(),
+                message: error.to_string(),
+            };
+        }
+        if error.chain().any(|e| e.is::<MissingApiKey>()) {
+            return CommandError::ApiKeyMissing;
+        }
+        if let Some(exceeded) = error
+            .chain()
+            .find_map(|e| e.downcast_ref::<BudgetExceeded>())
+        {
+            return CommandError::BudgetExceeded(exceeded.to_string());
+        }
+        if error.chain().any(|e| e.is::<ExtractionError>()) {
+            return CommandError::ExtractionFailed(error.to_string());
+        }
+        if openrouter::is_timeout(error) {
+            return CommandError::Timeout;
+        }
+        if error
+            .chain()
+            .any(|e| e.downcast_ref::<reqwest::Error>().is_some())
+        {
+            return CommandError::Network(error.to_string());
+        }
+        CommandError::Other(error.to_string())
+    }
+}
+
+impl From<anyhow::Error> for CommandError {
+    fn from(error: anyhow::Error) -> Self {
+        CommandError::from(&error)
+    }
+}
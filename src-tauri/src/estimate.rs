@@ -0,0 +1,69 @@
+use crate::config::Config;
+use crate::ModelPricing;
+use crate::{openrouter, prompt};
+use serde::Serialize;
+
+/// Tokens the translation markers and separators add to a response.
+const RESPONSE_OVERHEAD_TOKENS: u64 = 16;
+
+/// What a request is expected to use, before it is sent.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Estimate {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// USD; `None` until the model's pricing is known.
+    pub cost: Option<f64>,
+}
+
+/// Rough token count without the model's tokenizer: CJK characters are about a token
+/// each, other text about four characters per token.
+pub fn tokens(text: &str) -> u64 {
+    let (wide, other) = text.chars().fold((0u64, 0u64), |(wide, other), ch| {
+        if is_wide(ch) {
+            (wide + 1, other)
+        } else {
+            (wide, other + 1)
+        }
+    });
+    wide + other.div_ceil(4)
+}
+
+/// Estimates a translation of `input` with the prompt `config` would send. The
+/// translation is assumed to be about as long as the input.
+pub fn estimate(config: &Config, input: &str, pricing: Option<ModelPricing>) -> Estimate {
+    let prompt = prompt::build_prompt(
+        input,
+        &config.target_language,
+        config.translation_tone,
+        openrouter::structured_output(config),
+    );
+    let prompt_tokens = tokens(&prompt.system) + tokens(&prompt.user);
+    let completion_tokens = tokens(input) + RESPONSE_OVERHEAD_TOKENS;
+    Estimate {
+        prompt_tokens,
+        completion_tokens,
+        cost: pricing.map(|pricing| pricing.cost(prompt_tokens, completion_tokens)),
+    }
+}
+
+/// `$0.0012`-style amount with enough digits to tell small costs apart.
+pub fn format_cost(cost: f64) -> String {
+    if cost >= 0.1 {
+        format!("${:.2}", cost)
+    } else if cost >= 0.001 {
+        format!("${:.4}", cost)
+    } else {
+        format!("${:.6}", cost)
+    }
+}
+
+fn is_wide(ch: char) -> bool {
+    matches!(ch,
+        '\u{1100}'..='\u{11FF}'
+            | '\u{3000}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF00}'..='\u{FFEF}')
+}
@@ -0,0 +1,128 @@
+use crate::config::Config;
+use crate::openrouter;
+use crate::subtitle;
+use anyhow::{anyhow, Context, Result};
+use reqwest::Client;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Plain-text extensions accepted by `translate_file`.
+pub const TEXT_EXTENSIONS: &[&str] = &["txt", "md"];
+/// Subtitle extensions; only their cue text is translated.
+pub const SUBTITLE_EXTENSIONS: &[&str] = &["srt", "vtt"];
+
+/// Translates a text or subtitle file and writes the result to `<name>.<lang>.<ext>`
+/// next to the original, or `<name>.<lang>-2.<ext>` and so on when that exists. Returns
+/// the path written.
+pub async fn translate_file(
+    client: &Client,
+    config: &Config,
+    path: &Path,
+    on_progress: impl Fn(usize, usize),
+) -> Result<PathBuf> {
+    let extension = extension(path);
+    let is_subtitle = SUBTITLE_EXTENSIONS.contains(&extension.as_str());
+    if !is_subtitle && !TEXT_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(anyhow!("Unsupported file type: {}", path.display()));
+    }
+    let input = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    if input.trim().is_empty() {
+        return Err(anyhow!("File is empty: {}", path.display()));
+    }
+
+    let mut translated = if is_subtitle {
+        subtitle::translate(client, config, &input, on_progress).await?
+    } else {
+        openrouter::translate_with_progress(client, config, &input, on_progress).await?
+    };
+    if input.ends_with('\n') && !translated.ends_with('\n') {
+        translated.push('\n');
+    }
+
+    let output = write_new(&output_path(path, &config.target_language), &translated)?;
+    info!(
+        input_len = input.len(),
+        translated_len = translated.len(),
+        output = %output.display(),
+        "File translated"
+    );
+    Ok(output)
+}
+
+/// `notes.md` translated into English becomes `notes.english.md`.
+pub fn output_path(path: &Path, target_language: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match path.extension() {
+        Some(extension) => format!(
+            "{}.{}.{}",
+            stem,
+            language_slug(target_language),
+            extension.to_string_lossy()
+        ),
+        None => format!("{}.{}", stem, language_slug(target_language)),
+    };
+    path.with_file_name(name)
+}
+
+/// Writes `data` to `path`, or to the first of `<stem>-2.<ext>`, `<stem>-3.<ext>`, ...
+/// that does not exist yet, so an earlier translation is never overwritten.
+fn write_new(path: &Path, data: &str) -> Result<PathBuf> {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut candidate = path.to_path_buf();
+    for attempt in 2.. {
+        // `create_new` fails instead of truncating, even if the file appeared just now
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&candidate)
+        {
+            Ok(mut file) => {
+                file.write_all(data.as_bytes())
+                    .with_context(|| format!("write {}", candidate.display()))?;
+                return Ok(candidate);
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                let name = match path.extension() {
+                    Some(extension) => {
+                        format!("{}-{}.{}", stem, attempt, extension.to_string_lossy())
+                    }
+                    None => format!("{}-{}", stem, attempt),
+                };
+                candidate = path.with_file_name(name);
+            }
+            Err(e) => {
+                return Err(e).with_context(|| format!("create {}", candidate.display()));
+            }
+        }
+    }
+    unreachable!("ran out of file name suffixes")
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+fn language_slug(language: &str) -> String {
+    let slug: String = language
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|ch| if ch.is_alphanumeric() { ch } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "translated".to_string()
+    } else {
+        slug.to_string()
+    }
+}
@@ -0,0 +1,52 @@
+use crate::config::{AppRule, Config};
+use crate::languages;
+use tracing::{debug, info};
+
+/// Applies the first of `config.app_rules` that matches the foreground application.
+/// Called as an action starts, so the foreground app is the one the hotkey was pressed in.
+pub fn apply_rules(config: &mut Config) {
+    if config.app_rules.is_empty() {
+        return;
+    }
+    let Ok(window) = active_win_pos_rs::get_active_window() else {
+        debug!("Foreground application unknown");
+        return;
+    };
+    let executable = window
+        .process_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let Some(rule) = config
+        .app_rules
+        .iter()
+        .find(|rule| matches(rule, &window.app_name, &executable))
+        .cloned()
+    else {
+        debug!(app = %window.app_name, executable = %executable, "No app rule matched");
+        return;
+    };
+
+    if !rule.target_language.trim().is_empty() {
+        config.target_language = languages::normalize(&rule.target_language);
+    }
+    if rule.tone.is_some() {
+        config.translation_tone = rule.tone;
+    }
+    if !rule.model.trim().is_empty() {
+        config.model = rule.model.trim().to_string();
+    }
+    info!(
+        app = %window.app_name,
+        rule = %rule.app,
+        target_language = %config.target_language,
+        tone = ?config.translation_tone,
+        model = %config.model,
+        "App rule applied"
+    );
+}
+
+fn matches(rule: &AppRule, app_name: &str, executable: &str) -> bool {
+    let app = rule.app.trim();
+    !app.is_empty() && (app.eq_ignore_ascii_case(app_name) || app.eq_ignore_ascii_case(executable))
+}
@@ -0,0 +1,83 @@
+use crate::config;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, LogicalPosition, LogicalSize, WebviewWindow};
+use tracing::{debug, warn};
+
+/// Distance into the window that must be on a monitor: enough of the title bar to drag it.
+const TITLE_BAR_PROBE: f64 = 24.0;
+
+/// Position and size of a window in logical pixels.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+pub fn path() -> Result<PathBuf> {
+    Ok(config::app_dir()?.join("windows.json"))
+}
+
+/// Saved geometry of the window `label`, unless it would open off screen, e.g. on a
+/// monitor that has since been unplugged.
+pub fn restore(app: &AppHandle, label: &str) -> Option<WindowGeometry> {
+    let geometry = *load().ok()?.get(label)?;
+    if !on_screen(app, &geometry) {
+        debug!(label, ?geometry, "Saved window position is off screen");
+        return None;
+    }
+    Some(geometry)
+}
+
+/// Saves where `window` is now, for `restore` to put it back the next time it opens.
+pub fn remember(window: &WebviewWindow) {
+    if let Err(e) = save(window) {
+        warn!(label = window.label(), error = %e, "Failed to save window geometry");
+    }
+}
+
+fn save(window: &WebviewWindow) -> Result<()> {
+    let scale = window.scale_factor()?;
+    let position: LogicalPosition<f64> = window.outer_position()?.to_logical(scale);
+    let size: LogicalSize<f64> = window.inner_size()?.to_logical(scale);
+    let geometry = WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    };
+
+    let mut saved = load().unwrap_or_default();
+    saved.insert(window.label().to_string(), geometry);
+    let path = path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).context("create app directory")?;
+    }
+    let data = serde_json::to_string_pretty(&saved).context("serialize window geometry")?;
+    fs::write(&path, data).context("write windows.json")?;
+    debug!(label = window.label(), ?geometry, "Window geometry saved");
+    Ok(())
+}
+
+fn load() -> Result<HashMap<String, WindowGeometry>> {
+    let data = fs::read_to_string(path()?).context("read windows.json")?;
+    serde_json::from_str(&data).context("parse windows.json")
+}
+
+fn on_screen(app: &AppHandle, geometry: &WindowGeometry) -> bool {
+    let Ok(monitors) = app.available_monitors() else {
+        return true;
+    };
+    let (x, y) = (geometry.x + TITLE_BAR_PROBE, geometry.y + TITLE_BAR_PROBE);
+    monitors.iter().any(|monitor| {
+        let scale = monitor.scale_factor();
+        let origin: LogicalPosition<f64> = monitor.position().to_logical(scale);
+        let size: LogicalSize<f64> = monitor.size().to_logical(scale);
+        x >= origin.x && x < origin.x + size.width && y >= origin.y && y < origin.y + size.height
+    })
+}
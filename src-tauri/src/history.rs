@@ -0,0 +1,107 @@
+use crate::clipboard::{Change, Subscriber};
+use crate::AppState;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::debug;
+
+/// How often the clipboard is sampled while the history is enabled.
+const POLL: Duration = Duration::from_secs(1);
+/// Shortest word `preview` considers a possible secret.
+const SECRET_MIN_CHARS: usize = 8;
+/// Stands in for a possible secret in a preview.
+const MASK: &str = "••••••";
+
+/// Recent clipboard text snapshots, newest first. Text the app wrote itself is skipped
+/// so translations do not push the originals out of the ring.
+#[derive(Default)]
+pub struct ClipboardHistory {
+    entries: Mutex<VecDeque<String>>,
+    own_write: Mutex<Option<String>>,
+}
+
+impl ClipboardHistory {
+    /// Adds a snapshot, moving an existing copy to the front. Returns whether the ring changed.
+    pub fn record(&self, text: &str, capacity: usize) -> bool {
+        if text.trim().is_empty() || self.own_write.lock().unwrap().as_deref() == Some(text) {
+            return false;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.front().is_some_and(|newest| newest == text) {
+            return false;
+        }
+        entries.retain(|entry| entry != text);
+        entries.push_front(text.to_string());
+        entries.truncate(capacity);
+        true
+    }
+
+    /// Remembers text the app is about to put on the clipboard.
+    pub fn ignore(&self, text: &str) {
+        *self.own_write.lock().unwrap() = Some(text.to_string());
+    }
+
+    /// Whether `text` is what the app last put on the clipboard itself.
+    pub fn is_own_write(&self, text: &str) -> bool {
+        self.own_write.lock().unwrap().as_deref() == Some(text)
+    }
+
+    pub fn entries(&self) -> Vec<String> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn get(&self, index: usize) -> Option<String> {
+        self.entries.lock().unwrap().get(index).cloned()
+    }
+
+    /// Empties the ring. Returns whether it held anything.
+    pub fn clear(&self) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let had_entries = !entries.is_empty();
+        entries.clear();
+        had_entries
+    }
+}
+
+/// One-line tray label for an entry. Words that look like passwords or tokens are shown
+/// as dots, since the tray menu is visible to anyone looking at the screen.
+pub fn preview(text: &str, max_chars: usize) -> String {
+    let line = text
+        .split_whitespace()
+        .map(|word| if looks_secret(word) { MASK } else { word })
+        .collect::<Vec<_>>()
+        .join(" ");
+    if line.chars().count() <= max_chars {
+        line
+    } else {
+        let cut: String = line.chars().take(max_chars).collect();
+        format!("{}…", cut.trim_end())
+    }
+}
+
+/// A word of at least `SECRET_MIN_CHARS` that mixes letters with digits or symbols,
+/// like most generated passwords and API keys. URLs and paths match too; their previews
+/// lose little.
+fn looks_secret(word: &str) -> bool {
+    let word = word.trim_matches(|ch: char| matches!(ch, '.' | ',' | ';' | ':' | '!' | '?'));
+    word.chars().count() >= SECRET_MIN_CHARS
+        && word.chars().any(|ch| ch.is_alphabetic())
+        && word.chars().any(|ch| !ch.is_alphabetic())
+}
+
+/// Feeds the ring from `clipboard::watch` while `clipboard_history_size` is above 0.
+pub const SUBSCRIBER: Subscriber = Subscriber {
+    poll: |config| (config.clipboard_history_size > 0).then_some(POLL),
+    on_change: record_change,
+};
+
+/// Records a new clipboard text and refreshes the tray menu when the history changes.
+fn record_change(app: &AppHandle, change: &Change<'_>) {
+    let state = app.state::<AppState>();
+    let capacity = state.config().clipboard_history_size;
+    if state.clipboard_history.record(change.text, capacity) {
+        debug!(text_len = change.text.len(), "Clipboard snapshot recorded");
+        crate::refresh_tray_menu(app);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::config::PostHooks;
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use std::borrow::Cow;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::{debug, warn};
+
+/// Runs the post-processing hooks on a result. A rule that does not compile and a
+/// command that fails, times out or prints nothing are skipped with a warning, so the
+/// result is always delivered.
+pub async fn apply(hooks: &PostHooks, text: String) -> String {
+    let mut text = apply_rules(hooks, text);
+    let command = hooks.command.trim();
+    if !command.is_empty() {
+        match run_command(hooks, &text).await {
+            Ok(output) => {
+                debug!(command, "Post-processing command applied");
+                text = output;
+            }
+            Err(e) => warn!(command, error = %e, "Post-processing command skipped"),
+        }
+    }
+    text
+}
+
+fn apply_rules(hooks: &PostHooks, mut text: String) -> String {
+    for (idx, rule) in hooks.rules.iter().enumerate() {
+        if rule.find.is_empty() {
+            continue;
+        }
+        let regex = match Regex::new(&rule.find) {
+            Ok(regex) => regex,
+            Err(e) => {
+                warn!(rule = idx, error = %e, "Invalid post-processing rule skipped");
+                continue;
+            }
+        };
+        if let Cow::Owned(replaced) = regex.replace_all(&text, rule.replace.as_str()) {
+            debug!(rule = idx, "Post-processing rule applied");
+            text = replaced;
+        }
+    }
+    text
+}
+
+/// Pipes `text` through `command` and returns what it printed.
+async fn run_command(hooks: &PostHooks, text: &str) -> Result<String> {
+    let mut command = Command::new(hooks.command.trim());
+    command
+        .args(&hooks.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // Dropping the wait on timeout then stops the command too
+        .kill_on_drop(true);
+    // CREATE_NO_WINDOW, so console programs do not flash a window
+    #[cfg(target_os = "windows")]
+    command.creation_flags(0x0800_0000);
+    let mut child = command.spawn().context("start command")?;
+
+    // Written from its own task so a command that prints before reading all of its input
+    // cannot block on a full pipe
+    let mut stdin = child.stdin.take().context("open command stdin")?;
+    let input = text.as_bytes().to_vec();
+    let writer = tauri::async_runtime::spawn(async move {
+        // A command that stops reading early closes the pipe; its output still counts
+        let _ = stdin.write_all(&input).await;
+    });
+    let output = tokio::time::timeout(
+        Duration::from_secs(hooks.timeout_secs.max(1)),
+        child.wait_with_output(),
+    )
+    .await
+    .map_err(|_| anyhow!("timed out after {} s", hooks.timeout_secs.max(1)))?
+    .context("wait for command")?;
+    writer.abort();
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let mut result = String::from_utf8(output.stdout).context("command output is not UTF-8")?;
+    // Most tools end their output with a line break the result did not have
+    if !text.ends_with('\n') && result.ends_with('\n') {
+        result.pop();
+        if result.ends_with('\r') {
+            result.pop();
+        }
+    }
+    if result.trim().is_empty() {
+        return Err(anyhow!("command printed nothing"));
+    }
+    Ok(result)
+}
@@ -0,0 +1,1035 @@
+use crate::prompt::Tone;
+use std::sync::atomic::{AtomicU8, Ordering};
+use tracing::debug;
+
+/// Languages the backend's toasts, tray menu and errors are available in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Lang {
+    En,
+    Zh,
+    Ja,
+    De,
+}
+
+impl Lang {
+    const ALL: [Lang; 4] = [Lang::En, Lang::Zh, Lang::Ja, Lang::De];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Zh => "zh",
+            Lang::Ja => "ja",
+            Lang::De => "de",
+        }
+    }
+
+    /// Matches on the primary subtag, so `zh-CN`, `ja_JP.UTF-8` and `de` all work.
+    pub fn from_code(code: &str) -> Option<Lang> {
+        let primary = code
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Lang::ALL.into_iter().find(|lang| lang.code() == primary)
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(Lang::En as u8);
+
+/// Applies `Config::ui_language`. Empty follows the system locale; anything without
+/// strings here falls back to English.
+pub fn set_language(setting: &str) -> Lang {
+    let setting = setting.trim();
+    let lang = if setting.is_empty() {
+        sys_locale::get_locale()
+            .as_deref()
+            .and_then(Lang::from_code)
+            .unwrap_or(Lang::En)
+    } else {
+        Lang::from_code(setting).unwrap_or(Lang::En)
+    };
+    if CURRENT.swap(lang as u8, Ordering::Relaxed) != lang as u8 {
+        debug!(setting, lang = lang.code(), "UI language set");
+    }
+    lang
+}
+
+pub fn current() -> Lang {
+    Lang::ALL
+        .into_iter()
+        .find(|lang| *lang as u8 == CURRENT.load(Ordering::Relaxed))
+        .unwrap_or(Lang::En)
+}
+
+/// Every user-facing string the backend shows: toast titles, tray menu labels, window
+/// and dialog titles, and the errors returned to the settings window.
+#[derive(Debug, Clone, Copy)]
+pub enum Msg<'a> {
+    // Toast titles
+    Saved,
+    Exported,
+    Imported,
+    Copied,
+    Restored,
+    Review,
+    FileSaved,
+    FileFailed,
+    ClipboardFailed,
+    ClipboardEmpty,
+    NothingToUndo,
+    MissingLanguage,
+    MissingText,
+    DeepLinksOff,
+    NoLanguages,
+    SaveFailed,
+    AutostartFailed,
+    Busy,
+    TimedOut,
+    RateLimited,
+    OfflineQueued,
+    TooLongRepeat,
+    BudgetReached,
+    BudgetReachedRepeat,
+    FastModeOn,
+    FastModeOff,
+    Paused,
+    Resumed,
+    QuickTranslateFailed,
+    SettingsFailed,
+    /// Estimated cost.
+    CostRepeat(&'a str),
+    /// Estimated cost.
+    TranslatingCost(&'a str),
+    /// Version of the newer release.
+    UpdateAvailable(&'a str),
+    Updating,
+    UpToDate,
+    UpdateCheckFailed,
+    UpdateFailed,
+    CaptureFailed,
+    SelectionTooSmall,
+    OcrFailed,
+    Listening,
+    MicrophoneFailed,
+    TooShort,
+    Transcribing,
+    TranscriptionFailed,
+    TypingFailed,
+    QueueDone,
+    /// Inputs still queued.
+    QueueLeft(usize),
+    CreditsLow,
+    AllowInputMonitoring,
+    DoubleTapUnavailable,
+    /// The hotkey setup finished with.
+    Ready(&'a str),
+
+    // Tray menu
+    MenuTranslate,
+    MenuTranslateSelection,
+    MenuTranslateCommit,
+    MenuTranslateFile,
+    MenuCaptureRegion,
+    MenuQuickTranslate,
+    MenuTranslateRecent,
+    MenuPinned,
+    MenuUndo,
+    MenuSummarize,
+    MenuProofread,
+    MenuRewrite,
+    MenuTone(Tone),
+    MenuActions,
+    MenuTargetLanguage,
+    MenuNextLanguage,
+    MenuFastMode,
+    MenuPause,
+    MenuResume,
+    MenuCheckUpdates,
+    MenuSettings,
+    MenuQuit,
+    /// Seconds the last request took.
+    TooltipLastDone(&'a str),
+    TooltipLastFailed(&'a str),
+
+    // Window and dialog titles
+    WindowResult,
+    WindowReview,
+    WindowCandidates,
+    WindowQuickTranslate,
+    WindowSettings,
+    DialogTranslateFile,
+    DialogQuit,
+    DialogDeepLink,
+    /// Start of the link's text.
+    DeepLinkConfirm(&'a str),
+    /// Running requests, then inputs in the offline queue.
+    QuitPending(usize, usize),
+    QuitWhenDone,
+    QuitNow,
+    KeepRunning,
+    FilterText,
+    FilterSubtitles,
+
+    // Errors
+    AllowAccessibility,
+    ApiKeyEmpty,
+    KeyAccepted,
+    /// Key label.
+    KeyAcceptedAs(&'a str),
+    ApiKeyNotConfigured,
+    /// Underlying error.
+    CouldNotReach(&'a str),
+    TargetLanguageNotSet,
+    EnterApiKey,
+    PickModel,
+    NoCaptureInProgress,
+    SelectionIsTooSmall,
+    RecordingTooShort,
+    KeyNotRecognized,
+    KeyDisabled,
+    TextEmpty,
+    ClipboardIsEmpty,
+    Quitting,
+    NoReviewWaiting,
+    NoInputWaiting,
+    /// Action name.
+    UnknownAction(&'a str),
+    NoClipboardEntry(usize),
+    NoCandidate(usize),
+    /// Journal record id.
+    NoTranslationWithId(&'a str),
+    /// Parser error.
+    InvalidLogFilter(&'a str),
+    LoggingNotInitialized,
+    /// Estimated cost, limit.
+    CostOverLimit(&'a str, &'a str),
+    /// Cost, tokens and month used.
+    BudgetExceeded(&'a str, u64, &'a str),
+    /// Length, limit.
+    InputTooLong(usize, usize),
+    RequestsInProgress(usize),
+    /// Underlying error.
+    QueuedUntilReachable(&'a str),
+
+    // Settings validation
+    ApiKeyMissing,
+    NotAnOpenRouterKey,
+    TargetLanguageEmpty,
+    UnknownLanguage(&'a str),
+    /// Comma-separated names.
+    UnknownLanguages(&'a str),
+    ModelMissing,
+    UnknownModel(&'a str),
+    /// The load error.
+    CaBundleInvalid(&'a str),
+    InvalidUrl,
+    LockedByPolicy,
+    ModelNotAllowed(&'a str),
+    TemperatureRange,
+    TopPRange,
+    MaxTokensMin,
+    MaxContextPairs(usize),
+    ContextNeedsHistory,
+    ConcurrentRange(usize),
+    MaxCandidates(usize),
+    ReasoningBudgetMin,
+    AppRuleNoApp,
+    /// The regex error.
+    InvalidPattern(&'a str),
+
+    // Hotkeys
+    HotkeyMissing,
+    /// Hotkey, the setting already using it.
+    HotkeyInUse(&'a str, &'a str),
+    HotkeyUsedElsewhere(&'a str),
+    HotkeyOwn,
+    HotkeyAvailable,
+    /// Underlying error.
+    HotkeyRegisterFailed(&'a str),
+    /// Hotkey name, parser error.
+    InvalidHotkey(&'a str, &'a str),
+    UnknownModifier(&'a str),
+    UnknownKey(&'a str),
+    UnknownFunctionKey(&'a str),
+    MultipleKeys,
+    NoKey,
+}
+
+/// `msg` in the current UI language.
+pub fn t(msg: Msg) -> String {
+    match current() {
+        Lang::En => en(msg),
+        Lang::Zh => zh(msg),
+        Lang::Ja => ja(msg),
+        Lang::De => de(msg),
+    }
+}
+
+fn en(msg: Msg) -> String {
+    match msg {
+        Msg::Saved => "Saved".into(),
+        Msg::Exported => "Exported".into(),
+        Msg::Imported => "Imported".into(),
+        Msg::Copied => "Copied".into(),
+        Msg::Restored => "Restored".into(),
+        Msg::Review => "Review".into(),
+        Msg::FileSaved => "File saved".into(),
+        Msg::FileFailed => "File failed".into(),
+        Msg::ClipboardFailed => "Clipboard failed".into(),
+        Msg::ClipboardEmpty => "Clipboard empty".into(),
+        Msg::NothingToUndo => "Nothing to undo".into(),
+        Msg::MissingLanguage => "Missing language".into(),
+        Msg::MissingText => "Missing text".into(),
+        Msg::DeepLinksOff => "Deep links are off".into(),
+        Msg::NoLanguages => "No languages".into(),
+        Msg::SaveFailed => "Save failed".into(),
+        Msg::AutostartFailed => "Autostart not changed".into(),
+        Msg::Busy => "Busy".into(),
+        Msg::TimedOut => "Timed out".into(),
+        Msg::RateLimited => "Rate limited, try again shortly".into(),
+        Msg::OfflineQueued => "Offline, queued".into(),
+        Msg::TooLongRepeat => "Too long, repeat to send".into(),
+        Msg::BudgetReached => "Budget reached".into(),
+        Msg::BudgetReachedRepeat => "Budget reached, repeat to send".into(),
+        Msg::FastModeOn => "Fast mode on".into(),
+        Msg::FastModeOff => "Fast mode off".into(),
+        Msg::Paused => "Paused".into(),
+        Msg::Resumed => "Resumed".into(),
+        Msg::QuickTranslateFailed => "Quick translate failed".into(),
+        Msg::SettingsFailed => "Settings failed".into(),
+        Msg::CostRepeat(cost) => format!("~{}, repeat to send", cost),
+        Msg::TranslatingCost(cost) => format!("Translating ~{}", cost),
+        Msg::UpdateAvailable(version) => format!("ThirdSpace {} is available", version),
+        Msg::Updating => "Updating...".into(),
+        Msg::UpToDate => "Up to date".into(),
+        Msg::UpdateCheckFailed => "Update check failed".into(),
+        Msg::UpdateFailed => "Update failed".into(),
+        Msg::CaptureFailed => "Capture failed".into(),
+        Msg::SelectionTooSmall => "Selection too small".into(),
+        Msg::OcrFailed => "OCR failed".into(),
+        Msg::Listening => "Listening...".into(),
+        Msg::MicrophoneFailed => "Microphone failed".into(),
+        Msg::TooShort => "Too short".into(),
+        Msg::Transcribing => "Transcribing...".into(),
+        Msg::TranscriptionFailed => "Transcription failed".into(),
+        Msg::TypingFailed => "Typing failed".into(),
+        Msg::QueueDone => "Queue done".into(),
+        Msg::QueueLeft(count) => format!("Queued, {} left", count),
+        Msg::CreditsLow => "Credits low".into(),
+        Msg::AllowInputMonitoring => "Allow Input Monitoring".into(),
+        Msg::DoubleTapUnavailable => "Double tap unavailable".into(),
+        Msg::Ready(hotkey) => format!("Ready: {}", hotkey),
+
+        Msg::MenuTranslate => "Translate".into(),
+        Msg::MenuTranslateSelection => "Translate Selection".into(),
+        Msg::MenuTranslateCommit => "Translate Commit Message".into(),
+        Msg::MenuTranslateFile => "Translate File…".into(),
+        Msg::MenuCaptureRegion => "Capture Region…".into(),
+        Msg::MenuQuickTranslate => "Quick Translate…".into(),
+        Msg::MenuTranslateRecent => "Translate Recent".into(),
+        Msg::MenuPinned => "Pinned".into(),
+        Msg::MenuUndo => "Undo Translation".into(),
+        Msg::MenuSummarize => "Summarize".into(),
+        Msg::MenuProofread => "Proofread".into(),
+        Msg::MenuRewrite => "Rewrite".into(),
+        Msg::MenuTone(Tone::Formal) => "More Formal".into(),
+        Msg::MenuTone(Tone::Concise) => "More Concise".into(),
+        Msg::MenuTone(Tone::Friendly) => "More Friendly".into(),
+        Msg::MenuActions => "Actions".into(),
+        Msg::MenuTargetLanguage => "Target Language".into(),
+        Msg::MenuNextLanguage => "Next Language".into(),
+        Msg::MenuFastMode => "Fast Mode".into(),
+        Msg::MenuPause => "Pause ThirdSpace".into(),
+        Msg::MenuResume => "Resume ThirdSpace".into(),
+        Msg::MenuCheckUpdates => "Check for Updates…".into(),
+        Msg::MenuSettings => "Settings".into(),
+        Msg::MenuQuit => "Quit".into(),
+        Msg::TooltipLastDone(secs) => format!("Last translation: done in {} s", secs),
+        Msg::TooltipLastFailed(secs) => format!("Last translation: failed after {} s", secs),
+
+        Msg::WindowResult => "ThirdSpace Result".into(),
+        Msg::WindowReview => "ThirdSpace Review".into(),
+        Msg::WindowCandidates => "ThirdSpace Candidates".into(),
+        Msg::WindowQuickTranslate => "ThirdSpace Quick Translate".into(),
+        Msg::WindowSettings => "ThirdSpace Settings".into(),
+        Msg::DialogTranslateFile => "Translate File".into(),
+        Msg::DialogQuit => "Quit ThirdSpace?".into(),
+        Msg::DialogDeepLink => "Translate Link?".into(),
+        Msg::DeepLinkConfirm(text) => format!(
+            "A link asks ThirdSpace to translate:\n\n{}\n\nThe result opens in the result window.",
+            text
+        ),
+        Msg::QuitPending(running, queued) => format!(
+            "Still running: {}. Waiting for a connection: {}.\n\nQuitting now loses their results.",
+            running, queued
+        ),
+        Msg::QuitWhenDone => "Quit When Done".into(),
+        Msg::QuitNow => "Quit Now".into(),
+        Msg::KeepRunning => "Keep Running".into(),
+        Msg::FilterText => "Text".into(),
+        Msg::FilterSubtitles => "Subtitles".into(),
+
+        Msg::AllowAccessibility => "Allow Accessibility".into(),
+        Msg::ApiKeyEmpty => "API key is empty".into(),
+        Msg::KeyAccepted => "Key accepted".into(),
+        Msg::KeyAcceptedAs(label) => format!("Key accepted ({})", label),
+        Msg::ApiKeyNotConfigured => "API key not configured".into(),
+        Msg::CouldNotReach(e) => format!("Could not reach OpenRouter: {}", e),
+        Msg::TargetLanguageNotSet => "Target language not set".into(),
+        Msg::EnterApiKey => "Enter your OpenRouter API key".into(),
+        Msg::PickModel => "Pick a model".into(),
+        Msg::NoCaptureInProgress => "No capture in progress".into(),
+        Msg::SelectionIsTooSmall => "Selection is too small".into(),
+        Msg::RecordingTooShort => "Recording is too short".into(),
+        Msg::KeyNotRecognized => {
+            "OpenRouter did not recognize this key; it may be mistyped or revoked".into()
+        }
+        Msg::KeyDisabled => "This key is disabled or not allowed to use the API".into(),
+        Msg::TextEmpty => "Text is empty".into(),
+        Msg::ClipboardIsEmpty => "Clipboard is empty".into(),
+        Msg::Quitting => "ThirdSpace is quitting".into(),
+        Msg::NoReviewWaiting => "No translation is waiting for review".into(),
+        Msg::NoInputWaiting => "No input is waiting for confirmation".into(),
+        Msg::UnknownAction(name) => format!("Unknown action: {}", name),
+        Msg::NoClipboardEntry(index) => format!("No clipboard history entry {}", index),
+        Msg::NoCandidate(index) => format!("No candidate {}", index),
+        Msg::NoTranslationWithId(id) => format!("No translation with id {}", id),
+        Msg::InvalidLogFilter(e) => format!("Invalid log filter: {}", e),
+        Msg::LoggingNotInitialized => "Logging is not initialized".into(),
+        Msg::CostOverLimit(cost, limit) => {
+            format!("Estimated cost {} is over the {} limit", cost, limit)
+        }
+        Msg::BudgetExceeded(cost, tokens, month) => format!(
+            "Monthly budget reached ({} and {} tokens used in {})",
+            cost, tokens, month
+        ),
+        Msg::InputTooLong(length, limit) => format!(
+            "Input is {} characters, over the {} character limit",
+            length, limit
+        ),
+        Msg::RequestsInProgress(count) => format!("{} requests already in progress", count),
+        Msg::QueuedUntilReachable(e) => format!("Queued until OpenRouter is reachable: {}", e),
+
+        Msg::ApiKeyMissing => "API key is missing".into(),
+        Msg::NotAnOpenRouterKey => {
+            "Doesn't look like an OpenRouter key (expected sk-or-…, without spaces)".into()
+        }
+        Msg::TargetLanguageEmpty => "Target language is empty".into(),
+        Msg::UnknownLanguage(name) => format!("Unknown language: {}", name),
+        Msg::UnknownLanguages(names) => format!("Unknown languages: {}", names),
+        Msg::ModelMissing => "Model is missing".into(),
+        Msg::UnknownModel(model) => format!("Unknown model: {}", model),
+        Msg::CaBundleInvalid(error) => format!("CA certificates unusable: {}", error),
+        Msg::InvalidUrl => "Enter a full URL, such as https://example.com".into(),
+        Msg::LockedByPolicy => "Set by your administrator".into(),
+        Msg::ModelNotAllowed(model) => format!("{} is not allowed by your administrator", model),
+        Msg::TemperatureRange => "Temperature must be between 0 and 2".into(),
+        Msg::TopPRange => "Top P must be above 0 and at most 1".into(),
+        Msg::MaxTokensMin => "Max tokens must be at least 1".into(),
+        Msg::MaxContextPairs(max) => format!("At most {} earlier translations", max),
+        Msg::ContextNeedsHistory => "Conversation context needs translation history".into(),
+        Msg::ConcurrentRange(max) => {
+            format!("Concurrent requests must be between 1 and {}", max)
+        }
+        Msg::MaxCandidates(max) => format!("At most {} candidates", max),
+        Msg::ReasoningBudgetMin => "Reasoning budget must be at least 1 token".into(),
+        Msg::AppRuleNoApp => "App rule has no app name".into(),
+        Msg::InvalidPattern(e) => format!("Invalid pattern: {}", e),
+
+        Msg::HotkeyMissing => "Hotkey is missing".into(),
+        Msg::HotkeyInUse(hotkey, other) => format!("{} is already used by {}", hotkey, other),
+        Msg::HotkeyUsedElsewhere(hotkey) => {
+            format!("{} is already used by another application", hotkey)
+        }
+        Msg::HotkeyOwn => "Registered by ThirdSpace".into(),
+        Msg::HotkeyAvailable => "Available".into(),
+        Msg::HotkeyRegisterFailed(e) => format!("Failed to register hotkey: {}", e),
+        Msg::InvalidHotkey(name, e) => format!("Invalid {} hotkey: {}", name, e),
+        Msg::UnknownModifier(modifier) => format!("Unknown modifier: {}", modifier),
+        Msg::UnknownKey(key) => format!("Unknown key: {}", key),
+        Msg::UnknownFunctionKey(key) => format!("Unknown function key: {}", key),
+        Msg::MultipleKeys => "Multiple keys specified".into(),
+        Msg::NoKey => "No key specified".into(),
+    }
+}
+
+fn zh(msg: Msg) -> String {
+    match msg {
+        Msg::Saved => "已保存".into(),
+        Msg::Exported => "已导出".into(),
+        Msg::Imported => "已导入".into(),
+        Msg::Copied => "已复制".into(),
+        Msg::Restored => "已恢复".into(),
+        Msg::Review => "待审阅".into(),
+        Msg::FileSaved => "文件已保存".into(),
+        Msg::FileFailed => "文件翻译失败".into(),
+        Msg::ClipboardFailed => "剪贴板出错".into(),
+        Msg::ClipboardEmpty => "剪贴板为空".into(),
+        Msg::NothingToUndo => "没有可撤销的内容".into(),
+        Msg::MissingLanguage => "未设置语言".into(),
+        Msg::MissingText => "缺少文本".into(),
+        Msg::DeepLinksOff => "深层链接已关闭".into(),
+        Msg::NoLanguages => "没有语言".into(),
+        Msg::SaveFailed => "保存失败".into(),
+        Msg::AutostartFailed => "开机启动未更改".into(),
+        Msg::Busy => "忙碌中".into(),
+        Msg::TimedOut => "超时".into(),
+        Msg::RateLimited => "请求过于频繁，请稍后再试".into(),
+        Msg::OfflineQueued => "离线，已排队".into(),
+        Msg::TooLongRepeat => "文本过长，再按一次发送".into(),
+        Msg::BudgetReached => "已达预算".into(),
+        Msg::BudgetReachedRepeat => "已达预算，再按一次发送".into(),
+        Msg::FastModeOn => "快速模式已开启".into(),
+        Msg::FastModeOff => "快速模式已关闭".into(),
+        Msg::Paused => "已暂停".into(),
+        Msg::Resumed => "已恢复运行".into(),
+        Msg::QuickTranslateFailed => "快速翻译打开失败".into(),
+        Msg::SettingsFailed => "设置打开失败".into(),
+        Msg::CostRepeat(cost) => format!("约 {}，再按一次发送", cost),
+        Msg::TranslatingCost(cost) => format!("翻译中，约 {}", cost),
+        Msg::UpdateAvailable(version) => format!("ThirdSpace {} 已发布", version),
+        Msg::Updating => "正在更新...".into(),
+        Msg::UpToDate => "已是最新版本".into(),
+        Msg::UpdateCheckFailed => "检查更新失败".into(),
+        Msg::UpdateFailed => "更新失败".into(),
+        Msg::CaptureFailed => "截图失败".into(),
+        Msg::SelectionTooSmall => "选区太小".into(),
+        Msg::OcrFailed => "文字识别失败".into(),
+        Msg::Listening => "正在聆听...".into(),
+        Msg::MicrophoneFailed => "麦克风出错".into(),
+        Msg::TooShort => "太短".into(),
+        Msg::Transcribing => "正在转写...".into(),
+        Msg::TranscriptionFailed => "转写失败".into(),
+        Msg::TypingFailed => "输入失败".into(),
+        Msg::QueueDone => "队列已完成".into(),
+        Msg::QueueLeft(count) => format!("队列中还剩 {} 项", count),
+        Msg::CreditsLow => "余额不足".into(),
+        Msg::AllowInputMonitoring => "请允许输入监控权限".into(),
+        Msg::DoubleTapUnavailable => "双击热键不可用".into(),
+        Msg::Ready(hotkey) => format!("已就绪：{}", hotkey),
+
+        Msg::MenuTranslate => "翻译".into(),
+        Msg::MenuTranslateSelection => "翻译所选内容".into(),
+        Msg::MenuTranslateCommit => "翻译提交信息".into(),
+        Msg::MenuTranslateFile => "翻译文件…".into(),
+        Msg::MenuCaptureRegion => "截取区域…".into(),
+        Msg::MenuQuickTranslate => "快速翻译…".into(),
+        Msg::MenuTranslateRecent => "翻译最近内容".into(),
+        Msg::MenuPinned => "已固定".into(),
+        Msg::MenuUndo => "撤销翻译".into(),
+        Msg::MenuSummarize => "摘要".into(),
+        Msg::MenuProofread => "校对".into(),
+        Msg::MenuRewrite => "改写".into(),
+        Msg::MenuTone(Tone::Formal) => "更正式".into(),
+        Msg::MenuTone(Tone::Concise) => "更简洁".into(),
+        Msg::MenuTone(Tone::Friendly) => "更友好".into(),
+        Msg::MenuActions => "自定义操作".into(),
+        Msg::MenuTargetLanguage => "目标语言".into(),
+        Msg::MenuNextLanguage => "下一种语言".into(),
+        Msg::MenuFastMode => "快速模式".into(),
+        Msg::MenuPause => "暂停 ThirdSpace".into(),
+        Msg::MenuResume => "恢复 ThirdSpace".into(),
+        Msg::MenuCheckUpdates => "检查更新…".into(),
+        Msg::MenuSettings => "设置".into(),
+        Msg::MenuQuit => "退出".into(),
+        Msg::TooltipLastDone(secs) => format!("上次翻译：{} 秒完成", secs),
+        Msg::TooltipLastFailed(secs) => format!("上次翻译：{} 秒后失败", secs),
+
+        Msg::WindowResult => "ThirdSpace 翻译结果".into(),
+        Msg::WindowReview => "ThirdSpace 审阅".into(),
+        Msg::WindowCandidates => "ThirdSpace 候选译文".into(),
+        Msg::WindowQuickTranslate => "ThirdSpace 快速翻译".into(),
+        Msg::WindowSettings => "ThirdSpace 设置".into(),
+        Msg::DialogTranslateFile => "翻译文件".into(),
+        Msg::DialogQuit => "退出 ThirdSpace？".into(),
+        Msg::DialogDeepLink => "翻译链接？".into(),
+        Msg::DeepLinkConfirm(text) => format!(
+            "有链接请求 ThirdSpace 翻译：\n\n{}\n\n结果将显示在结果窗口中。",
+            text
+        ),
+        Msg::QuitPending(running, queued) => format!(
+            "正在进行：{}。等待连接：{}。\n\n现在退出将丢失这些结果。",
+            running, queued
+        ),
+        Msg::QuitWhenDone => "完成后退出".into(),
+        Msg::QuitNow => "立即退出".into(),
+        Msg::KeepRunning => "继续运行".into(),
+        Msg::FilterText => "文本".into(),
+        Msg::FilterSubtitles => "字幕".into(),
+
+        Msg::AllowAccessibility => "请允许辅助功能权限".into(),
+        Msg::ApiKeyEmpty => "API 密钥为空".into(),
+        Msg::KeyAccepted => "密钥有效".into(),
+        Msg::KeyAcceptedAs(label) => format!("密钥有效（{}）", label),
+        Msg::ApiKeyNotConfigured => "未配置 API 密钥".into(),
+        Msg::CouldNotReach(e) => format!("无法连接 OpenRouter：{}", e),
+        Msg::TargetLanguageNotSet => "未设置目标语言".into(),
+        Msg::EnterApiKey => "请输入 OpenRouter API 密钥".into(),
+        Msg::PickModel => "请选择模型".into(),
+        Msg::NoCaptureInProgress => "没有正在进行的截图".into(),
+        Msg::SelectionIsTooSmall => "选区太小".into(),
+        Msg::RecordingTooShort => "录音太短".into(),
+        Msg::KeyNotRecognized => "OpenRouter 无法识别此密钥，可能输入有误或已被撤销".into(),
+        Msg::KeyDisabled => "此密钥已停用或无权使用 API".into(),
+        Msg::TextEmpty => "文本为空".into(),
+        Msg::ClipboardIsEmpty => "剪贴板为空".into(),
+        Msg::Quitting => "ThirdSpace 正在退出".into(),
+        Msg::NoReviewWaiting => "没有等待审阅的翻译".into(),
+        Msg::NoInputWaiting => "没有等待确认的输入".into(),
+        Msg::UnknownAction(name) => format!("未知操作：{}", name),
+        Msg::NoClipboardEntry(index) => format!("剪贴板历史中没有第 {} 项", index),
+        Msg::NoCandidate(index) => format!("没有候选译文 {}", index),
+        Msg::NoTranslationWithId(id) => format!("没有 ID 为 {} 的翻译", id),
+        Msg::InvalidLogFilter(e) => format!("日志过滤器无效：{}", e),
+        Msg::LoggingNotInitialized => "日志尚未初始化".into(),
+        Msg::CostOverLimit(cost, limit) => format!("预计费用 {} 超过上限 {}", cost, limit),
+        Msg::BudgetExceeded(cost, tokens, month) => format!(
+            "已达每月预算（{} 已用 {} 和 {} 个 token）",
+            month, cost, tokens
+        ),
+        Msg::InputTooLong(length, limit) => {
+            format!("输入有 {} 个字符，超过 {} 个字符的上限", length, limit)
+        }
+        Msg::RequestsInProgress(count) => format!("已有 {} 个请求正在进行", count),
+        Msg::QueuedUntilReachable(e) => format!("已排队，待能连接 OpenRouter 时发送：{}", e),
+
+        Msg::ApiKeyMissing => "缺少 API 密钥".into(),
+        Msg::NotAnOpenRouterKey => {
+            "看起来不是 OpenRouter 密钥（应以 sk-or- 开头，且不含空格）".into()
+        }
+        Msg::TargetLanguageEmpty => "目标语言为空".into(),
+        Msg::UnknownLanguage(name) => format!("未知语言：{}", name),
+        Msg::UnknownLanguages(names) => format!("未知语言：{}", names),
+        Msg::ModelMissing => "缺少模型".into(),
+        Msg::UnknownModel(model) => format!("未知模型：{}", model),
+        Msg::CaBundleInvalid(error) => format!("CA 证书无法使用：{}", error),
+        Msg::InvalidUrl => "请输入完整的 URL，例如 https://example.com".into(),
+        Msg::LockedByPolicy => "由管理员设置".into(),
+        Msg::ModelNotAllowed(model) => format!("管理员不允许使用 {}", model),
+        Msg::TemperatureRange => "温度必须在 0 到 2 之间".into(),
+        Msg::TopPRange => "Top P 必须大于 0 且不超过 1".into(),
+        Msg::MaxTokensMin => "最大 token 数至少为 1".into(),
+        Msg::MaxContextPairs(max) => format!("最多 {} 条之前的翻译", max),
+        Msg::ContextNeedsHistory => "对话上下文需要开启翻译历史".into(),
+        Msg::ConcurrentRange(max) => format!("并发请求数必须在 1 到 {} 之间", max),
+        Msg::MaxCandidates(max) => format!("最多 {} 个候选译文", max),
+        Msg::ReasoningBudgetMin => "推理预算至少为 1 个 token".into(),
+        Msg::AppRuleNoApp => "应用规则缺少应用名称".into(),
+        Msg::InvalidPattern(e) => format!("无效的模式：{}", e),
+
+        Msg::HotkeyMissing => "缺少快捷键".into(),
+        Msg::HotkeyInUse(hotkey, other) => format!("{} 已被 {} 使用", hotkey, other),
+        Msg::HotkeyUsedElsewhere(hotkey) => format!("{} 已被其他应用占用", hotkey),
+        Msg::HotkeyOwn => "已由 ThirdSpace 注册".into(),
+        Msg::HotkeyAvailable => "可用".into(),
+        Msg::HotkeyRegisterFailed(e) => format!("快捷键注册失败：{}", e),
+        Msg::InvalidHotkey(name, e) => format!("{} 快捷键无效：{}", name, e),
+        Msg::UnknownModifier(modifier) => format!("未知修饰键：{}", modifier),
+        Msg::UnknownKey(key) => format!("未知按键：{}", key),
+        Msg::UnknownFunctionKey(key) => format!("未知功能键：{}", key),
+        Msg::MultipleKeys => "指定了多个按键".into(),
+        Msg::NoKey => "未指定按键".into(),
+    }
+}
+
+fn ja(msg: Msg) -> String {
+    match msg {
+        Msg::Saved => "保存しました".into(),
+        Msg::Exported => "エクスポートしました".into(),
+        Msg::Imported => "インポートしました".into(),
+        Msg::Copied => "コピーしました".into(),
+        Msg::Restored => "元に戻しました".into(),
+        Msg::Review => "確認待ち".into(),
+        Msg::FileSaved => "ファイルを保存しました".into(),
+        Msg::FileFailed => "ファイルの翻訳に失敗".into(),
+        Msg::ClipboardFailed => "クリップボードエラー".into(),
+        Msg::ClipboardEmpty => "クリップボードが空です".into(),
+        Msg::NothingToUndo => "元に戻す操作はありません".into(),
+        Msg::MissingLanguage => "言語が未設定です".into(),
+        Msg::MissingText => "テキストがありません".into(),
+        Msg::DeepLinksOff => "ディープリンクはオフです".into(),
+        Msg::NoLanguages => "言語がありません".into(),
+        Msg::SaveFailed => "保存に失敗".into(),
+        Msg::AutostartFailed => "自動起動を変更できません".into(),
+        Msg::Busy => "処理中".into(),
+        Msg::TimedOut => "タイムアウト".into(),
+        Msg::RateLimited => "レート制限中です。しばらくしてから再試行してください".into(),
+        Msg::OfflineQueued => "オフラインのため待機中".into(),
+        Msg::TooLongRepeat => "長すぎます。もう一度押すと送信".into(),
+        Msg::BudgetReached => "予算に到達".into(),
+        Msg::BudgetReachedRepeat => "予算に到達。もう一度押すと送信".into(),
+        Msg::FastModeOn => "高速モード オン".into(),
+        Msg::FastModeOff => "高速モード オフ".into(),
+        Msg::Paused => "一時停止しました".into(),
+        Msg::Resumed => "再開しました".into(),
+        Msg::QuickTranslateFailed => "クイック翻訳を開けません".into(),
+        Msg::SettingsFailed => "設定を開けません".into(),
+        Msg::CostRepeat(cost) => format!("約{}。もう一度押すと送信", cost),
+        Msg::TranslatingCost(cost) => format!("翻訳中 約{}", cost),
+        Msg::UpdateAvailable(version) => format!("ThirdSpace {} が利用可能です", version),
+        Msg::Updating => "更新中...".into(),
+        Msg::UpToDate => "最新版です".into(),
+        Msg::UpdateCheckFailed => "更新の確認に失敗".into(),
+        Msg::UpdateFailed => "更新に失敗".into(),
+        Msg::CaptureFailed => "キャプチャに失敗".into(),
+        Msg::SelectionTooSmall => "選択範囲が小さすぎます".into(),
+        Msg::OcrFailed => "文字認識に失敗".into(),
+        Msg::Listening => "聞き取り中...".into(),
+        Msg::MicrophoneFailed => "マイクのエラー".into(),
+        Msg::TooShort => "短すぎます".into(),
+        Msg::Transcribing => "文字起こし中...".into(),
+        Msg::TranscriptionFailed => "文字起こしに失敗".into(),
+        Msg::TypingFailed => "入力に失敗".into(),
+        Msg::QueueDone => "キューを処理しました".into(),
+        Msg::QueueLeft(count) => format!("キュー処理中、残り {} 件", count),
+        Msg::CreditsLow => "クレジット残高が少なくなっています".into(),
+        Msg::AllowInputMonitoring => "入力監視を許可してください".into(),
+        Msg::DoubleTapUnavailable => "ダブルタップは使用できません".into(),
+        Msg::Ready(hotkey) => format!("準備完了: {}", hotkey),
+
+        Msg::MenuTranslate => "翻訳".into(),
+        Msg::MenuTranslateSelection => "選択範囲を翻訳".into(),
+        Msg::MenuTranslateCommit => "コミットメッセージを翻訳".into(),
+        Msg::MenuTranslateFile => "ファイルを翻訳…".into(),
+        Msg::MenuCaptureRegion => "範囲をキャプチャ…".into(),
+        Msg::MenuQuickTranslate => "クイック翻訳…".into(),
+        Msg::MenuTranslateRecent => "最近の項目を翻訳".into(),
+        Msg::MenuPinned => "ピン留め".into(),
+        Msg::MenuUndo => "翻訳を元に戻す".into(),
+        Msg::MenuSummarize => "要約".into(),
+        Msg::MenuProofread => "校正".into(),
+        Msg::MenuRewrite => "書き換え".into(),
+        Msg::MenuTone(Tone::Formal) => "よりフォーマルに".into(),
+        Msg::MenuTone(Tone::Concise) => "より簡潔に".into(),
+        Msg::MenuTone(Tone::Friendly) => "より親しみやすく".into(),
+        Msg::MenuActions => "アクション".into(),
+        Msg::MenuTargetLanguage => "翻訳先の言語".into(),
+        Msg::MenuNextLanguage => "次の言語".into(),
+        Msg::MenuFastMode => "高速モード".into(),
+        Msg::MenuPause => "ThirdSpace を一時停止".into(),
+        Msg::MenuResume => "ThirdSpace を再開".into(),
+        Msg::MenuCheckUpdates => "アップデートを確認…".into(),
+        Msg::MenuSettings => "設定".into(),
+        Msg::MenuQuit => "終了".into(),
+        Msg::TooltipLastDone(secs) => format!("前回の翻訳: {} 秒で完了", secs),
+        Msg::TooltipLastFailed(secs) => format!("前回の翻訳: {} 秒後に失敗", secs),
+
+        Msg::WindowResult => "ThirdSpace 翻訳結果".into(),
+        Msg::WindowReview => "ThirdSpace 確認".into(),
+        Msg::WindowCandidates => "ThirdSpace 翻訳候補".into(),
+        Msg::WindowQuickTranslate => "ThirdSpace クイック翻訳".into(),
+        Msg::WindowSettings => "ThirdSpace 設定".into(),
+        Msg::DialogTranslateFile => "ファイルを翻訳".into(),
+        Msg::DialogQuit => "ThirdSpace を終了しますか？".into(),
+        Msg::DialogDeepLink => "リンクを翻訳しますか？".into(),
+        Msg::DeepLinkConfirm(text) => format!(
+            "リンクから ThirdSpace に翻訳の依頼があります：\n\n{}\n\n結果は結果ウィンドウに表示されます。",
+            text
+        ),
+        Msg::QuitPending(running, queued) => format!(
+            "処理中: {}。接続待ち: {}。\n\n今終了すると、これらの結果は失われます。",
+            running, queued
+        ),
+        Msg::QuitWhenDone => "完了後に終了".into(),
+        Msg::QuitNow => "今すぐ終了".into(),
+        Msg::KeepRunning => "終了しない".into(),
+        Msg::FilterText => "テキスト".into(),
+        Msg::FilterSubtitles => "字幕".into(),
+
+        Msg::AllowAccessibility => "アクセシビリティを許可してください".into(),
+        Msg::ApiKeyEmpty => "API キーが空です".into(),
+        Msg::KeyAccepted => "キーを確認しました".into(),
+        Msg::KeyAcceptedAs(label) => format!("キーを確認しました（{}）", label),
+        Msg::ApiKeyNotConfigured => "API キーが設定されていません".into(),
+        Msg::CouldNotReach(e) => format!("OpenRouter に接続できません: {}", e),
+        Msg::TargetLanguageNotSet => "翻訳先の言語が設定されていません".into(),
+        Msg::EnterApiKey => "OpenRouter の API キーを入力してください".into(),
+        Msg::PickModel => "モデルを選択してください".into(),
+        Msg::NoCaptureInProgress => "キャプチャは実行されていません".into(),
+        Msg::SelectionIsTooSmall => "選択範囲が小さすぎます".into(),
+        Msg::RecordingTooShort => "録音が短すぎます".into(),
+        Msg::KeyNotRecognized => {
+            "OpenRouter がこのキーを認識できません。入力ミスか無効化された可能性があります".into()
+        }
+        Msg::KeyDisabled => "このキーは無効か、API の使用が許可されていません".into(),
+        Msg::TextEmpty => "テキストが空です".into(),
+        Msg::ClipboardIsEmpty => "クリップボードが空です".into(),
+        Msg::Quitting => "ThirdSpace を終了しています".into(),
+        Msg::NoReviewWaiting => "確認待ちの翻訳はありません".into(),
+        Msg::NoInputWaiting => "確認待ちの入力はありません".into(),
+        Msg::UnknownAction(name) => format!("不明なアクション: {}", name),
+        Msg::NoClipboardEntry(index) => {
+            format!("クリップボード履歴に {} 番の項目はありません", index)
+        }
+        Msg::NoCandidate(index) => format!("候補 {} はありません", index),
+        Msg::NoTranslationWithId(id) => format!("ID {} の翻訳はありません", id),
+        Msg::InvalidLogFilter(e) => format!("ログフィルターが無効です: {}", e),
+        Msg::LoggingNotInitialized => "ログが初期化されていません".into(),
+        Msg::CostOverLimit(cost, limit) => {
+            format!("見積もり費用 {} が上限 {} を超えています", cost, limit)
+        }
+        Msg::BudgetExceeded(cost, tokens, month) => format!(
+            "月間予算に到達しました（{} に {} と {} トークンを使用）",
+            month, cost, tokens
+        ),
+        Msg::InputTooLong(length, limit) => {
+            format!(
+                "入力は {} 文字で、上限の {} 文字を超えています",
+                length, limit
+            )
+        }
+        Msg::RequestsInProgress(count) => format!("{} 件のリクエストが処理中です", count),
+        Msg::QueuedUntilReachable(e) => {
+            format!("OpenRouter に接続できるまで待機します: {}", e)
+        }
+
+        Msg::ApiKeyMissing => "API キーがありません".into(),
+        Msg::NotAnOpenRouterKey => {
+            "OpenRouter のキーではないようです（sk-or- で始まり、空白を含まない形式）".into()
+        }
+        Msg::TargetLanguageEmpty => "翻訳先の言語が空です".into(),
+        Msg::UnknownLanguage(name) => format!("不明な言語: {}", name),
+        Msg::UnknownLanguages(names) => format!("不明な言語: {}", names),
+        Msg::ModelMissing => "モデルがありません".into(),
+        Msg::UnknownModel(model) => format!("不明なモデル: {}", model),
+        Msg::CaBundleInvalid(error) => format!("CA 証明書を使用できません: {}", error),
+        Msg::InvalidUrl => "https://example.com のような完全な URL を入力してください".into(),
+        Msg::LockedByPolicy => "管理者が設定しています".into(),
+        Msg::ModelNotAllowed(model) => format!("{} は管理者により許可されていません", model),
+        Msg::TemperatureRange => "Temperature は 0 から 2 の間にしてください".into(),
+        Msg::TopPRange => "Top P は 0 より大きく 1 以下にしてください".into(),
+        Msg::MaxTokensMin => "最大トークン数は 1 以上にしてください".into(),
+        Msg::MaxContextPairs(max) => format!("以前の翻訳は最大 {} 件です", max),
+        Msg::ContextNeedsHistory => "会話の文脈には翻訳履歴が必要です".into(),
+        Msg::ConcurrentRange(max) => {
+            format!("同時リクエスト数は 1 から {} の間にしてください", max)
+        }
+        Msg::MaxCandidates(max) => format!("候補は最大 {} 件です", max),
+        Msg::ReasoningBudgetMin => "推論予算は 1 トークン以上にしてください".into(),
+        Msg::AppRuleNoApp => "アプリルールにアプリ名がありません".into(),
+        Msg::InvalidPattern(e) => format!("無効なパターンです: {}", e),
+
+        Msg::HotkeyMissing => "ホットキーがありません".into(),
+        Msg::HotkeyInUse(hotkey, other) => format!("{} は {} で使用されています", hotkey, other),
+        Msg::HotkeyUsedElsewhere(hotkey) => {
+            format!("{} は別のアプリケーションで使用されています", hotkey)
+        }
+        Msg::HotkeyOwn => "ThirdSpace が登録済み".into(),
+        Msg::HotkeyAvailable => "使用できます".into(),
+        Msg::HotkeyRegisterFailed(e) => format!("ホットキーを登録できません: {}", e),
+        Msg::InvalidHotkey(name, e) => format!("{} のホットキーが無効です: {}", name, e),
+        Msg::UnknownModifier(modifier) => format!("不明な修飾キー: {}", modifier),
+        Msg::UnknownKey(key) => format!("不明なキー: {}", key),
+        Msg::UnknownFunctionKey(key) => format!("不明なファンクションキー: {}", key),
+        Msg::MultipleKeys => "キーが複数指定されています".into(),
+        Msg::NoKey => "キーが指定されていません".into(),
+    }
+}
+
+fn de(msg: Msg) -> String {
+    match msg {
+        Msg::Saved => "Gespeichert".into(),
+        Msg::Exported => "Exportiert".into(),
+        Msg::Imported => "Importiert".into(),
+        Msg::Copied => "Kopiert".into(),
+        Msg::Restored => "Wiederhergestellt".into(),
+        Msg::Review => "Prüfen".into(),
+        Msg::FileSaved => "Datei gespeichert".into(),
+        Msg::FileFailed => "Datei fehlgeschlagen".into(),
+        Msg::ClipboardFailed => "Zwischenablage-Fehler".into(),
+        Msg::ClipboardEmpty => "Zwischenablage leer".into(),
+        Msg::NothingToUndo => "Nichts rückgängig zu machen".into(),
+        Msg::MissingLanguage => "Sprache fehlt".into(),
+        Msg::MissingText => "Text fehlt".into(),
+        Msg::DeepLinksOff => "Deep Links sind aus".into(),
+        Msg::NoLanguages => "Keine Sprachen".into(),
+        Msg::SaveFailed => "Speichern fehlgeschlagen".into(),
+        Msg::AutostartFailed => "Autostart nicht geändert".into(),
+        Msg::Busy => "Beschäftigt".into(),
+        Msg::TimedOut => "Zeitüberschreitung".into(),
+        Msg::RateLimited => "Ratenlimit erreicht, bitte gleich erneut versuchen".into(),
+        Msg::OfflineQueued => "Offline, eingereiht".into(),
+        Msg::TooLongRepeat => "Zu lang, zum Senden wiederholen".into(),
+        Msg::BudgetReached => "Budget erreicht".into(),
+        Msg::BudgetReachedRepeat => "Budget erreicht, zum Senden wiederholen".into(),
+        Msg::FastModeOn => "Schnellmodus an".into(),
+        Msg::FastModeOff => "Schnellmodus aus".into(),
+        Msg::Paused => "Pausiert".into(),
+        Msg::Resumed => "Fortgesetzt".into(),
+        Msg::QuickTranslateFailed => "Schnellübersetzung fehlgeschlagen".into(),
+        Msg::SettingsFailed => "Einstellungen fehlgeschlagen".into(),
+        Msg::CostRepeat(cost) => format!("~{}, zum Senden wiederholen", cost),
+        Msg::TranslatingCost(cost) => format!("Übersetze ~{}", cost),
+        Msg::UpdateAvailable(version) => format!("ThirdSpace {} ist verfügbar", version),
+        Msg::Updating => "Aktualisiere...".into(),
+        Msg::UpToDate => "Aktuell".into(),
+        Msg::UpdateCheckFailed => "Update-Prüfung fehlgeschlagen".into(),
+        Msg::UpdateFailed => "Update fehlgeschlagen".into(),
+        Msg::CaptureFailed => "Aufnahme fehlgeschlagen".into(),
+        Msg::SelectionTooSmall => "Auswahl zu klein".into(),
+        Msg::OcrFailed => "Texterkennung fehlgeschlagen".into(),
+        Msg::Listening => "Höre zu...".into(),
+        Msg::MicrophoneFailed => "Mikrofonfehler".into(),
+        Msg::TooShort => "Zu kurz".into(),
+        Msg::Transcribing => "Transkribiere...".into(),
+        Msg::TranscriptionFailed => "Transkription fehlgeschlagen".into(),
+        Msg::TypingFailed => "Eingabe fehlgeschlagen".into(),
+        Msg::QueueDone => "Warteschlange erledigt".into(),
+        Msg::QueueLeft(count) => format!("Warteschlange, noch {}", count),
+        Msg::CreditsLow => "Guthaben niedrig".into(),
+        Msg::AllowInputMonitoring => "Eingabeüberwachung erlauben".into(),
+        Msg::DoubleTapUnavailable => "Doppeltippen nicht verfügbar".into(),
+        Msg::Ready(hotkey) => format!("Bereit: {}", hotkey),
+
+        Msg::MenuTranslate => "Übersetzen".into(),
+        Msg::MenuTranslateSelection => "Auswahl übersetzen".into(),
+        Msg::MenuTranslateCommit => "Commit-Nachricht übersetzen".into(),
+        Msg::MenuTranslateFile => "Datei übersetzen…".into(),
+        Msg::MenuCaptureRegion => "Bereich erfassen…".into(),
+        Msg::MenuQuickTranslate => "Schnellübersetzung…".into(),
+        Msg::MenuTranslateRecent => "Zuletzt kopiert übersetzen".into(),
+        Msg::MenuPinned => "Angeheftet".into(),
+        Msg::MenuUndo => "Übersetzung rückgängig".into(),
+        Msg::MenuSummarize => "Zusammenfassen".into(),
+        Msg::MenuProofread => "Korrekturlesen".into(),
+        Msg::MenuRewrite => "Umschreiben".into(),
+        Msg::MenuTone(Tone::Formal) => "Förmlicher".into(),
+        Msg::MenuTone(Tone::Concise) => "Knapper".into(),
+        Msg::MenuTone(Tone::Friendly) => "Freundlicher".into(),
+        Msg::MenuActions => "Aktionen".into(),
+        Msg::MenuTargetLanguage => "Zielsprache".into(),
+        Msg::MenuNextLanguage => "Nächste Sprache".into(),
+        Msg::MenuFastMode => "Schnellmodus".into(),
+        Msg::MenuPause => "ThirdSpace pausieren".into(),
+        Msg::MenuResume => "ThirdSpace fortsetzen".into(),
+        Msg::MenuCheckUpdates => "Nach Updates suchen…".into(),
+        Msg::MenuSettings => "Einstellungen".into(),
+        Msg::MenuQuit => "Beenden".into(),
+        Msg::TooltipLastDone(secs) => format!("Letzte Übersetzung: fertig in {} s", secs),
+        Msg::TooltipLastFailed(secs) => {
+            format!("Letzte Übersetzung: nach {} s fehlgeschlagen", secs)
+        }
+
+        Msg::WindowResult => "ThirdSpace Ergebnis".into(),
+        Msg::WindowReview => "ThirdSpace Prüfen".into(),
+        Msg::WindowCandidates => "ThirdSpace Vorschläge".into(),
+        Msg::WindowQuickTranslate => "ThirdSpace Schnellübersetzung".into(),
+        Msg::WindowSettings => "ThirdSpace Einstellungen".into(),
+        Msg::DialogTranslateFile => "Datei übersetzen".into(),
+        Msg::DialogQuit => "ThirdSpace beenden?".into(),
+        Msg::DialogDeepLink => "Link übersetzen?".into(),
+        Msg::DeepLinkConfirm(text) => format!(
+            "Ein Link möchte ThirdSpace übersetzen lassen:\n\n{}\n\nDas Ergebnis erscheint im Ergebnisfenster.",
+            text
+        ),
+        Msg::QuitPending(running, queued) => format!(
+            "Laufend: {}. Warten auf Verbindung: {}.\n\nBeim sofortigen Beenden gehen ihre Ergebnisse verloren.",
+            running, queued
+        ),
+        Msg::QuitWhenDone => "Danach beenden".into(),
+        Msg::QuitNow => "Jetzt beenden".into(),
+        Msg::KeepRunning => "Weiterlaufen".into(),
+        Msg::FilterText => "Text".into(),
+        Msg::FilterSubtitles => "Untertitel".into(),
+
+        Msg::AllowAccessibility => "Bedienungshilfen erlauben".into(),
+        Msg::ApiKeyEmpty => "API-Schlüssel ist leer".into(),
+        Msg::KeyAccepted => "Schlüssel akzeptiert".into(),
+        Msg::KeyAcceptedAs(label) => format!("Schlüssel akzeptiert ({})", label),
+        Msg::ApiKeyNotConfigured => "API-Schlüssel nicht eingerichtet".into(),
+        Msg::CouldNotReach(e) => format!("OpenRouter nicht erreichbar: {}", e),
+        Msg::TargetLanguageNotSet => "Keine Zielsprache festgelegt".into(),
+        Msg::EnterApiKey => "Geben Sie Ihren OpenRouter-API-Schlüssel ein".into(),
+        Msg::PickModel => "Wählen Sie ein Modell".into(),
+        Msg::NoCaptureInProgress => "Keine Aufnahme aktiv".into(),
+        Msg::SelectionIsTooSmall => "Die Auswahl ist zu klein".into(),
+        Msg::RecordingTooShort => "Die Aufnahme ist zu kurz".into(),
+        Msg::KeyNotRecognized => {
+            "OpenRouter erkennt diesen Schlüssel nicht; er ist vielleicht falsch oder widerrufen"
+                .into()
+        }
+        Msg::KeyDisabled => "Dieser Schlüssel ist deaktiviert oder darf die API nicht nutzen".into(),
+        Msg::TextEmpty => "Text ist leer".into(),
+        Msg::ClipboardIsEmpty => "Zwischenablage ist leer".into(),
+        Msg::Quitting => "ThirdSpace wird beendet".into(),
+        Msg::NoReviewWaiting => "Keine Übersetzung wartet auf Prüfung".into(),
+        Msg::NoInputWaiting => "Keine Eingabe wartet auf Bestätigung".into(),
+        Msg::UnknownAction(name) => format!("Unbekannte Aktion: {}", name),
+        Msg::NoClipboardEntry(index) => format!("Kein Eintrag {} im Zwischenablage-Verlauf", index),
+        Msg::NoCandidate(index) => format!("Kein Vorschlag {}", index),
+        Msg::NoTranslationWithId(id) => format!("Keine Übersetzung mit ID {}", id),
+        Msg::InvalidLogFilter(e) => format!("Ungültiger Logfilter: {}", e),
+        Msg::LoggingNotInitialized => "Logging ist nicht initialisiert".into(),
+        Msg::CostOverLimit(cost, limit) => {
+            format!(
+                "Geschätzte Kosten {} liegen über dem Limit von {}",
+                cost, limit
+            )
+        }
+        Msg::BudgetExceeded(cost, tokens, month) => format!(
+            "Monatsbudget erreicht ({} und {} Tokens in {} verbraucht)",
+            cost, tokens, month
+        ),
+        Msg::InputTooLong(length, limit) => format!(
+            "Die Eingabe hat {} Zeichen, mehr als das Limit von {} Zeichen",
+            length, limit
+        ),
+        Msg::RequestsInProgress(count) => format!("Bereits {} Anfragen in Bearbeitung", count),
+        Msg::QueuedUntilReachable(e) => {
+            format!("Eingereiht, bis OpenRouter erreichbar ist: {}", e)
+        }
+
+        Msg::ApiKeyMissing => "API-Schlüssel fehlt".into(),
+        Msg::NotAnOpenRouterKey => {
+            "Sieht nicht nach einem OpenRouter-Schlüssel aus (erwartet sk-or-…, ohne Leerzeichen)"
+                .into()
+        }
+        Msg::TargetLanguageEmpty => "Zielsprache ist leer".into(),
+        Msg::UnknownLanguage(name) => format!("Unbekannte Sprache: {}", name),
+        Msg::UnknownLanguages(names) => format!("Unbekannte Sprachen: {}", names),
+        Msg::ModelMissing => "Modell fehlt".into(),
+        Msg::UnknownModel(model) => format!("Unbekanntes Modell: {}", model),
+        Msg::CaBundleInvalid(error) => format!("CA-Zertifikate unbrauchbar: {}", error),
+        Msg::InvalidUrl => "Vollständige URL eingeben, z. B. https://example.com".into(),
+        Msg::LockedByPolicy => "Von Ihrem Administrator festgelegt".into(),
+        Msg::ModelNotAllowed(model) => format!("{} ist von Ihrem Administrator nicht erlaubt", model),
+        Msg::TemperatureRange => "Temperature muss zwischen 0 und 2 liegen".into(),
+        Msg::TopPRange => "Top P muss über 0 und höchstens 1 sein".into(),
+        Msg::MaxTokensMin => "Max. Tokens muss mindestens 1 sein".into(),
+        Msg::MaxContextPairs(max) => format!("Höchstens {} frühere Übersetzungen", max),
+        Msg::ContextNeedsHistory => "Gesprächskontext braucht den Übersetzungsverlauf".into(),
+        Msg::ConcurrentRange(max) => {
+            format!(
+                "Gleichzeitige Anfragen müssen zwischen 1 und {} liegen",
+                max
+            )
+        }
+        Msg::MaxCandidates(max) => format!("Höchstens {} Vorschläge", max),
+        Msg::ReasoningBudgetMin => "Reasoning-Budget muss mindestens 1 Token sein".into(),
+        Msg::AppRuleNoApp => "App-Regel ohne App-Namen".into(),
+        Msg::InvalidPattern(e) => format!("Ungültiges Muster: {}", e),
+
+        Msg::HotkeyMissing => "Tastenkürzel fehlt".into(),
+        Msg::HotkeyInUse(hotkey, other) => {
+            format!("{} wird bereits von {} verwendet", hotkey, other)
+        }
+        Msg::HotkeyUsedElsewhere(hotkey) => {
+            format!(
+                "{} wird bereits von einer anderen Anwendung verwendet",
+                hotkey
+            )
+        }
+        Msg::HotkeyOwn => "Von ThirdSpace registriert".into(),
+        Msg::HotkeyAvailable => "Verfügbar".into(),
+        Msg::HotkeyRegisterFailed(e) => {
+            format!("Tastenkürzel konnte nicht registriert werden: {}", e)
+        }
+        Msg::InvalidHotkey(name, e) => format!("Ungültiges Tastenkürzel für {}: {}", name, e),
+        Msg::UnknownModifier(modifier) => format!("Unbekannte Zusatztaste: {}", modifier),
+        Msg::UnknownKey(key) => format!("Unbekannte Taste: {}", key),
+        Msg::UnknownFunctionKey(key) => format!("Unbekannte Funktionstaste: {}", key),
+        Msg::MultipleKeys => "Mehrere Tasten angegeben".into(),
+        Msg::NoKey => "Keine Taste angegeben".into(),
+    }
+}
@@ -0,0 +1,253 @@
+use crate::config;
+use crate::languages;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Serializes appends and compaction of the journal file.
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A finished translation, stored one JSON object per line in `translations.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    /// `<finished_at millis>-<request id>`, unique across sessions.
+    pub id: String,
+    pub action: String,
+    pub source: String,
+    pub translation: String,
+    pub model: String,
+    pub target_language: String,
+    pub duration_ms: u64,
+    /// Unix seconds when the result was written to the clipboard.
+    pub finished_at: u64,
+    /// Pinned records are never dropped by compaction.
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Narrows `search`; unset fields match everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Filters {
+    pub target_language: Option<String>,
+    pub model: Option<String>,
+    /// Inclusive lower bound on `finished_at`, in Unix seconds.
+    pub from: Option<u64>,
+    /// Inclusive upper bound on `finished_at`, in Unix seconds.
+    pub to: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+/// File formats for `export`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+}
+
+/// Inclusive bounds on `finished_at`, in Unix seconds.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub struct DateRange {
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+}
+
+const CSV_HEADER: &str =
+    "id,finished_at,action,model,target_language,duration_ms,source,translation";
+
+pub fn path() -> Result<PathBuf> {
+    Ok(config::app_dir()?.join("translations.jsonl"))
+}
+
+/// Appends a record, then drops the oldest unpinned ones beyond `capacity`.
+pub fn append(record: &Record, capacity: usize) -> Result<()> {
+    let _guard = FILE_LOCK.lock().unwrap();
+    let path = path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).context("create app directory")?;
+    }
+    let mut line = serde_json::to_string(record).context("serialize translation record")?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .context("append translations.jsonl")?;
+
+    let records = read(&path)?;
+    let unpinned = records.iter().filter(|record| !record.pinned).count();
+    if unpinned > capacity {
+        let mut excess = unpinned - capacity;
+        let dropped = excess;
+        let kept: Vec<Record> = records
+            .into_iter()
+            .filter(|record| {
+                if excess > 0 && !record.pinned {
+                    excess -= 1;
+                    return false;
+                }
+                true
+            })
+            .collect();
+        write(&path, &kept)?;
+        debug!(dropped, "Translation journal compacted");
+    }
+    Ok(())
+}
+
+/// Pins or unpins the record with `id`. Returns `None` when no record has that id.
+pub fn set_pinned(id: &str, pinned: bool) -> Result<Option<Record>> {
+    let _guard = FILE_LOCK.lock().unwrap();
+    let path = path()?;
+    let mut records = read(&path)?;
+    let Some(record) = records.iter_mut().find(|record| record.id == id) else {
+        return Ok(None);
+    };
+    record.pinned = pinned;
+    let updated = record.clone();
+    write(&path, &records)?;
+    Ok(Some(updated))
+}
+
+/// Pinned records, newest first.
+pub fn pinned() -> Result<Vec<Record>> {
+    Ok(load()?
+        .into_iter()
+        .rev()
+        .filter(|record| record.pinned)
+        .collect())
+}
+
+/// All records, oldest first. Lines that fail to parse are skipped.
+pub fn load() -> Result<Vec<Record>> {
+    let _guard = FILE_LOCK.lock().unwrap();
+    read(&path()?)
+}
+
+fn read(path: &Path) -> Result<Vec<Record>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(path).context("read translations.jsonl")?;
+    let mut records = Vec::new();
+    for (idx, line) in data.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(record) => records.push(record),
+            Err(e) => warn!(line = idx + 1, error = %e, "Skipping malformed journal line"),
+        }
+    }
+    Ok(records)
+}
+
+fn write(path: &Path, records: &[Record]) -> Result<()> {
+    let mut data = String::new();
+    for record in records {
+        data.push_str(&serde_json::to_string(record).context("serialize translation record")?);
+        data.push('\n');
+    }
+    let tmp = path.with_extension("jsonl.tmp");
+    fs::write(&tmp, data).context("write translations.jsonl")?;
+    fs::rename(&tmp, path).context("replace translations.jsonl")?;
+    Ok(())
+}
+
+/// Records whose source or translation contains every whitespace-separated term of
+/// `query` (case-insensitive), newest first. An empty query matches all records.
+pub fn search(records: Vec<Record>, query: &str, filters: &Filters) -> Vec<Record> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    let language = filters
+        .target_language
+        .as_deref()
+        .map(languages::normalize)
+        .filter(|language| !language.is_empty());
+    let model = filters
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|model| !model.is_empty());
+
+    records
+        .into_iter()
+        .rev()
+        .filter(|record| {
+            language.as_ref().is_none_or(|language| {
+                languages::normalize(&record.target_language).eq_ignore_ascii_case(language)
+            })
+        })
+        .filter(|record| model.is_none_or(|model| record.model.eq_ignore_ascii_case(model)))
+        .filter(|record| filters.from.is_none_or(|from| record.finished_at >= from))
+        .filter(|record| filters.to.is_none_or(|to| record.finished_at <= to))
+        .filter(|record| {
+            if terms.is_empty() {
+                return true;
+            }
+            let haystack = format!(
+                "{}\n{}",
+                record.source.to_lowercase(),
+                record.translation.to_lowercase()
+            );
+            terms.iter().all(|term| haystack.contains(term.as_str()))
+        })
+        .take(filters.limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// Writes the records within `range`, oldest first, to `path`. Returns how many were written.
+pub fn export(path: &Path, format: ExportFormat, range: DateRange) -> Result<usize> {
+    let records: Vec<Record> = load()?
+        .into_iter()
+        .filter(|record| range.from.is_none_or(|from| record.finished_at >= from))
+        .filter(|record| range.to.is_none_or(|to| record.finished_at <= to))
+        .collect();
+
+    let mut data = String::new();
+    match format {
+        ExportFormat::Csv => {
+            data.push_str(CSV_HEADER);
+            data.push_str("\r\n");
+            for record in &records {
+                let fields = [
+                    csv_field(&record.id),
+                    record.finished_at.to_string(),
+                    csv_field(&record.action),
+                    csv_field(&record.model),
+                    csv_field(&record.target_language),
+                    record.duration_ms.to_string(),
+                    csv_field(&record.source),
+                    csv_field(&record.translation),
+                ];
+                data.push_str(&fields.join(","));
+                data.push_str("\r\n");
+            }
+        }
+        ExportFormat::Jsonl => {
+            for record in &records {
+                data.push_str(
+                    &serde_json::to_string(record).context("serialize translation record")?,
+                );
+                data.push('\n');
+            }
+        }
+    }
+    fs::write(path, data).with_context(|| format!("write {}", path.display()))?;
+    Ok(records.len())
+}
+
+/// Quotes a field per RFC 4180 when it contains a delimiter, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
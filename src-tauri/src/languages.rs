@@ -0,0 +1,113 @@
+use serde::Serialize;
+
+/// A target language the prompts are known to work with.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Language {
+    /// ISO 639-1 code, with a script or region subtag where the name alone is ambiguous.
+    pub code: &'static str,
+    /// English name; this is what gets stored in the config and sent in prompts.
+    pub name: &'static str,
+    pub native_name: &'static str,
+    /// Other spellings accepted by `find`.
+    #[serde(skip)]
+    aliases: &'static [&'static str],
+}
+
+const fn language(
+    code: &'static str,
+    name: &'static str,
+    native_name: &'static str,
+    aliases: &'static [&'static str],
+) -> Language {
+    Language {
+        code,
+        name,
+        native_name,
+        aliases,
+    }
+}
+
+pub const LANGUAGES: &[Language] = &[
+    language("en", "English", "English", &[]),
+    language(
+        "zh-Hans",
+        "Chinese",
+        "简体中文",
+        &[
+            "Simplified Chinese",
+            "Chinese (Simplified)",
+            "Mandarin",
+            "zh",
+            "zh-CN",
+        ],
+    ),
+    language(
+        "zh-Hant",
+        "Traditional Chinese",
+        "繁體中文",
+        &["Chinese (Traditional)", "zh-TW", "zh-HK"],
+    ),
+    language("ja", "Japanese", "日本語", &[]),
+    language("ko", "Korean", "한국어", &[]),
+    language("es", "Spanish", "Español", &[]),
+    language("fr", "French", "Français", &[]),
+    language("de", "German", "Deutsch", &[]),
+    language("it", "Italian", "Italiano", &[]),
+    language("pt", "Portuguese", "Português", &["pt-PT"]),
+    language(
+        "pt-BR",
+        "Brazilian Portuguese",
+        "Português do Brasil",
+        &["Portuguese (Brazil)"],
+    ),
+    language("nl", "Dutch", "Nederlands", &[]),
+    language("ru", "Russian", "Русский", &[]),
+    language("uk", "Ukrainian", "Українська", &[]),
+    language("pl", "Polish", "Polski", &[]),
+    language("cs", "Czech", "Čeština", &[]),
+    language("sv", "Swedish", "Svenska", &[]),
+    language("da", "Danish", "Dansk", &[]),
+    language("no", "Norwegian", "Norsk", &["nb", "Norwegian Bokmål"]),
+    language("fi", "Finnish", "Suomi", &[]),
+    language("el", "Greek", "Ελληνικά", &[]),
+    language("tr", "Turkish", "Türkçe", &[]),
+    language("hu", "Hungarian", "Magyar", &[]),
+    language("ro", "Romanian", "Română", &[]),
+    language("bg", "Bulgarian", "Български", &[]),
+    language("ar", "Arabic", "العربية", &[]),
+    language("he", "Hebrew", "עברית", &[]),
+    language("fa", "Persian", "فارسی", &["Farsi"]),
+    language("hi", "Hindi", "हिन्दी", &[]),
+    language("bn", "Bengali", "বাংলা", &["Bangla"]),
+    language("ur", "Urdu", "اردو", &[]),
+    language("th", "Thai", "ไทย", &[]),
+    language("vi", "Vietnamese", "Tiếng Việt", &[]),
+    language("id", "Indonesian", "Bahasa Indonesia", &[]),
+    language("ms", "Malay", "Bahasa Melayu", &[]),
+    language("fil", "Filipino", "Filipino", &["Tagalog", "tl"]),
+    language("sw", "Swahili", "Kiswahili", &[]),
+];
+
+/// Looks a language up by English name, native name, code or alias, ignoring case and
+/// surrounding whitespace.
+pub fn find(input: &str) -> Option<&'static Language> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return None;
+    }
+    LANGUAGES.iter().find(|language| {
+        [language.code, language.name, language.native_name]
+            .iter()
+            .chain(language.aliases)
+            .any(|candidate| candidate.to_lowercase() == input)
+    })
+}
+
+/// The catalog's English name for `input`, or `input` trimmed when it is not in the
+/// catalog.
+pub fn normalize(input: &str) -> String {
+    match find(input) {
+        Some(language) => language.name.to_string(),
+        None => input.trim().to_string(),
+    }
+}
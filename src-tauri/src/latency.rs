@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Weight of the newest sample in the moving average.
+const SMOOTHING: f64 = 0.3;
+
+/// Smoothed per-model response times observed during this session.
+#[derive(Default)]
+pub struct LatencyTracker {
+    averages: Mutex<HashMap<String, f64>>,
+}
+
+impl LatencyTracker {
+    pub fn record(&self, model: &str, duration_ms: u128) {
+        let sample = duration_ms as f64;
+        let mut averages = self.averages.lock().unwrap();
+        averages
+            .entry(model.to_string())
+            .and_modify(|avg| *avg = *avg * (1.0 - SMOOTHING) + sample * SMOOTHING)
+            .or_insert(sample);
+    }
+
+    /// Picks the candidate with the lowest observed latency. Models that were
+    /// never measured rank first, in their configured order, so each one is tried
+    /// once and gets a measurement.
+    pub fn fastest<'a>(&self, candidates: &'a [String]) -> Option<&'a String> {
+        let averages = self.averages.lock().unwrap();
+        candidates
+            .iter()
+            .filter(|model| !model.trim().is_empty())
+            .enumerate()
+            .min_by(|(a_idx, a), (b_idx, b)| {
+                let a_ms = averages.get(a.as_str()).copied().unwrap_or(f64::NEG_INFINITY);
+                let b_ms = averages.get(b.as_str()).copied().unwrap_or(f64::NEG_INFINITY);
+                a_ms.total_cmp(&b_ms).then(a_idx.cmp(b_idx))
+            })
+            .map(|(_, model)| model)
+    }
+}
@@ -1,33 +1,150 @@
+mod audit;
+mod automodel;
+mod bridge;
+mod bundle;
+mod cache;
+mod capture;
+mod chunk;
+mod cli;
+mod clipboard;
 mod config;
+mod copybutton;
+mod diagnostics;
+mod dictation;
+mod doubletap;
+mod error;
+mod estimate;
+mod files;
+mod foreground;
+mod geometry;
+mod history;
+mod hooks;
+mod i18n;
+mod journal;
+mod languages;
+mod latency;
+mod logs;
+mod mask;
+mod metrics;
+#[cfg(feature = "mock")]
+mod mock;
+mod models;
+mod onboarding;
 mod openrouter;
+mod permissions;
+mod policy;
+mod postformat;
 mod prompt;
+mod queue;
+mod redact;
+mod requests;
+mod rtf;
+mod scheduler;
+mod secret;
+mod shutdown;
+mod sound;
+mod subtitle;
+mod telemetry;
+mod toast;
+mod typing;
+mod updater;
+mod usage;
+mod watcher;
 
-use config::Config;
+use arc_swap::ArcSwap;
+use cache::ResultCache;
+use clipboard::{Formatted, Input};
+use config::{
+    BudgetAction, Config, CustomAction, OutputLayout, OutputMode, PostHooks, ReasoningEffort,
+    TrayClick, UpdateChannel,
+};
+use error::CommandError;
+use history::ClipboardHistory;
+use i18n::Msg;
+use latency::LatencyTracker;
+use metrics::Metrics;
+use permissions::Permission;
+use prompt::{ContextPair, DictionaryEntry, Tone};
+use requests::{ActiveRequest, RequestRegistry, MAX_CONCURRENT_REQUESTS};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Mutex;
-use std::time::{Duration, SystemTime};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
     pub id: String,
     pub name: String,
+    /// `None` when OpenRouter reports no fixed price, e.g. for routers.
+    pub pricing: Option<ModelPricing>,
+    /// Tokens the model accepts per request, prompt and completion together.
+    pub context_length: Option<u64>,
+    /// Takes OpenRouter's `reasoning` parameter.
+    #[serde(default)]
+    pub supports_reasoning: bool,
+    /// Takes a `response_format` JSON schema, which `structured_output` needs.
+    #[serde(default)]
+    pub supports_structured_output: bool,
+}
+
+/// USD per token.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub prompt: f64,
+    pub completion: f64,
+}
+
+impl ModelPricing {
+    /// USD for a request with these token counts.
+    pub fn cost(&self, prompt_tokens: u64, completion_tokens: u64) -> f64 {
+        prompt_tokens as f64 * self.prompt + completion_tokens as f64 * self.completion
+    }
 }
 use tauri::{
-    menu::{Menu, MenuItem},
-    tray::TrayIconBuilder,
-    AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder,
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, Wry,
 };
 use tauri_plugin_autostart::ManagerExt;
-use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
-use tracing::{debug, error, info, Instrument};
+use toast::{show_toast, ToastManager};
+use tracing::{debug, error, info, warn, Instrument};
+use tracing_subscriber::{
+    fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry,
+};
 
-const TOAST_DURATION_MS: u64 = 2200;
-const LOG_RETENTION_DAYS: u64 = 14;
-const LOG_FILE_PREFIX: &str = "thirdspace.log";
 const DEFAULT_LOG_FILTER: &str = "info,tauri=warn,reqwest=warn,hyper=warn";
+const TRAY_ID: &str = "main";
+const LANGUAGE_MENU_PREFIX: &str = "language:";
+const REWRITE_MENU_PREFIX: &str = "rewrite:";
+const CUSTOM_ACTION_MENU_PREFIX: &str = "action:";
+const HISTORY_MENU_PREFIX: &str = "history:";
+const PINNED_MENU_PREFIX: &str = "pinned:";
+const DEEP_LINK_SCHEME: &str = "thirdspace";
+/// Longest part of a deep link's text shown when asking to translate it.
+const DEEP_LINK_PREVIEW_CHARS: usize = 200;
+/// Longest clipboard preview shown in the History menu.
+const HISTORY_PREVIEW_CHARS: usize = 40;
+/// Swaps the active log filter at runtime; set once logging is initialized.
+static LOG_FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+/// Flushes the log file writer when dropped. The event loop never returns from `run`, so
+/// the guard is kept here and dropped on `RunEvent::Exit`.
+static LOG_GUARD: Mutex<Option<tracing_appender::non_blocking::WorkerGuard>> = Mutex::new(None);
+
+/// How often a running request reports its elapsed time.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+/// Smallest settings window that still fits a full form row.
+const SETTINGS_MIN_WIDTH: f64 = 420.0;
+const SETTINGS_MIN_HEIGHT: f64 = 440.0;
+/// Upper bound for `translation_candidates`; each one is a separate request.
+const MAX_CANDIDATES: usize = 5;
+/// Upper bound for `conversation_context`, which keeps prompts from growing unbounded.
+const MAX_CONTEXT_PAIRS: usize = 10;
+/// Older translations belong to a different conversation and are not sent as context.
+const CONTEXT_MAX_AGE: Duration = Duration::from_secs(15 * 60);
 
 static REQUEST_SEQ: AtomicU64 = AtomicU64::new(1);
 
@@ -35,274 +152,3360 @@ fn next_request_id() -> u64 {
     REQUEST_SEQ.fetch_add(1, Ordering::Relaxed)
 }
 
-fn urlencoding(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
-            ' ' => "%20".to_string(),
-            _ => format!("%{:02X}", c as u8),
+/// Clipboard pipelines that can be triggered from the tray, hotkey or frontend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Translate,
+    CommitMessage,
+    Summarize,
+    Proofread,
+    Rewrite(Tone),
+    /// Index into `Config::actions`.
+    Custom(usize),
+}
+
+impl Action {
+    fn name(self) -> &'static str {
+        match self {
+            Action::Translate => "translate",
+            Action::CommitMessage => "commit_message",
+            Action::Summarize => "summarize",
+            Action::Proofread => "proofread",
+            Action::Rewrite(_) => "rewrite",
+            Action::Custom(_) => "custom",
+        }
+    }
+
+    fn custom(self, config: &Config) -> Option<&CustomAction> {
+        match self {
+            Action::Custom(idx) => config.actions.get(idx),
+            _ => None,
+        }
+    }
+
+    /// Applies per-action overrides before the request is logged and sent.
+    fn prepare(self, config: &mut Config) {
+        let model = match self.custom(config) {
+            Some(custom) if !custom.model.trim().is_empty() => custom.model.trim().to_string(),
+            _ => return,
+        };
+        config.model = model;
+    }
+
+    /// Proofreading and rewriting stay in the input's language; custom actions only need
+    /// a target when their prompt references it. Everything else targets
+    /// `Config::target_language`.
+    fn needs_target_language(self, config: &Config) -> bool {
+        match self {
+            Action::Proofread | Action::Rewrite(_) => false,
+            Action::Custom(_) => self
+                .custom(config)
+                .is_some_and(|custom| custom.prompt.contains("{target_language}")),
+            _ => true,
+        }
+    }
+
+    async fn run(
+        self,
+        client: &reqwest::Client,
+        config: &Config,
+        input: &Input,
+        context: &[ContextPair],
+    ) -> anyhow::Result<ActionOutput> {
+        let text = input.text.as_str();
+        let formatted = input
+            .formatted
+            .as_ref()
+            .filter(|_| config.preserve_formatting);
+        match self {
+            Action::Translate if config.multi_target_languages.len() > 1 => {
+                translate_multi(client, config, text).await
+            }
+            Action::Translate if config.translation_candidates > 1 => {
+                translate_candidates(client, config, text).await
+            }
+            Action::Translate
+                if config.dictionary_lookup
+                    && prompt::is_dictionary_candidate(text, config.dictionary_max_words) =>
+            {
+                let entry = openrouter::lookup(client, config, text).await?;
+                Ok(ActionOutput {
+                    text: entry.translation.clone(),
+                    romanization: None,
+                    dictionary: Some(entry),
+                    formatted: None,
+                    targets: Vec::new(),
+                    candidates: Vec::new(),
+                })
+            }
+            Action::Translate if formatted.is_some() => {
+                translate_formatted(client, config, text, formatted).await
+            }
+            // Long inputs go through the chunked plain translation instead
+            Action::Translate
+                if config.romanization && !chunk::needs_split(text, config.chunk_max_chars) =>
+            {
+                let romanized = openrouter::translate_romanized(client, config, text).await?;
+                Ok(ActionOutput {
+                    text: romanized.translation,
+                    romanization: romanized.romanization,
+                    dictionary: None,
+                    formatted: None,
+                    targets: Vec::new(),
+                    candidates: Vec::new(),
+                })
+            }
+            Action::Translate => Ok(openrouter::translate_in_context(
+                client, config, text, context,
+            )
+            .await?
+            .into()),
+            Action::CommitMessage => Ok(openrouter::translate_commit_message(client, config, text)
+                .await?
+                .into()),
+            Action::Summarize => Ok(openrouter::summarize(client, config, text).await?.into()),
+            Action::Proofread => Ok(openrouter::proofread(client, config, text).await?.into()),
+            Action::Rewrite(tone) => Ok(openrouter::rewrite(client, config, text, tone)
+                .await?
+                .into()),
+            Action::Custom(idx) => {
+                let custom = self
+                    .custom(config)
+                    .ok_or_else(|| anyhow::anyhow!("Custom action {} no longer exists", idx))?;
+                Ok(openrouter::run_custom(client, config, text, custom)
+                    .await?
+                    .into())
+            }
+        }
+    }
+}
+
+/// Where `execute_action` puts a successful result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delivery {
+    /// As `output_mode` says: onto the clipboard or typed into the focused field.
+    Output,
+    /// Only into the result window, for requests the user did not just trigger, whose
+    /// result must not replace what they copied since.
+    Window,
+}
+
+/// App commands bound to hotkeys alongside the clipboard actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Utility {
+    CycleLanguage,
+    Undo,
+    QuickTranslate,
+    CaptureRegion,
+    /// Push-to-talk: records while held, see `dictation`.
+    Dictate,
+    /// Stays registered while paused, so it can resume.
+    TogglePause,
+    TranslateSelection,
+}
+
+impl Utility {
+    fn name(self) -> &'static str {
+        match self {
+            Utility::CycleLanguage => "cycle_language",
+            Utility::Undo => "undo",
+            Utility::QuickTranslate => "quick_translate",
+            Utility::CaptureRegion => "capture_region",
+            Utility::Dictate => "dictate",
+            Utility::TogglePause => "toggle_pause",
+            Utility::TranslateSelection => "translate_selection",
+        }
+    }
+
+    fn run(self, app: &AppHandle) {
+        match self {
+            Utility::CycleLanguage => cycle_target_language(app),
+            Utility::Undo => {
+                let _ = undo_last_translation(app);
+            }
+            Utility::QuickTranslate => show_quick_translate(app),
+            Utility::CaptureRegion => capture::start(app),
+            Utility::Dictate => dictation::start(app),
+            Utility::TogglePause => toggle_pause(app),
+            Utility::TranslateSelection => spawn_selection_action(app, Action::Translate),
+        }
+    }
+}
+
+/// Translates the rich clipboard flavor, falling back to the plain text when the markup
+/// cannot be carried through.
+async fn translate_formatted(
+    client: &reqwest::Client,
+    config: &Config,
+    text: &str,
+    formatted: Option<&Formatted>,
+) -> anyhow::Result<ActionOutput> {
+    if let Some(formatted) = formatted {
+        match openrouter::translate_formatted(client, config, formatted).await {
+            Ok((translated, plain)) => {
+                return Ok(ActionOutput {
+                    text: plain,
+                    romanization: None,
+                    dictionary: None,
+                    formatted: Some(translated),
+                    targets: Vec::new(),
+                    candidates: Vec::new(),
+                })
+            }
+            Err(e) => warn!(error = %e, "Formatted translation failed, using plain text"),
+        }
+    }
+    Ok(openrouter::translate(client, config, text).await?.into())
+}
+
+/// Translates into every `multi_target_languages` entry concurrently. The clipboard gets
+/// one labeled block per language; languages that failed are left out unless all did.
+async fn translate_multi(
+    client: &reqwest::Client,
+    config: &Config,
+    text: &str,
+) -> anyhow::Result<ActionOutput> {
+    let languages: Vec<&String> = config
+        .multi_target_languages
+        .iter()
+        .filter(|lang| !lang.trim().is_empty())
+        .collect();
+    let results = futures_util::future::join_all(languages.iter().map(|language| {
+        let mut config = config.clone();
+        config.target_language = language.to_string();
+        async move { openrouter::translate(client, &config, text).await }
+    }))
+    .await;
+
+    let mut targets = Vec::with_capacity(languages.len());
+    let mut first_error = None;
+    for (language, result) in languages.into_iter().zip(results) {
+        match result {
+            Ok(text) => targets.push(TargetTranslation {
+                language: language.clone(),
+                text,
+            }),
+            Err(e) => {
+                warn!(error = %e, language = %language, "Target language failed");
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    if targets.is_empty() {
+        return Err(first_error.unwrap_or_else(|| anyhow::anyhow!("No target languages")));
+    }
+    let text = targets
+        .iter()
+        .map(|target| format!("[{}]\n{}", target.language, target.text))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Ok(ActionOutput {
+        text,
+        romanization: None,
+        dictionary: None,
+        formatted: None,
+        targets,
+        candidates: Vec::new(),
+    })
+}
+
+/// Requests `translation_candidates` translations concurrently for the chooser popup.
+/// Identical answers are merged; the first candidate is what the clipboard gets.
+async fn translate_candidates(
+    client: &reqwest::Client,
+    config: &Config,
+    text: &str,
+) -> anyhow::Result<ActionOutput> {
+    let count = config.translation_candidates.min(MAX_CANDIDATES);
+    let results = futures_util::future::join_all(
+        (0..count).map(|_| openrouter::translate(client, config, text)),
+    )
+    .await;
+
+    let mut candidates: Vec<String> = Vec::with_capacity(count);
+    let mut first_error = None;
+    for result in results {
+        match result {
+            Ok(candidate) => {
+                if !candidates.contains(&candidate) {
+                    candidates.push(candidate);
+                }
+            }
+            Err(e) => {
+                warn!(error = %e, "Candidate request failed");
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    let Some(first) = candidates.first().cloned() else {
+        return Err(first_error.unwrap_or_else(|| anyhow::anyhow!("No candidates requested")));
+    };
+    info!(
+        requested = count,
+        distinct = candidates.len(),
+        "Candidates received"
+    );
+    Ok(ActionOutput {
+        candidates,
+        ..ActionOutput::from(first)
+    })
+}
+
+/// What an action produced: the text written to the clipboard plus any
+/// extra detail that only the result window shows.
+#[derive(Clone)]
+struct ActionOutput {
+    text: String,
+    romanization: Option<String>,
+    dictionary: Option<DictionaryEntry>,
+    /// Rich flavor written to the clipboard next to `text`.
+    formatted: Option<Formatted>,
+    /// Per-language results of a multi-target translation; `text` combines them.
+    targets: Vec<TargetTranslation>,
+    /// Alternative translations offered in the chooser popup; `text` is the first.
+    candidates: Vec<String>,
+}
+
+impl ActionOutput {
+    fn has_details(&self) -> bool {
+        self.romanization.is_some() || self.dictionary.is_some() || !self.targets.is_empty()
+    }
+
+    /// Runs the post-processing hooks on every variant that can be delivered: the text,
+    /// each candidate and each target language. Hooks cannot rewrite markup safely, so the
+    /// rich flavor is dropped once they change the text.
+    async fn post_process(mut self, hooks: &PostHooks) -> Self {
+        let text = hooks::apply(hooks, self.text.clone()).await;
+        if text != self.text && self.formatted.take().is_some() {
+            debug!("Formatted result dropped, post-processing changed its text");
+        }
+        self.text = text;
+        for candidate in &mut self.candidates {
+            *candidate = hooks::apply(hooks, std::mem::take(candidate)).await;
+        }
+        for target in &mut self.targets {
+            target.text = hooks::apply(hooks, std::mem::take(&mut target.text)).await;
+        }
+        self
+    }
+}
+
+/// Requests with equal keys get the same answer from `AppState::results`.
+#[derive(PartialEq)]
+struct CacheKey {
+    action: Action,
+    model: String,
+    target_language: String,
+    multi_target_languages: Vec<String>,
+    translation_tone: Option<Tone>,
+    text: String,
+    formatted: Option<Formatted>,
+}
+
+impl CacheKey {
+    fn new(action: Action, config: &Config, input: &Input) -> Self {
+        Self {
+            action,
+            model: config.model.clone(),
+            target_language: config.target_language.clone(),
+            multi_target_languages: config.multi_target_languages.clone(),
+            translation_tone: config.translation_tone,
+            text: input.text.clone(),
+            formatted: input.formatted.clone(),
+        }
+    }
+}
+
+/// One language of a multi-target translation.
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetTranslation {
+    pub language: String,
+    pub text: String,
+}
+
+impl From<String> for ActionOutput {
+    fn from(text: String) -> Self {
+        Self {
+            text,
+            romanization: None,
+            dictionary: None,
+            formatted: None,
+            targets: Vec::new(),
+            candidates: Vec::new(),
+        }
+    }
+}
+
+/// Payload for the result window.
+#[derive(Debug, Clone, Serialize)]
+pub struct TranslationResult {
+    pub source: String,
+    pub translation: String,
+    pub romanization: Option<String>,
+    pub dictionary: Option<DictionaryEntry>,
+    /// Per-language sections of a multi-target translation; empty otherwise.
+    pub translations: Vec<TargetTranslation>,
+}
+
+/// The most recent successful action, for side-by-side comparison.
+#[derive(Debug, Clone, Serialize)]
+pub struct LastTranslation {
+    pub request_id: u64,
+    pub action: &'static str,
+    pub source: String,
+    pub translation: String,
+    pub model: String,
+    pub target_language: String,
+    pub duration_ms: u64,
+    /// Unix seconds when the result was written to the clipboard.
+    pub finished_at: u64,
+    /// Source and translation paragraphs paired up; a single pair when the counts differ.
+    pub paragraphs: Vec<ParagraphPair>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RequestOutcome {
+    ok: bool,
+    duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParagraphPair {
+    pub source: String,
+    pub translation: String,
+}
+
+/// Appends a finished translation to the searchable journal, unless it is disabled.
+fn journal_translation(config: &Config, last: &LastTranslation, finished_ms: u128) {
+    if config.translation_history_size == 0 {
+        return;
+    }
+    let record = journal::Record {
+        id: format!("{}-{}", finished_ms, last.request_id),
+        action: last.action.to_string(),
+        source: last.source.clone(),
+        translation: last.translation.clone(),
+        model: last.model.clone(),
+        target_language: last.target_language.clone(),
+        duration_ms: last.duration_ms,
+        finished_at: last.finished_at,
+        pinned: false,
+    };
+    if let Err(e) = journal::append(&record, config.translation_history_size) {
+        error!(error = %e, "Failed to journal translation");
+    }
+}
+
+/// The last `conversation_context` translations into the current target language, oldest
+/// first, taken from the journal while they are recent enough to be the same conversation.
+fn conversation_context(config: &Config) -> Vec<ContextPair> {
+    let wanted = config.conversation_context.min(MAX_CONTEXT_PAIRS);
+    if wanted == 0 || config.translation_history_size == 0 {
+        return Vec::new();
+    }
+    let records = match journal::load() {
+        Ok(records) => records,
+        Err(e) => {
+            warn!(error = %e, "Failed to load conversation context");
+            return Vec::new();
+        }
+    };
+    let since = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .saturating_sub(CONTEXT_MAX_AGE)
+        .as_secs();
+    let mut context: Vec<ContextPair> = records
+        .into_iter()
+        .rev()
+        .filter(|record| {
+            record.action == Action::Translate.name()
+                && record.target_language == config.target_language
+                && record.finished_at >= since
         })
-        .collect()
+        .take(wanted)
+        .map(|record| ContextPair {
+            source: record.source,
+            translation: record.translation,
+        })
+        .collect();
+    context.reverse();
+    context
+}
+
+/// Arranges a translation and its original as `output_layout` asks. Other actions always
+/// replace the original.
+fn lay_out(config: &Config, action: Action, source: &str, translation: &str) -> String {
+    if action != Action::Translate {
+        return translation.to_string();
+    }
+    match config.output_layout {
+        OutputLayout::Replace => translation.to_string(),
+        OutputLayout::Append => format!("{}\n\n---\n\n{}", source.trim_end(), translation),
+        OutputLayout::Quote => {
+            let quoted: Vec<String> = source
+                .trim_end()
+                .lines()
+                .map(|line| format!("> {}", line).trim_end().to_string())
+                .collect();
+            format!("{}\n\n{}", translation.trim_end(), quoted.join("\n"))
+        }
+    }
+}
+
+/// Keeps a delivered translation as the last translation and journals it.
+fn record_translation(
+    state: &AppState,
+    config: &Config,
+    action: Action,
+    request_id: u64,
+    source: &str,
+    translation: &str,
+    duration_ms: u64,
+) {
+    let finished = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let last = LastTranslation {
+        request_id,
+        action: action.name(),
+        paragraphs: align_paragraphs(source, translation),
+        source: source.to_string(),
+        translation: translation.to_string(),
+        model: config.model.clone(),
+        target_language: config.target_language.clone(),
+        duration_ms,
+        finished_at: finished.as_secs(),
+    };
+    journal_translation(config, &last, finished.as_millis());
+    *state.last_translation.lock().unwrap() = Some(last);
+}
+
+/// Pairs paragraphs split on `%%` and blank lines. Models keep the paragraph count
+/// almost always; when they don't, the whole texts form one pair.
+fn align_paragraphs(source: &str, translation: &str) -> Vec<ParagraphPair> {
+    let sources = chunk::paragraphs(source);
+    let translations = chunk::paragraphs(translation);
+    if sources.len() == translations.len() {
+        sources
+            .into_iter()
+            .zip(translations)
+            .map(|(source, translation)| ParagraphPair {
+                source: source.to_string(),
+                translation: translation.to_string(),
+            })
+            .collect()
+    } else {
+        vec![ParagraphPair {
+            source: source.trim().to_string(),
+            translation: translation.trim().to_string(),
+        }]
+    }
+}
+
+/// A translation waiting in the review window until it is accepted or rejected.
+struct PendingReview {
+    action: Action,
+    request_id: u64,
+    input: Input,
+    translation: String,
+    /// Written instead of the plain text when the translation is accepted unedited.
+    formatted: Option<Formatted>,
+    config: Config,
+    duration_ms: u64,
+}
+
+/// What the review window shows.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewItem {
+    pub source: String,
+    pub translation: String,
+    pub target_language: String,
+}
+
+/// Oversized clipboard text held back until the user confirms or truncates it.
+struct PendingInput {
+    action: Action,
+    fast: bool,
+    input: Input,
+}
+
+pub struct CachedModels {
+    pub models: Vec<ModelInfo>,
+    pub fetched_at: Instant,
 }
 
 pub struct AppState {
-    pub config: Mutex<Config>,
-    pub translate_in_flight: Mutex<bool>,
+    /// Read through `config()` and changed through `update_config`.
+    config: ArcSwap<Config>,
+    pub requests: RequestRegistry,
     pub current_shortcut: Mutex<Option<Shortcut>>,
-    pub models_cache: Mutex<Option<Vec<ModelInfo>>>,
+    pub fast_shortcut: Mutex<Option<Shortcut>>,
+    action_shortcuts: Mutex<Vec<(Shortcut, Action)>>,
+    utility_shortcuts: Mutex<Vec<(Shortcut, Utility)>>,
+    /// Clipboard contents replaced by the last translation, for `undo_translation`.
+    undo_snapshot: Mutex<Option<Input>>,
+    /// Screenshot the capture overlay is selecting from.
+    capture: Mutex<Option<xcap::image::RgbaImage>>,
+    dictation: dictation::Dictation,
+    /// Set by "Pause ThirdSpace": hotkeys and the clipboard watcher are off until resumed.
+    paused: Mutex<bool>,
+    /// Found by the last update check, waiting for the user to install it.
+    pending_update: Mutex<Option<tauri_plugin_updater::Update>>,
+    pub models_cache: Mutex<Option<CachedModels>>,
+    pub toasts: ToastManager,
+    pub latency: LatencyTracker,
+    metrics: Metrics,
+    offline_queue: queue::OfflineQueue,
+    /// Listener task of the WebSocket bridge, while it is enabled.
+    bridge: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+    pub last_result: Mutex<Option<TranslationResult>>,
+    /// Alternatives of the last translation, for the chooser popup.
+    candidates: Mutex<Vec<String>>,
+    pub last_translation: Mutex<Option<LastTranslation>>,
+    /// How the last request ended, shown in the tray tooltip.
+    last_outcome: Mutex<Option<RequestOutcome>>,
+    /// Recent results by request, for `translation_cache_size`.
+    results: ResultCache<CacheKey, ActionOutput>,
+    /// Pinned journal records, newest first, behind the "Pinned" tray submenu.
+    pinned: Mutex<Vec<journal::Record>>,
+    pending_input: Mutex<Option<PendingInput>>,
+    /// Held back by `confirm_cost_above` until the same action is repeated.
+    pending_cost: Mutex<Option<PendingInput>>,
+    /// Held back by `review_translations` until `accept_translation`.
+    pending_review: Mutex<Option<PendingReview>>,
+    pub clipboard_history: ClipboardHistory,
+    /// Shared by all OpenRouter calls so connections are pooled; rebuilt when the
+    /// timeouts change.
+    http: Mutex<reqwest::Client>,
+}
+
+impl AppState {
+    /// Snapshot of the settings. Never blocks, and a request keeps the snapshot it started
+    /// with when the settings are saved meanwhile.
+    pub fn config(&self) -> Arc<Config> {
+        self.config.load_full()
+    }
+
+    /// Applies `change` to the settings and returns them. `change` runs again if another
+    /// update lands in between, so it must not have side effects.
+    pub fn update_config(&self, mut change: impl FnMut(&mut Config)) -> Arc<Config> {
+        let mut updated = None;
+        self.config.rcu(|current| {
+            let mut config = Config::clone(current);
+            change(&mut config);
+            policy::current().enforce(&mut config);
+            let config = Arc::new(config);
+            updated = Some(Arc::clone(&config));
+            config
+        });
+        updated.expect("rcu runs the update at least once")
+    }
+
+    pub fn http(&self) -> reqwest::Client {
+        self.http.lock().unwrap().clone()
+    }
 }
 
 #[tauri::command]
 fn get_config(state: tauri::State<'_, AppState>) -> Config {
-    state.config.lock().unwrap().clone()
+    Config::clone(&state.config())
+}
+
+/// The administrator's policy.json as last loaded; empty when there is none.
+#[tauri::command]
+fn get_policy() -> policy::Policy {
+    policy::Policy::clone(&policy::current())
+}
+
+/// Rewrites language names to the catalog spelling, e.g. "japanese" or "日本語" to
+/// "Japanese", so prompts and the tray list stay consistent.
+fn normalize_languages(config: &mut Config) {
+    config.target_language = languages::normalize(&config.target_language);
+    for lang in config
+        .languages
+        .iter_mut()
+        .chain(&mut config.multi_target_languages)
+    {
+        *lang = languages::normalize(lang);
+    }
+}
+
+#[tauri::command]
+fn get_languages() -> &'static [languages::Language] {
+    languages::LANGUAGES
 }
 
 #[tauri::command]
 async fn save_config(
     app: AppHandle,
     state: tauri::State<'_, AppState>,
-    new_config: Config,
-) -> Result<(), String> {
-    // Update hotkey if changed
-    let old_hotkey = state.config.lock().unwrap().hotkey.clone();
-    if old_hotkey != new_config.hotkey {
-        update_hotkey(&app, &state, &new_config.hotkey)?;
+    mut new_config: Config,
+) -> Result<(), CommandError> {
+    normalize_languages(&mut new_config);
+    bridge::ensure_token(&mut new_config);
+    if let Some(violation) = policy::current().violations(&new_config).first() {
+        warn!(field = violation.field(), "Settings refused by policy");
+        return Err(violation.to_string().into());
+    }
+    // Saved first, so a failed write cannot leave the running config ahead of the file
+    config::save(&new_config).map_err(|e| e.to_string())?;
+    apply_config(&app, &state, &new_config)?;
+
+    info!(
+        model = %new_config.model,
+        target_language = %new_config.target_language,
+        reasoning = ?new_config.reasoning,
+        hotkey = %new_config.hotkey,
+        autostart = new_config.autostart,
+        "Settings saved"
+    );
+
+    show_toast(&app, "success", &i18n::t(Msg::Saved));
+    Ok(())
+}
+
+/// Makes `new_config` the live config: re-registers changed hotkeys, syncs autostart
+/// and rebuilds the tray menu. Persisting it is up to the caller, before this. Only
+/// the hotkeys can refuse the config, and they go first; a failed autostart change is
+/// reported without undoing the rest.
+fn apply_config(
+    app: &AppHandle,
+    state: &tauri::State<'_, AppState>,
+    new_config: &Config,
+) -> Result<(), CommandError> {
+    // Nothing is re-registered unless every hotkey parses
+    check_hotkeys(new_config)?;
+    let (old_hotkey, old_fast_modifier) = {
+        let config = state.config();
+        (config.hotkey.clone(), config.fast_mode_modifier.clone())
+    };
+    if old_hotkey != new_config.hotkey || old_fast_modifier != new_config.fast_mode_modifier {
+        update_hotkey(
+            app,
+            state,
+            &new_config.hotkey,
+            &new_config.fast_mode_modifier,
+        )?;
+    }
+    let old_bindings = action_hotkeys(&state.config());
+    if old_bindings != action_hotkeys(new_config) {
+        update_action_hotkeys(app, state, new_config)?;
+    }
+    let old_utilities = utility_hotkeys(&state.config());
+    if old_utilities != utility_hotkeys(new_config) {
+        update_utility_hotkeys(app, state, new_config)?;
+    }
+    doubletap::ensure_listening(app, new_config);
+    // Re-registered hotkeys stay off until resumed
+    if *state.paused.lock().unwrap() {
+        for shortcut in pausable_shortcuts(state) {
+            let _ = app.global_shortcut().unregister(shortcut);
+        }
+    }
+
+    let bridge_changed = bridge::settings(&state.config()) != bridge::settings(new_config);
+    if bridge_changed {
+        bridge::restart(app, new_config);
     }
 
-    // Update autostart if changed
-    let old_autostart = state.config.lock().unwrap().autostart;
-    if old_autostart != new_config.autostart {
+    let old_autostart = state.config().autostart;
+    let client_changed = {
+        let config = state.config();
+        config.connect_timeout_secs != new_config.connect_timeout_secs
+            || config.request_timeout_secs != new_config.request_timeout_secs
+            || config.ca_bundle != new_config.ca_bundle
+            || config.use_system_certificates != new_config.use_system_certificates
+            || config.bypass_proxy != new_config.bypass_proxy
+            || config.proxy != new_config.proxy
+    };
+
+    let config = state.update_config(|config| *config = new_config.clone());
+    logs::set_limits(&config);
+    i18n::set_language(&config.ui_language);
+    openrouter::set_log_content(config.log_content);
+    openrouter::set_api_base(&config.api_base_url);
+    audit::configure(&config);
+    if config.clipboard_history_size == 0 {
+        state.clipboard_history.clear();
+    }
+    // Prompts, tones or app rules may have changed what a request would produce
+    state.results.clear();
+    if client_changed {
+        *state.http.lock().unwrap() = http_client(&config);
+        info!(
+            connect_timeout_secs = new_config.connect_timeout_secs,
+            request_timeout_secs = new_config.request_timeout_secs,
+            ca_bundle = %new_config.ca_bundle,
+            use_system_certificates = new_config.use_system_certificates,
+            bypass_proxy = new_config.bypass_proxy,
+            // The proxy URL may carry credentials
+            custom_proxy = !new_config.proxy.trim().is_empty(),
+            "HTTP client rebuilt"
+        );
+    }
+    // Not fatal: the config is live by now, and refusing it here would leave the
+    // hotkeys and the bridge out of step with it
+    if old_autostart != config.autostart {
         let autostart_manager = app.autolaunch();
-        if new_config.autostart {
-            autostart_manager.enable().map_err(|e| e.to_string())?;
-            info!("Autostart enabled");
+        let synced = if config.autostart {
+            autostart_manager.enable()
         } else {
-            autostart_manager.disable().map_err(|e| e.to_string())?;
-            info!("Autostart disabled");
+            autostart_manager.disable()
+        };
+        match synced {
+            Ok(()) => info!(autostart = config.autostart, "Autostart updated"),
+            Err(e) => {
+                error!(error = %e, autostart = config.autostart, "Autostart update failed");
+                show_toast(app, "error", &i18n::t(Msg::AutostartFailed));
+            }
+        }
+    }
+    refresh_tray_menu(app);
+    Ok(())
+}
+
+fn http_client(config: &Config) -> reqwest::Client {
+    openrouter::http_client(config).unwrap_or_else(|e| {
+        error!(error = %e, "HTTP client setup failed, using defaults");
+        reqwest::Client::new()
+    })
+}
+
+/// Writes config and prompt templates to a single file; with a passphrase the bundle is
+/// encrypted and also carries the API key.
+#[tauri::command]
+fn export_settings(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    path: String,
+    passphrase: Option<String>,
+) -> Result<(), CommandError> {
+    let config = state.config();
+    let passphrase = passphrase.filter(|passphrase| !passphrase.is_empty());
+    bundle::export(&config, Path::new(&path), passphrase.as_deref()).map_err(|e| {
+        error!(error = %e, "Settings export failed");
+        e.to_string()
+    })?;
+    show_toast(&app, "success", &i18n::t(Msg::Exported));
+    Ok(())
+}
+
+/// Writes journaled translations within `date_range` to a CSV or JSONL file.
+#[tauri::command]
+fn export_history(
+    app: AppHandle,
+    path: String,
+    format: journal::ExportFormat,
+    date_range: Option<journal::DateRange>,
+) -> Result<usize, CommandError> {
+    let count =
+        journal::export(Path::new(&path), format, date_range.unwrap_or_default()).map_err(|e| {
+            error!(error = %e, "History export failed");
+            e.to_string()
+        })?;
+    info!(count, format = ?format, "History exported");
+    show_toast(&app, "success", &i18n::t(Msg::Exported));
+    Ok(count)
+}
+
+/// Replaces the live config with the one from an exported bundle.
+#[tauri::command]
+fn import_settings(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    path: String,
+    passphrase: Option<String>,
+) -> Result<(), CommandError> {
+    let current = state.config();
+    let passphrase = passphrase.filter(|passphrase| !passphrase.is_empty());
+    let mut imported =
+        bundle::import(Path::new(&path), passphrase.as_deref(), &current).map_err(|e| {
+            error!(error = %e, "Settings import failed");
+            e.to_string()
+        })?;
+    policy::current().enforce(&mut imported);
+    bridge::ensure_token(&mut imported);
+    config::save(&imported).map_err(|e| e.to_string())?;
+    apply_config(&app, &state, &imported)?;
+    let _ = app.emit("config-changed", &imported);
+    show_toast(&app, "success", &i18n::t(Msg::Imported));
+    Ok(())
+}
+
+/// Result of `validate_api_key`: whether OpenRouter accepted the key, and why not.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiKeyStatus {
+    pub valid: bool,
+    pub message: String,
+}
+
+/// Verifies a key with OpenRouter before it is saved. An `Err` means OpenRouter could not
+/// be reached, which says nothing about the key.
+#[tauri::command]
+async fn validate_api_key(
+    state: tauri::State<'_, AppState>,
+    key: String,
+) -> Result<ApiKeyStatus, CommandError> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Ok(ApiKeyStatus {
+            valid: false,
+            message: i18n::t(Msg::ApiKeyEmpty),
+        });
+    }
+    match openrouter::check_key(&state.http(), key).await {
+        Ok(openrouter::KeyCheck::Valid(info)) => Ok(ApiKeyStatus {
+            valid: true,
+            message: match info.label.filter(|label| !label.is_empty()) {
+                Some(label) => i18n::t(Msg::KeyAcceptedAs(&label)),
+                None => i18n::t(Msg::KeyAccepted),
+            },
+        }),
+        Ok(openrouter::KeyCheck::Rejected(reason)) => Ok(ApiKeyStatus {
+            valid: false,
+            message: reason,
+        }),
+        Err(e) => {
+            warn!(error = %e, "API key check failed");
+            Err(i18n::t(Msg::CouldNotReach(&e.to_string())).into())
+        }
+    }
+}
+
+#[tauri::command]
+async fn get_account_status(
+    state: tauri::State<'_, AppState>,
+) -> Result<openrouter::AccountStatus, CommandError> {
+    let api_key = state.config().api_key.clone();
+    if api_key.trim().is_empty() {
+        return Err(CommandError::ApiKeyMissing);
+    }
+    openrouter::account_status(&state.http(), &api_key)
+        .await
+        .map_err(CommandError::from)
+}
+
+/// A problem with one config field, reported by `validate_config`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigIssue {
+    /// The config key, e.g. `hotkey` or `actions[0].shortcut`.
+    pub field: String,
+    pub message: String,
+    pub severity: Severity,
+}
+
+/// Errors are values that cannot be applied and block saving; warnings are values that
+/// can be saved but will probably make requests fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl ConfigIssue {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+            severity: Severity::Error,
+        }
+    }
+
+    fn warning(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::new(field, message)
         }
     }
+}
+
+/// Checks a config before it is saved, so mistakes surface in settings rather than
+/// when a translation fails. An empty list means the config is usable; settings only
+/// refuses to save when an issue is an error.
+#[tauri::command]
+fn validate_config(state: tauri::State<'_, AppState>, config: Config) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+
+    let api_key = config.api_key.trim();
+    if api_key.is_empty() {
+        issues.push(ConfigIssue::warning("api_key", i18n::t(Msg::ApiKeyMissing)));
+    } else if !api_key.starts_with("sk-or-") || api_key.len() < 20 || api_key != config.api_key {
+        issues.push(ConfigIssue::warning(
+            "api_key",
+            i18n::t(Msg::NotAnOpenRouterKey),
+        ));
+    }
+
+    if config.target_language.trim().is_empty() {
+        issues.push(ConfigIssue::new(
+            "target_language",
+            i18n::t(Msg::TargetLanguageEmpty),
+        ));
+    } else if languages::find(&config.target_language).is_none() {
+        issues.push(ConfigIssue::warning(
+            "target_language",
+            i18n::t(Msg::UnknownLanguage(config.target_language.trim())),
+        ));
+    }
+    let unknown: Vec<&str> = config
+        .languages
+        .iter()
+        .map(|lang| lang.trim())
+        .filter(|lang| !lang.is_empty() && languages::find(lang).is_none())
+        .collect();
+    if !unknown.is_empty() {
+        issues.push(ConfigIssue::warning(
+            "languages",
+            i18n::t(Msg::UnknownLanguages(&unknown.join(", "))),
+        ));
+    }
+    let unknown: Vec<&str> = config
+        .multi_target_languages
+        .iter()
+        .map(|lang| lang.trim())
+        .filter(|lang| !lang.is_empty() && languages::find(lang).is_none())
+        .collect();
+    if !unknown.is_empty() {
+        issues.push(ConfigIssue::warning(
+            "multi_target_languages",
+            i18n::t(Msg::UnknownLanguages(&unknown.join(", "))),
+        ));
+    }
+
+    // Model ids can only be checked once the list has been fetched
+    let known_models: Option<Vec<String>> = state
+        .models_cache
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|cached| cached.models.iter().map(|model| model.id.clone()).collect());
+    let auto = automodel::is_auto(&config.model);
+    let auto_models = [
+        &config.auto_model.fast_model,
+        &config.auto_model.strong_model,
+    ];
+    if config.model.trim().is_empty() {
+        issues.push(ConfigIssue::new("model", i18n::t(Msg::ModelMissing)));
+    } else if auto && auto_models.iter().all(|model| model.trim().is_empty()) {
+        issues.push(ConfigIssue::new("auto_model", i18n::t(Msg::ModelMissing)));
+    } else if let Some(known) = &known_models {
+        if auto {
+            for model in auto_models {
+                if !model.trim().is_empty() && !known.contains(model) {
+                    issues.push(ConfigIssue::warning(
+                        "auto_model",
+                        i18n::t(Msg::UnknownModel(model)),
+                    ));
+                }
+            }
+        } else if !known.contains(&config.model) {
+            issues.push(ConfigIssue::warning(
+                "model",
+                i18n::t(Msg::UnknownModel(&config.model)),
+            ));
+        }
+        for model in &config.favorite_models {
+            if !model.trim().is_empty() && !known.contains(model) {
+                issues.push(ConfigIssue::warning(
+                    "favorite_models",
+                    i18n::t(Msg::UnknownModel(model)),
+                ));
+            }
+        }
+    }
+
+    if let Some(temperature) = config.temperature {
+        if !(0.0..=2.0).contains(&temperature) {
+            issues.push(ConfigIssue::new(
+                "temperature",
+                i18n::t(Msg::TemperatureRange),
+            ));
+        }
+    }
+    if let Some(top_p) = config.top_p {
+        if !(top_p > 0.0 && top_p <= 1.0) {
+            issues.push(ConfigIssue::new("top_p", i18n::t(Msg::TopPRange)));
+        }
+    }
+    if config.max_tokens == Some(0) {
+        issues.push(ConfigIssue::new("max_tokens", i18n::t(Msg::MaxTokensMin)));
+    }
+    if config.conversation_context > MAX_CONTEXT_PAIRS {
+        issues.push(ConfigIssue::new(
+            "conversation_context",
+            i18n::t(Msg::MaxContextPairs(MAX_CONTEXT_PAIRS)),
+        ));
+    } else if config.conversation_context > 0 && config.translation_history_size == 0 {
+        issues.push(ConfigIssue::new(
+            "conversation_context",
+            i18n::t(Msg::ContextNeedsHistory),
+        ));
+    }
+    if !config.ca_bundle.trim().is_empty() {
+        if let Err(e) = openrouter::load_ca_bundle(config.ca_bundle.trim()) {
+            issues.push(ConfigIssue::new(
+                "ca_bundle",
+                i18n::t(Msg::CaBundleInvalid(&format!("{:#}", e))),
+            ));
+        }
+    }
+    let proxy = config.proxy.trim();
+    if !proxy.is_empty() && reqwest::Proxy::all(proxy).is_err() {
+        issues.push(ConfigIssue::new("proxy", i18n::t(Msg::InvalidUrl)));
+    }
+    let api_base_url = config.api_base_url.trim();
+    if !api_base_url.is_empty()
+        && !reqwest::Url::parse(api_base_url)
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+    {
+        issues.push(ConfigIssue::new("api_base_url", i18n::t(Msg::InvalidUrl)));
+    }
+    if !(1..=MAX_CONCURRENT_REQUESTS).contains(&config.max_concurrent_requests) {
+        issues.push(ConfigIssue::new(
+            "max_concurrent_requests",
+            i18n::t(Msg::ConcurrentRange(MAX_CONCURRENT_REQUESTS)),
+        ));
+    }
+    if config.translation_candidates > MAX_CANDIDATES {
+        issues.push(ConfigIssue::new(
+            "translation_candidates",
+            i18n::t(Msg::MaxCandidates(MAX_CANDIDATES)),
+        ));
+    }
+    if config.reasoning == ReasoningEffort::Budget(0) {
+        issues.push(ConfigIssue::new(
+            "reasoning",
+            i18n::t(Msg::ReasoningBudgetMin),
+        ));
+    }
+
+    for (idx, rule) in config.app_rules.iter().enumerate() {
+        let field = format!("app_rules[{}]", idx);
+        if rule.app.trim().is_empty() {
+            issues.push(ConfigIssue::new(field, i18n::t(Msg::AppRuleNoApp)));
+        } else if !rule.target_language.trim().is_empty()
+            && languages::find(&rule.target_language).is_none()
+        {
+            issues.push(ConfigIssue::warning(
+                field,
+                i18n::t(Msg::UnknownLanguage(rule.target_language.trim())),
+            ));
+        }
+    }
+    for (idx, rule) in config.post_hooks.rules.iter().enumerate() {
+        if let Err(e) = regex::Regex::new(&rule.find) {
+            issues.push(ConfigIssue::new(
+                format!("post_hooks.rules[{}].find", idx),
+                i18n::t(Msg::InvalidPattern(&e.to_string())),
+            ));
+        }
+    }
+
+    let builtin = [
+        ("hotkey", &config.hotkey),
+        ("summarize_hotkey", &config.summarize_hotkey),
+        ("proofread_hotkey", &config.proofread_hotkey),
+        ("rewrite_hotkey", &config.rewrite_hotkey),
+        ("cycle_language_hotkey", &config.cycle_language_hotkey),
+        ("undo_hotkey", &config.undo_hotkey),
+        ("quick_translate_hotkey", &config.quick_translate_hotkey),
+        ("capture_hotkey", &config.capture_hotkey),
+        ("dictation_hotkey", &config.dictation_hotkey),
+        ("toggle_pause_hotkey", &config.toggle_pause_hotkey),
+        (
+            "translate_selection_hotkey",
+            &config.translate_selection_hotkey,
+        ),
+    ]
+    .map(|(field, hotkey)| (field.to_string(), hotkey));
+    let custom = config
+        .actions
+        .iter()
+        .enumerate()
+        .map(|(idx, action)| (format!("actions[{}].shortcut", idx), &action.shortcut));
+    let mut bound: Vec<(Shortcut, String)> = Vec::new();
+    for (field, hotkey) in builtin.into_iter().chain(custom) {
+        if hotkey.trim().is_empty() {
+            if field == "hotkey" {
+                issues.push(ConfigIssue::new(field, i18n::t(Msg::HotkeyMissing)));
+            }
+            continue;
+        }
+        let shortcut = match parse_shortcut(hotkey) {
+            Ok(shortcut) => shortcut,
+            Err(e) => {
+                issues.push(ConfigIssue::new(field, format!("{}: {}", hotkey, e)));
+                continue;
+            }
+        };
+        if field == "hotkey" {
+            match fast_variant(&shortcut, &config.fast_mode_modifier) {
+                Ok(Some(fast)) => bound.push((fast, "fast_mode_modifier".to_string())),
+                Ok(None) => {}
+                Err(e) => issues.push(ConfigIssue::new("fast_mode_modifier", e)),
+            }
+        }
+        if let Some((_, other)) = bound.iter().find(|(existing, _)| *existing == shortcut) {
+            issues.push(ConfigIssue::new(
+                field,
+                i18n::t(Msg::HotkeyInUse(hotkey, other)),
+            ));
+            continue;
+        }
+        bound.push((shortcut, field));
+    }
+
+    let double_tap = config.double_tap_modifier.trim();
+    if !double_tap.is_empty() && parse_modifier(&double_tap.to_ascii_lowercase()).is_none() {
+        issues.push(ConfigIssue::new(
+            "double_tap_modifier",
+            i18n::t(Msg::UnknownModifier(double_tap)),
+        ));
+    }
+
+    for violation in policy::current().violations(&config) {
+        let message = match &violation {
+            policy::Violation::Locked(_) => i18n::t(Msg::LockedByPolicy),
+            policy::Violation::ModelNotAllowed { model, .. } => {
+                i18n::t(Msg::ModelNotAllowed(model))
+            }
+        };
+        issues.push(ConfigIssue::new(violation.field(), message));
+    }
+
+    debug!(issues = issues.len(), "Config validated");
+    issues
+}
+
+/// Result of `validate_hotkey`.
+#[derive(Debug, Clone, Serialize)]
+pub struct HotkeyStatus {
+    /// The string parses as a shortcut.
+    pub valid: bool,
+    /// No other application holds the combination.
+    pub available: bool,
+    pub message: String,
+}
+
+/// Checks a hotkey before it is saved: parses it, then registers and immediately
+/// releases it to find out whether another application already owns the combination.
+#[tauri::command]
+fn validate_hotkey(app: AppHandle, hotkey: String) -> HotkeyStatus {
+    let shortcut = match parse_shortcut(&hotkey) {
+        Ok(shortcut) => shortcut,
+        Err(message) => {
+            return HotkeyStatus {
+                valid: false,
+                available: false,
+                message,
+            }
+        }
+    };
+    // One of ThirdSpace's own hotkeys, e.g. the one being re-recorded
+    if app.global_shortcut().is_registered(shortcut) {
+        return HotkeyStatus {
+            valid: true,
+            available: true,
+            message: i18n::t(Msg::HotkeyOwn),
+        };
+    }
+    match app.global_shortcut().register(shortcut) {
+        Ok(()) => {
+            let _ = app.global_shortcut().unregister(shortcut);
+            HotkeyStatus {
+                valid: true,
+                available: true,
+                message: i18n::t(Msg::HotkeyAvailable),
+            }
+        }
+        Err(e) => {
+            debug!(hotkey = %hotkey, error = %e, "Hotkey taken by another application");
+            HotkeyStatus {
+                valid: true,
+                available: false,
+                message: i18n::t(Msg::HotkeyUsedElsewhere(hotkey.trim())),
+            }
+        }
+    }
+}
+
+#[tauri::command]
+fn pause_hotkey(app: AppHandle, state: tauri::State<'_, AppState>) -> Result<(), CommandError> {
+    // While paused only the pause toggle is registered
+    if *state.paused.lock().unwrap() {
+        for (s, _) in pause_toggle_shortcuts(&state) {
+            let _ = app.global_shortcut().unregister(s);
+        }
+        return Ok(());
+    }
+    let shortcut = state.current_shortcut.lock().unwrap();
+    if let Some(s) = shortcut.as_ref() {
+        app.global_shortcut()
+            .unregister(*s)
+            .map_err(|e| e.to_string())?;
+        debug!("Hotkey paused for recording");
+    }
+    if let Some(s) = state.fast_shortcut.lock().unwrap().as_ref() {
+        let _ = app.global_shortcut().unregister(*s);
+    }
+    for (s, _) in state.action_shortcuts.lock().unwrap().iter() {
+        let _ = app.global_shortcut().unregister(*s);
+    }
+    for (s, _) in state.utility_shortcuts.lock().unwrap().iter() {
+        let _ = app.global_shortcut().unregister(*s);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn resume_hotkey(app: AppHandle, state: tauri::State<'_, AppState>) -> Result<(), CommandError> {
+    if *state.paused.lock().unwrap() {
+        for (s, _) in pause_toggle_shortcuts(&state) {
+            let _ = app.global_shortcut().register(s);
+        }
+        return Ok(());
+    }
+    let shortcut = state.current_shortcut.lock().unwrap();
+    if let Some(s) = shortcut.as_ref() {
+        app.global_shortcut()
+            .register(*s)
+            .map_err(|e| e.to_string())?;
+        debug!("Hotkey resumed after recording");
+    }
+    if let Some(s) = state.fast_shortcut.lock().unwrap().as_ref() {
+        let _ = app.global_shortcut().register(*s);
+    }
+    for (s, _) in state.action_shortcuts.lock().unwrap().iter() {
+        let _ = app.global_shortcut().register(*s);
+    }
+    for (s, _) in state.utility_shortcuts.lock().unwrap().iter() {
+        let _ = app.global_shortcut().register(*s);
+    }
+    Ok(())
+}
+
+/// Every registered shortcut except the pause toggle, which has to keep working to resume.
+fn pausable_shortcuts(state: &AppState) -> Vec<Shortcut> {
+    let main = *state.current_shortcut.lock().unwrap();
+    let fast = *state.fast_shortcut.lock().unwrap();
+    let actions = state.action_shortcuts.lock().unwrap();
+    let utilities = state.utility_shortcuts.lock().unwrap();
+    main.into_iter()
+        .chain(fast)
+        .chain(actions.iter().map(|(s, _)| *s))
+        .chain(
+            utilities
+                .iter()
+                .filter(|(_, utility)| *utility != Utility::TogglePause)
+                .map(|(s, _)| *s),
+        )
+        .collect()
+}
+
+fn pause_toggle_shortcuts(state: &AppState) -> Vec<(Shortcut, Utility)> {
+    state
+        .utility_shortcuts
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, utility)| *utility == Utility::TogglePause)
+        .copied()
+        .collect()
+}
+
+#[tauri::command]
+fn set_paused(app: AppHandle, paused: bool) {
+    apply_pause(&app, paused);
+}
+
+#[tauri::command]
+fn is_paused(state: tauri::State<'_, AppState>) -> bool {
+    *state.paused.lock().unwrap()
+}
+
+/// Checks a release channel, the configured one by default; `None` means up to date.
+/// The settings UI confirms with the user before calling `install_update`.
+#[tauri::command]
+async fn check_for_updates(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    channel: Option<UpdateChannel>,
+) -> Result<Option<updater::UpdateInfo>, CommandError> {
+    let channel = channel.unwrap_or_else(|| state.config().update_channel);
+    updater::check(&app, channel).await.map_err(|e| {
+        warn!(error = %e, "Update check failed");
+        CommandError::from(e)
+    })
+}
+
+/// Compares this version with the latest GitHub release; a newer one also emits
+/// `update-available` and shows a toast.
+#[tauri::command]
+async fn check_latest_version(app: AppHandle) -> Result<updater::LatestVersion, CommandError> {
+    updater::check_latest(&app).await.map_err(|e| {
+        warn!(error = %e, "Version check failed");
+        CommandError::from(e)
+    })
+}
+
+#[tauri::command]
+async fn install_update(app: AppHandle) -> Result<(), CommandError> {
+    updater::install(&app).await.map_err(|e| {
+        error!(error = %e, "Update failed");
+        CommandError::from(e)
+    })
+}
+
+#[tauri::command]
+async fn fetch_models(state: tauri::State<'_, AppState>) -> Result<Vec<ModelInfo>, CommandError> {
+    models_catalog(&state).await
+}
+
+/// One page of the models catalog matching `query` and `filters`, for the settings picker.
+#[tauri::command]
+async fn get_models(
+    state: tauri::State<'_, AppState>,
+    query: Option<String>,
+    page: Option<usize>,
+    filters: Option<models::ModelFilters>,
+) -> Result<models::ModelPage, CommandError> {
+    let mut catalog = models_catalog(&state).await?;
+    let policy = policy::current();
+    catalog.retain(|model| policy.allows_model(&model.id));
+    let query = query.unwrap_or_default();
+    let filters = filters.unwrap_or_default();
+    let page = models::search(&catalog, &query, &filters, page.unwrap_or(0));
+    debug!(
+        query_len = query.len(),
+        ?filters,
+        page = page.page,
+        total = page.total,
+        "Models searched"
+    );
+    Ok(page)
+}
+
+/// The cached models list, fetched from OpenRouter when the cache is empty.
+async fn models_catalog(state: &AppState) -> Result<Vec<ModelInfo>, CommandError> {
+    // Check if we have cached models
+    {
+        let cache = state.models_cache.lock().unwrap();
+        if let Some(cached) = cache.as_ref() {
+            debug!(count = cached.models.len(), "Returning cached models");
+            return Ok(cached.models.clone());
+        }
+    }
+
+    // Get API key from config
+    let api_key = {
+        let config = state.config();
+        config.api_key.clone()
+    };
+
+    if api_key.trim().is_empty() {
+        return Err(CommandError::ApiKeyMissing);
+    }
+
+    // Fetch from OpenRouter
+    let models = openrouter::fetch_models(&state.http(), &api_key).await?;
+
+    // Cache the results
+    {
+        let mut cache = state.models_cache.lock().unwrap();
+        *cache = Some(CachedModels {
+            models: models.clone(),
+            fetched_at: Instant::now(),
+        });
+    }
+
+    info!(count = models.len(), "Models fetched and cached");
+    Ok(models)
+}
+
+#[tauri::command]
+async fn translate_clipboard(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), CommandError> {
+    run_action(app, state, Action::Translate, false).await
+}
+
+/// Copies the selection in the focused application first, then translates it like
+/// `translate_clipboard`.
+#[tauri::command]
+async fn translate_selection(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), CommandError> {
+    run_selection_action(app, state, Action::Translate, false).await
+}
+
+#[tauri::command]
+async fn translate_commit_message(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), CommandError> {
+    run_action(app, state, Action::CommitMessage, false).await
+}
+
+#[tauri::command]
+async fn summarize(app: AppHandle, state: tauri::State<'_, AppState>) -> Result<(), CommandError> {
+    run_action(app, state, Action::Summarize, false).await
+}
+
+#[tauri::command]
+async fn proofread(app: AppHandle, state: tauri::State<'_, AppState>) -> Result<(), CommandError> {
+    run_action(app, state, Action::Proofread, false).await
+}
+
+#[tauri::command]
+async fn rewrite(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    tone: Option<Tone>,
+) -> Result<(), CommandError> {
+    let tone = tone.unwrap_or_else(|| state.config().rewrite_tone);
+    run_action(app, state, Action::Rewrite(tone), false).await
+}
+
+#[tauri::command]
+async fn run_custom_action(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    name: String,
+) -> Result<(), CommandError> {
+    let idx = state
+        .config()
+        .actions
+        .iter()
+        .position(|action| action.name == name)
+        .ok_or_else(|| i18n::t(Msg::UnknownAction(&name)))?;
+    run_action(app, state, Action::Custom(idx), false).await
+}
+
+#[tauri::command]
+async fn translate_file(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    path: String,
+) -> Result<String, CommandError> {
+    let config = state.config();
+    run_file_translation(&app, config, PathBuf::from(path)).await
+}
+
+#[tauri::command]
+fn get_clipboard_history(state: tauri::State<'_, AppState>) -> Vec<String> {
+    state.clipboard_history.entries()
+}
+
+/// Translates an earlier clipboard snapshot; `index` 0 is the newest.
+#[tauri::command]
+async fn translate_history_entry(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    index: usize,
+) -> Result<(), CommandError> {
+    let text = state
+        .clipboard_history
+        .get(index)
+        .ok_or_else(|| i18n::t(Msg::NoClipboardEntry(index)))?;
+    run_input(app, state, Action::Translate, false, text).await
+}
+
+#[tauri::command]
+fn get_result(state: tauri::State<'_, AppState>) -> Option<TranslationResult> {
+    state.last_result.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn get_review(state: tauri::State<'_, AppState>) -> Option<ReviewItem> {
+    state
+        .pending_review
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|review| ReviewItem {
+            source: review.input.text.clone(),
+            translation: review.translation.clone(),
+            target_language: review.config.target_language.clone(),
+        })
+}
+
+/// Writes the reviewed translation, as edited in the review window, to the clipboard.
+#[tauri::command]
+fn accept_translation(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    translation: String,
+) -> Result<(), CommandError> {
+    let review = state
+        .pending_review
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| i18n::t(Msg::NoReviewWaiting))?;
+    let edited = translation != review.translation;
+    let delivered = lay_out(
+        &review.config,
+        review.action,
+        &review.input.text,
+        &translation,
+    );
+    state.clipboard_history.ignore(&delivered);
+    let snapshot = clipboard::snapshot(&app);
+    let written = match &review.formatted {
+        Some(formatted) if !edited && review.config.output_layout == OutputLayout::Replace => {
+            clipboard::write_formatted(formatted, &translation)
+        }
+        _ => clipboard::write_text(&app, &delivered),
+    };
+    written.map_err(|e| {
+        error!(error = %e, "Clipboard write failed");
+        show_toast(&app, "error", &i18n::t(Msg::ClipboardFailed));
+        e.to_string()
+    })?;
+    info!(
+        request_id = review.request_id,
+        edited,
+        translated_len = translation.len(),
+        "Reviewed translation accepted"
+    );
+    show_toast(&app, "success", "");
+    *state.undo_snapshot.lock().unwrap() = Some(snapshot);
+    refresh_tray_menu(&app);
+    record_translation(
+        &state,
+        &review.config,
+        review.action,
+        review.request_id,
+        &review.input.text,
+        &translation,
+        review.duration_ms,
+    );
+    Ok(())
+}
+
+/// Drops the translation waiting for review; the clipboard keeps its contents.
+#[tauri::command]
+fn reject_translation(state: tauri::State<'_, AppState>) {
+    if let Some(review) = state.pending_review.lock().unwrap().take() {
+        info!(
+            request_id = review.request_id,
+            "Reviewed translation rejected"
+        );
+    }
+}
+
+#[tauri::command]
+fn get_candidates(state: tauri::State<'_, AppState>) -> Vec<String> {
+    state.candidates.lock().unwrap().clone()
+}
+
+/// Replaces the clipboard with the candidate picked in the chooser popup.
+#[tauri::command]
+fn choose_candidate(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    index: usize,
+) -> Result<(), CommandError> {
+    let candidate = state
+        .candidates
+        .lock()
+        .unwrap()
+        .get(index)
+        .cloned()
+        .ok_or_else(|| i18n::t(Msg::NoCandidate(index)))?;
+    let source = state
+        .last_translation
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|last| last.source.clone())
+        .unwrap_or_default();
+    let config = state.config();
+    let delivered = lay_out(&config, Action::Translate, &source, &candidate);
+    state.clipboard_history.ignore(&delivered);
+    clipboard::write_text(&app, &delivered).map_err(|e| {
+        error!(error = %e, "Clipboard write failed");
+        e.to_string()
+    })?;
+    if let Some(last) = state.last_translation.lock().unwrap().as_mut() {
+        last.paragraphs = align_paragraphs(&last.source, &candidate);
+        last.translation = candidate;
+    }
+    info!(index, "Candidate chosen");
+    show_toast(&app, "success", "");
+    Ok(())
+}
+
+/// Puts back the clipboard contents the last translation replaced.
+#[tauri::command]
+fn undo_translation(app: AppHandle) -> Result<(), CommandError> {
+    undo_last_translation(&app)
+}
+
+/// Screenshots the monitor under the cursor and lets the user select a region to
+/// translate.
+#[tauri::command]
+fn start_capture(app: AppHandle) {
+    capture::start(&app);
+}
+
+/// The frozen screen for the capture overlay to draw.
+#[tauri::command]
+fn get_capture(app: AppHandle) -> Result<capture::Screenshot, CommandError> {
+    capture::screenshot(&app).map_err(CommandError::from)
+}
+
+/// OCRs the selected region of the capture and translates the text onto the clipboard.
+#[tauri::command]
+async fn capture_region(app: AppHandle, region: capture::Region) -> Result<(), CommandError> {
+    capture::translate_region(&app, region).await
+}
+
+#[tauri::command]
+fn cancel_capture(app: AppHandle) {
+    capture::cancel(&app);
+}
+
+/// Values the setup window starts from.
+#[tauri::command]
+fn get_onboarding(app: AppHandle) -> onboarding::Progress {
+    onboarding::progress(&app, "api_key")
+}
+
+/// Checks and applies one setup step: API key, model, hotkey, then finish.
+#[tauri::command]
+async fn complete_onboarding(
+    app: AppHandle,
+    step: onboarding::Step,
+) -> Result<onboarding::Progress, CommandError> {
+    onboarding::complete(&app, step).await
+}
+
+fn undo_last_translation(app: &AppHandle) -> Result<(), CommandError> {
+    let state = app.state::<AppState>();
+    let Some(snapshot) = state.undo_snapshot.lock().unwrap().take() else {
+        show_toast(app, "error", &i18n::t(Msg::NothingToUndo));
+        return Err(i18n::t(Msg::NothingToUndo).into());
+    };
+    refresh_tray_menu(app);
+
+    state.clipboard_history.ignore(&snapshot.text);
+    let written = match &snapshot.formatted {
+        Some(formatted) => clipboard::write_formatted(formatted, &snapshot.text),
+        None => clipboard::write_text(app, &snapshot.text),
+    };
+    if let Err(e) = written {
+        error!(error = %e, "Clipboard restore failed");
+        show_toast(app, "error", &i18n::t(Msg::ClipboardFailed));
+        return Err(e.into());
+    }
+    info!(restored_len = snapshot.text.len(), "Translation undone");
+    show_toast(app, "success", &i18n::t(Msg::Restored));
+    Ok(())
+}
+
+#[tauri::command]
+fn get_last_translation(state: tauri::State<'_, AppState>) -> Option<LastTranslation> {
+    state.last_translation.lock().unwrap().clone()
+}
+
+/// Marks a journal record as pinned, exempting it from compaction and listing it in
+/// the tray's "Pinned" submenu.
+#[tauri::command]
+fn pin_translation(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    id: String,
+    pinned: bool,
+) -> Result<(), CommandError> {
+    if journal::set_pinned(&id, pinned)
+        .map_err(|e| e.to_string())?
+        .is_none()
+    {
+        return Err(i18n::t(Msg::NoTranslationWithId(&id)).into());
+    }
+    let records = journal::pinned().map_err(|e| e.to_string())?;
+    info!(id = %id, pinned, total = records.len(), "Translation pin changed");
+    *state.pinned.lock().unwrap() = records;
+    refresh_tray_menu(&app);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_pinned(state: tauri::State<'_, AppState>) -> Vec<journal::Record> {
+    state.pinned.lock().unwrap().clone()
+}
+
+/// Puts a pinned translation on the clipboard for reuse.
+fn copy_pinned(app: &AppHandle, index: usize) {
+    let state = app.state::<AppState>();
+    let Some(record) = state.pinned.lock().unwrap().get(index).cloned() else {
+        return;
+    };
+    state.clipboard_history.ignore(&record.translation);
+    match clipboard::write_text(app, &record.translation) {
+        Ok(()) => {
+            debug!(id = %record.id, "Pinned translation copied");
+            show_toast(app, "success", &i18n::t(Msg::Copied));
+        }
+        Err(e) => {
+            error!(error = %e, "Clipboard write failed");
+            show_toast(app, "error", &i18n::t(Msg::ClipboardFailed));
+        }
+    }
+}
+
+/// Replaces the log filter until restart, e.g. `debug` or `info,thirdspace_lib=trace`.
+/// An empty filter restores the startup one.
+#[tauri::command]
+fn set_log_level(filter: String) -> Result<(), CommandError> {
+    let filter = filter.trim();
+    let new_filter = if filter.is_empty() {
+        build_log_filter()
+    } else {
+        EnvFilter::try_new(filter).map_err(|e| i18n::t(Msg::InvalidLogFilter(&e.to_string())))?
+    };
+    let handle = LOG_FILTER
+        .get()
+        .ok_or_else(|| i18n::t(Msg::LoggingNotInitialized))?;
+    handle.reload(new_filter).map_err(|e| e.to_string())?;
+    info!(filter = %filter, "Log filter changed");
+    Ok(())
+}
+
+/// Checks DNS, TLS, the API key, clipboard access and hotkey registration.
+#[tauri::command]
+async fn run_diagnostics(app: AppHandle) -> diagnostics::Report {
+    diagnostics::run(&app).await
+}
+
+/// Writes a zip of logs, the config without secrets and version info for a bug report.
+#[tauri::command]
+fn export_diagnostics(app: AppHandle, path: String) -> Result<(), CommandError> {
+    diagnostics::export(&app, Path::new(&path)).map_err(|e| {
+        error!(error = %e, "Diagnostics export failed");
+        e.to_string()
+    })?;
+    show_toast(&app, "success", &i18n::t(Msg::Exported));
+    Ok(())
+}
+
+/// Latency percentiles and error rates per model for this session.
+#[tauri::command]
+fn get_metrics(state: tauri::State<'_, AppState>) -> Vec<metrics::ModelStats> {
+    state.metrics.stats()
+}
+
+/// Requests currently running, oldest first.
+#[tauri::command]
+fn get_active_requests(state: tauri::State<'_, AppState>) -> Vec<ActiveRequest> {
+    state.requests.list()
+}
+
+/// Tokens and spend of this calendar month, counted against the monthly budget.
+#[tauri::command]
+fn get_usage() -> usage::MonthlyUsage {
+    usage::current()
+}
+
+/// Searches the translation journal, newest first.
+#[tauri::command]
+fn search_history(
+    query: String,
+    filters: Option<journal::Filters>,
+) -> Result<Vec<journal::Record>, CommandError> {
+    let records = journal::load().map_err(|e| e.to_string())?;
+    let results = journal::search(records, &query, &filters.unwrap_or_default());
+    debug!(
+        query_len = query.len(),
+        results = results.len(),
+        "History searched"
+    );
+    Ok(results)
+}
+
+#[tauri::command]
+fn open_quick_translate(app: AppHandle) {
+    show_quick_translate(&app);
+}
+
+#[tauri::command]
+async fn translate_text(
+    state: tauri::State<'_, AppState>,
+    text: String,
+    target_language: Option<String>,
+) -> Result<String, CommandError> {
+    if text.trim().is_empty() {
+        return Err(i18n::t(Msg::TextEmpty).into());
+    }
+
+    let mut config = Config::clone(&state.config());
+    if let Some(language) = target_language.filter(|lang| !lang.trim().is_empty()) {
+        config.target_language = languages::normalize(&language);
+    }
+    if config.fast_mode {
+        apply_fast_mode(&mut config, &state.latency);
+    }
+    if config.target_language.trim().is_empty() {
+        return Err(i18n::t(Msg::TargetLanguageNotSet).into());
+    }
+    automodel::resolve(&mut config, &text);
+
+    let request_id = next_request_id();
+    let span = tracing::info_span!(
+        "translate_text",
+        request_id,
+        model = %config.model,
+        target_language = %config.target_language,
+        input_len = text.len()
+    );
+
+    let started = Instant::now();
+    let result = openrouter::translate(&state.http(), &config, &text)
+        .instrument(span.clone())
+        .await;
+    let elapsed_ms = started.elapsed().as_millis();
+    state
+        .metrics
+        .record(&config.model, elapsed_ms, result.is_ok(), text.len());
+    span.in_scope(|| match result {
+        Ok(translated) => {
+            state.latency.record(&config.model, elapsed_ms);
+            info!(translated_len = translated.len(), "Text translated");
+            Ok(translated)
+        }
+        Err(e) => {
+            error!(error = %e, "Text translation failed");
+            Err(e.into())
+        }
+    })
+}
+
+/// Sends oversized clipboard text that was held back by the size guard, optionally cut
+/// down to `max_input_chars`.
+#[tauri::command]
+async fn confirm_pending_input(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    truncate: bool,
+) -> Result<(), CommandError> {
+    let pending = state
+        .pending_input
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| i18n::t(Msg::NoInputWaiting))?;
+    let mut input = pending.input;
+    if truncate {
+        let limit = state.config().max_input_chars;
+        if let Some((end, _)) = input.text.char_indices().nth(limit) {
+            input.text.truncate(end);
+            // The formatted flavor no longer matches the shortened text
+            input.formatted = None;
+        }
+    }
+    info!(
+        action = pending.action.name(),
+        input_len = input.text.len(),
+        truncate,
+        "Oversized input confirmed"
+    );
+    execute_action(
+        app,
+        state,
+        pending.action,
+        pending.fast,
+        input,
+        None,
+        Delivery::Output,
+    )
+    .await
+}
+
+#[tauri::command]
+fn discard_pending_input(state: tauri::State<'_, AppState>) {
+    state.pending_input.lock().unwrap().take();
+}
+
+async fn run_action(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    action: Action,
+    fast: bool,
+) -> Result<(), CommandError> {
+    // Read clipboard
+    let primary_selection = state.config().primary_selection;
+    let input = clipboard::read_for_action(&app, primary_selection).map_err(|e| {
+        error!(error = %e, primary_selection, "Clipboard read failed");
+        show_toast(&app, "error", &i18n::t(Msg::ClipboardFailed));
+        e.to_string()
+    })?;
+    run_copied(app, state, action, fast, input).await
+}
+
+/// Like `run_action`, but first has the focused application copy its selection.
+async fn run_selection_action(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    action: Action,
+    fast: bool,
+) -> Result<(), CommandError> {
+    if !permissions::ensure(Permission::Accessibility) {
+        show_toast(&app, "error", &i18n::t(Msg::AllowAccessibility));
+        return Err(i18n::t(Msg::AllowAccessibility).into());
+    }
+    let input = clipboard::copy_selection(&app).await.map_err(|e| {
+        error!(error = %e, "Copying the selection failed");
+        show_toast(&app, "error", &i18n::t(Msg::ClipboardFailed));
+        e.to_string()
+    })?;
+    run_copied(app, state, action, fast, input).await
+}
+
+/// Runs an action on text just read from the clipboard, recording it in the history.
+async fn run_copied(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    action: Action,
+    fast: bool,
+    input: String,
+) -> Result<(), CommandError> {
+    if input.trim().is_empty() {
+        debug!("Clipboard was empty");
+        show_toast(&app, "error", &i18n::t(Msg::ClipboardEmpty));
+        return Err(CommandError::ClipboardEmpty);
+    }
+
+    let capacity = state.config().clipboard_history_size;
+    if capacity > 0 && state.clipboard_history.record(&input, capacity) {
+        refresh_tray_menu(&app);
+    }
+    run_input(app, state, action, fast, input).await
+}
+
+/// Runs an action on text taken from the clipboard or its history.
+async fn run_input(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    action: Action,
+    fast: bool,
+    input: String,
+) -> Result<(), CommandError> {
+    admit_input(&app, &state, action, fast, &input)?;
+    // The rich flavor belongs to the clipboard, not the PRIMARY selection
+    let formatted = action == Action::Translate && {
+        let config = state.config();
+        config.preserve_formatting && !config.primary_selection
+    };
+    let formatted = if formatted {
+        clipboard::read_formatted()
+    } else {
+        None
+    };
+    let input = Input {
+        text: input,
+        formatted,
+    };
+    execute_action(app, state, action, fast, input, None, Delivery::Output).await
+}
+
+/// Holds back a request estimated to cost more than `confirm_cost_above` until the same
+/// action is repeated on the same text.
+fn admit_cost(
+    app: &AppHandle,
+    state: &AppState,
+    action: Action,
+    fast: bool,
+    input: &Input,
+    config: &Config,
+    estimate: &estimate::Estimate,
+) -> Result<(), CommandError> {
+    let limit = config.confirm_cost_above;
+    let Some(cost) = estimate.cost.filter(|&cost| limit > 0.0 && cost > limit) else {
+        return Ok(());
+    };
+
+    if !hold_until_repeated(state, action, fast, input) {
+        info!(
+            action = action.name(),
+            cost, limit, "Expensive request repeated"
+        );
+        return Ok(());
+    }
+
+    info!(
+        action = action.name(),
+        cost,
+        limit,
+        prompt_tokens = estimate.prompt_tokens,
+        completion_tokens = estimate.completion_tokens,
+        "Expensive request held"
+    );
+    let _ = app.emit(
+        "cost-confirmation",
+        serde_json::json!({
+            "action": action.name(),
+            "estimate": estimate,
+            "limit": limit,
+        }),
+    );
+    let cost = estimate::format_cost(cost);
+    show_toast(app, "error", &i18n::t(Msg::CostRepeat(&cost)));
+    Err(i18n::t(Msg::CostOverLimit(&cost, &estimate::format_cost(limit))).into())
+}
+
+/// Refuses requests that `usage::check` would refuse once this month's usage reached
+/// `monthly_budget` or `monthly_token_limit`, with a toast. With `BudgetAction::Confirm` a
+/// repeated action is released; the returned flag says so, and the request then has to
+/// run inside `usage::confirmed`.
+fn admit_budget(
+    app: &AppHandle,
+    state: &AppState,
+    action: Action,
+    fast: bool,
+    input: &Input,
+    config: &Config,
+) -> Result<bool, CommandError> {
+    let Err(exceeded) = usage::check(config) else {
+        return Ok(false);
+    };
+    let usage = &exceeded.usage;
+
+    if config.budget_action == BudgetAction::Confirm
+        && !hold_until_repeated(state, action, fast, input)
+    {
+        info!(
+            action = action.name(),
+            month_cost = usage.cost,
+            month_tokens = usage.tokens(),
+            "Request over budget confirmed"
+        );
+        return Ok(true);
+    }
+
+    warn!(
+        action = action.name(),
+        month = %usage.month,
+        month_cost = usage.cost,
+        month_tokens = usage.tokens(),
+        budget = config.monthly_budget,
+        token_limit = config.monthly_token_limit,
+        budget_action = ?config.budget_action,
+        "Monthly budget reached"
+    );
+    let title = match config.budget_action {
+        BudgetAction::Block => Msg::BudgetReached,
+        BudgetAction::Confirm => Msg::BudgetReachedRepeat,
+    };
+    show_toast(app, "budget", &i18n::t(title));
+    Err(CommandError::BudgetExceeded(exceeded.to_string()))
+}
+
+/// Keeps `input` in `pending_cost` so that repeating the action releases it. Returns
+/// false when this request is that repeat.
+fn hold_until_repeated(state: &AppState, action: Action, fast: bool, input: &Input) -> bool {
+    let mut pending = state.pending_cost.lock().unwrap();
+    if pending
+        .as_ref()
+        .is_some_and(|held| held.action == action && held.input.text == input.text)
+    {
+        *pending = None;
+        return false;
+    }
+    *pending = Some(PendingInput {
+        action,
+        fast,
+        input: input.clone(),
+    });
+    true
+}
+
+/// Pricing of `model` from the models cache. An empty cache is filled in the background,
+/// so the next request gets a cost estimate.
+fn model_pricing(app: &AppHandle, state: &AppState, model: &str) -> Option<ModelPricing> {
+    if let Some(cached) = state.models_cache.lock().unwrap().as_ref() {
+        return cached
+            .models
+            .iter()
+            .find(|info| info.id == model)
+            .and_then(|info| info.pricing);
+    }
+    static FETCHING: AtomicBool = AtomicBool::new(false);
+    if FETCHING.swap(true, Ordering::SeqCst) {
+        return None;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let api_key = state.config().api_key.clone();
+        if !api_key.trim().is_empty() {
+            match openrouter::fetch_models(&state.http(), &api_key).await {
+                Ok(models) => {
+                    debug!(count = models.len(), "Models fetched for pricing");
+                    *state.models_cache.lock().unwrap() = Some(CachedModels {
+                        models,
+                        fetched_at: Instant::now(),
+                    });
+                }
+                Err(e) => warn!(error = %e, "Models fetch for pricing failed"),
+            }
+        }
+        FETCHING.store(false, Ordering::SeqCst);
+    });
+    None
+}
+
+/// Holds back clipboard text over `max_input_chars` until it is confirmed, either through
+/// `confirm_pending_input` or by repeating the same action on the same text.
+fn admit_input(
+    app: &AppHandle,
+    state: &tauri::State<'_, AppState>,
+    action: Action,
+    fast: bool,
+    input: &str,
+) -> Result<(), CommandError> {
+    let limit = state.config().max_input_chars;
+    let length = input.chars().count();
+    if limit == 0 || length <= limit {
+        return Ok(());
+    }
+
+    let mut pending = state.pending_input.lock().unwrap();
+    if pending
+        .as_ref()
+        .is_some_and(|held| held.action == action && held.input.text == input)
+    {
+        info!(
+            action = action.name(),
+            input_chars = length,
+            max_input_chars = limit,
+            "Oversized input repeated"
+        );
+        *pending = None;
+        return Ok(());
+    }
+
+    info!(
+        action = action.name(),
+        input_chars = length,
+        max_input_chars = limit,
+        "Oversized input held"
+    );
+    *pending = Some(PendingInput {
+        action,
+        fast,
+        input: Input::from(input.to_string()),
+    });
+    let _ = app.emit(
+        "input-too-large",
+        serde_json::json!({
+            "action": action.name(),
+            "length": length,
+            "limit": limit,
+        }),
+    );
+    show_toast(app, "error", &i18n::t(Msg::TooLongRepeat));
+    Err(i18n::t(Msg::InputTooLong(length, limit)).into())
+}
+
+/// Refuses a request because `max_concurrent_requests` are already running.
+fn busy(app: &AppHandle, state: &AppState) -> String {
+    let running = state.requests.list();
+    debug!(
+        running = running.len(),
+        oldest_ms = running.first().map(|request| request.elapsed_ms),
+        "Translation requested while busy"
+    );
+    show_toast(app, "error", &i18n::t(Msg::Busy));
+    i18n::t(Msg::RequestsInProgress(running.len()))
+}
+
+/// Runs an action and delivers its result as `delivery` says. `target_language` overrides
+/// the configured one for this request only.
+async fn execute_action(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    action: Action,
+    fast: bool,
+    input: Input,
+    target_language: Option<String>,
+    delivery: Delivery,
+) -> Result<(), CommandError> {
+    if shutdown::in_progress() {
+        return Err(i18n::t(Msg::Quitting).into());
+    }
+    let mut config = Config::clone(&state.config());
+    let limit = config.max_concurrent_requests;
+    if state.requests.len() >= limit.max(1) {
+        return Err(busy(&app, &state).into());
+    }
+
+    foreground::apply_rules(&mut config);
+    let fast = fast || config.fast_mode;
+    if fast {
+        apply_fast_mode(&mut config, &state.latency);
+    }
+    if let Some(language) = &target_language {
+        config.target_language = languages::normalize(language);
+        config.multi_target_languages.clear();
+    }
+    action.prepare(&mut config);
+    if action.needs_target_language(&config) && config.target_language.trim().is_empty() {
+        debug!("Missing target language");
+        show_toast(&app, "error", &i18n::t(Msg::MissingLanguage));
+        return Err(i18n::t(Msg::TargetLanguageNotSet).into());
+    }
+    automodel::resolve(&mut config, &input.text);
+    let cache_key =
+        (config.translation_cache_size > 0).then(|| CacheKey::new(action, &config, &input));
+    let cached = cache_key.as_ref().and_then(|key| state.results.get(key));
+    let from_cache = cached.is_some();
+    // A cached result costs nothing, so neither limit applies
+    let over_budget = !from_cache && admit_budget(&app, &state, action, fast, &input, &config)?;
+    let estimate = (!from_cache && (config.show_cost_estimate || config.confirm_cost_above > 0.0))
+        .then(|| {
+            let pricing = model_pricing(&app, &state, &config.model);
+            estimate::estimate(&config, &input.text, pricing)
+        });
+    // Repeating the action to go over the budget confirmed its cost as well
+    if let Some(estimate) = estimate.as_ref().filter(|_| !over_budget) {
+        admit_cost(&app, &state, action, fast, &input, &config, estimate)?;
+    }
+
+    let request_id = next_request_id();
+    let Some(in_flight) = state.requests.begin(
+        limit,
+        ActiveRequest {
+            request_id,
+            action: action.name(),
+            model: config.model.clone(),
+            target_language: config.target_language.clone(),
+            input_len: input.text.len(),
+            elapsed_ms: 0,
+        },
+    ) else {
+        return Err(busy(&app, &state).into());
+    };
+    let processing = match estimate.and_then(|estimate| estimate.cost) {
+        Some(cost) if config.show_cost_estimate => {
+            i18n::t(Msg::TranslatingCost(&estimate::format_cost(cost)))
+        }
+        _ => String::new(),
+    };
+    if !from_cache {
+        show_toast(&app, "processing", &processing);
+    }
+    let span = tracing::info_span!(
+        "translation",
+        request_id,
+        action = action.name(),
+        model = %config.model,
+        target_language = %config.target_language,
+        reasoning = ?config.reasoning,
+        fast,
+        input_len = input.text.len(),
+        formatted = input.formatted.as_ref().map(Formatted::kind),
+        elapsed_ms = tracing::field::Empty,
+        chars_per_sec = tracing::field::Empty
+    );
+    span.in_scope(|| {
+        info!("Translation triggered");
+    });
+    let _ = app.emit(
+        "translation-started",
+        serde_json::json!({
+            "request_id": request_id,
+            "action": action.name(),
+            "model": config.model,
+            "target_language": config.target_language,
+            "input_len": input.text.len(),
+            "fast": fast,
+            "estimate": estimate,
+        }),
+    );
+
+    let context = match action {
+        Action::Translate => conversation_context(&config),
+        _ => Vec::new(),
+    };
+    let started = Instant::now();
+    let ticker = spawn_progress(&app, request_id, started);
+    let result = match cached {
+        Some(output) => {
+            span.in_scope(|| info!("Result served from cache"));
+            Ok(output)
+        }
+        None => {
+            usage::confirmed(
+                over_budget,
+                action.run(&state.http(), &config, &input, &context),
+            )
+            .instrument(span.clone())
+            .await
+        }
+    };
+    ticker.abort();
+    let elapsed_ms = started.elapsed().as_millis();
+    span.record("elapsed_ms", elapsed_ms as u64);
+    if let Ok(output) = &result {
+        let secs = started.elapsed().as_secs_f64().max(0.001);
+        span.record(
+            "chars_per_sec",
+            (output.text.chars().count() as f64 / secs).round() as u64,
+        );
+    }
+    if !from_cache {
+        if result.is_ok() {
+            state.latency.record(&config.model, elapsed_ms);
+        }
+        state
+            .metrics
+            .record(&config.model, elapsed_ms, result.is_ok(), input.text.len());
+        if let (Some(key), Ok(output)) = (cache_key, &result) {
+            state
+                .results
+                .insert(key, output.clone(), config.translation_cache_size);
+        }
+    }
+    let failed = |code: &str, error: &str| {
+        let _ = app.emit(
+            "translation-failed",
+            serde_json::json!({
+                "request_id": request_id,
+                "action": action.name(),
+                "duration_ms": elapsed_ms,
+                "code": code,
+                "error": error,
+            }),
+        );
+    };
+
+    drop(in_flight);
+
+    // After caching, so changed hooks apply to cached results too
+    let result = match result {
+        Ok(output) if !config.post_hooks.is_empty() => Ok(output
+            .post_process(&config.post_hooks)
+            .instrument(span.clone())
+            .await),
+        result => result,
+    };
+
+    let outcome = span.in_scope(|| match result {
+        Ok(output) => {
+            let has_details = output.has_details();
+            let candidates = output.candidates;
+            let translated = output.text;
+            let to_clipboard =
+                delivery == Delivery::Output && config.output_mode == OutputMode::Clipboard;
+            if config.review_translations && to_clipboard {
+                info!(
+                    translated_len = translated.len(),
+                    "Translation held for review"
+                );
+                show_toast(&app, "success", &i18n::t(Msg::Review));
+                let _ = app.emit(
+                    "translation-finished",
+                    serde_json::json!({
+                        "request_id": request_id,
+                        "action": action.name(),
+                        "duration_ms": elapsed_ms,
+                        "output_len": translated.len(),
+                        "cached": from_cache,
+                    }),
+                );
+                show_review(
+                    &app,
+                    PendingReview {
+                        action,
+                        request_id,
+                        input: input.clone(),
+                        translation: translated,
+                        formatted: output.formatted,
+                        config: config.clone(),
+                        duration_ms: elapsed_ms as u64,
+                    },
+                );
+                return Ok(());
+            }
+            let delivered = lay_out(&config, action, &input.text, &translated);
+            if delivery == Delivery::Output {
+                state.clipboard_history.ignore(&delivered);
+            }
+            let formatted = output
+                .formatted
+                .as_ref()
+                .filter(|_| config.output_layout == OutputLayout::Replace);
+            // What the write replaces, which is not always the input: a selection copy
+            // or the PRIMARY selection never touched the clipboard
+            let snapshot = to_clipboard.then(|| clipboard::snapshot(&app));
+            let written = match (config.output_mode, formatted) {
+                _ if delivery == Delivery::Window => Ok(()),
+                (OutputMode::Type, _) => {
+                    typing::spawn(&app, delivered, config.typing_chars_per_sec);
+                    Ok(())
+                }
+                (OutputMode::Clipboard, Some(formatted)) => {
+                    clipboard::write_formatted(formatted, &translated)
+                }
+                (OutputMode::Clipboard, None) => clipboard::write_text(&app, &delivered),
+            };
+            written.map_err(|e| {
+                error!(error = %e, "Clipboard write failed");
+                show_toast(&app, "error", &i18n::t(Msg::ClipboardFailed));
+                failed("clipboard", &e.to_string());
+                e.to_string()
+            })?;
+            info!(
+                translated_len = translated.len(),
+                from_cache, "Translation applied"
+            );
+            show_toast(&app, if from_cache { "cached" } else { "success" }, "");
+            if let Some(snapshot) = snapshot {
+                *state.undo_snapshot.lock().unwrap() = Some(snapshot);
+                refresh_tray_menu(&app);
+            }
+            record_translation(
+                &state,
+                &config,
+                action,
+                request_id,
+                &input.text,
+                &translated,
+                elapsed_ms as u64,
+            );
+            let _ = app.emit(
+                "translation-finished",
+                serde_json::json!({
+                    "request_id": request_id,
+                    "action": action.name(),
+                    "duration_ms": elapsed_ms,
+                    "output_len": translated.len(),
+                    "cached": from_cache,
+                }),
+            );
+            if candidates.len() > 1 && to_clipboard {
+                show_candidates(&app, candidates);
+            }
+            if has_details || delivery == Delivery::Window {
+                show_result(
+                    &app,
+                    TranslationResult {
+                        source: input.text.clone(),
+                        translation: translated,
+                        romanization: output.romanization,
+                        dictionary: output.dictionary,
+                        translations: output.targets,
+                    },
+                );
+            }
+            Ok(())
+        }
+        Err(e) => {
+            error!(error = %e, "Translation failed");
+            // Replays only go to the result window, so any output mode can queue
+            if config.offline_queue
+                && openrouter::is_offline(&e)
+                && state
+                    .offline_queue
+                    .push(action, input.clone(), target_language.clone())
+            {
+                info!(
+                    queued = state.offline_queue.len(),
+                    "Input queued until online"
+                );
+                show_toast(&app, "error", &i18n::t(Msg::OfflineQueued));
+                failed("queued", &e.to_string());
+                return Err(i18n::t(Msg::QueuedUntilReachable(&e.to_string())).into());
+            }
+            let error = CommandError::from(&e);
+            show_toast(&app, "error", &error.toast());
+            failed(error.code(), &e.to_string());
+            Err(error)
+        }
+    });
+    *state.last_outcome.lock().unwrap() = Some(RequestOutcome {
+        ok: outcome.is_ok(),
+        duration_ms: elapsed_ms as u64,
+    });
+    refresh_tray_tooltip(&app);
+    if config.completion_sound {
+        sound::play(if outcome.is_ok() {
+            sound::Cue::Success
+        } else {
+            sound::Cue::Failure
+        });
+    }
+    outcome
+}
+
+/// Emits `translation-progress` with the elapsed time every `PROGRESS_INTERVAL` until
+/// aborted, so the processing toast can show a live timer.
+fn spawn_progress(
+    app: &AppHandle,
+    request_id: u64,
+    started: Instant,
+) -> tauri::async_runtime::JoinHandle<()> {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(PROGRESS_INTERVAL).await;
+            let _ = app.emit(
+                "translation-progress",
+                serde_json::json!({
+                    "request_id": request_id,
+                    "elapsed_ms": started.elapsed().as_millis() as u64,
+                }),
+            );
+        }
+    })
+}
+
+/// Translates a file, emitting `file-translation-progress` after each chunk and
+/// `file-translation-finished` or `file-translation-failed` at the end.
+async fn run_file_translation(
+    app: &AppHandle,
+    config: Arc<Config>,
+    path: PathBuf,
+) -> Result<String, CommandError> {
+    if config.target_language.trim().is_empty() {
+        show_toast(app, "error", &i18n::t(Msg::MissingLanguage));
+        return Err(i18n::t(Msg::TargetLanguageNotSet).into());
+    }
+
+    let event_path = path.display().to_string();
+    let span = tracing::info_span!(
+        "file_translation",
+        request_id = next_request_id(),
+        path = %event_path,
+        model = %config.model,
+        target_language = %config.target_language
+    );
+    show_toast(app, "processing", "");
+
+    let progress_app = app.clone();
+    let progress_path = event_path.clone();
+    let client = app.state::<AppState>().http();
+    let result = files::translate_file(&client, &config, &path, move |done, total| {
+        let _ = progress_app.emit(
+            "file-translation-progress",
+            serde_json::json!({ "path": progress_path, "done": done, "total": total }),
+        );
+    })
+    .instrument(span.clone())
+    .await;
+
+    span.in_scope(|| match result {
+        Ok(output) => {
+            let output = output.display().to_string();
+            let _ = app.emit(
+                "file-translation-finished",
+                serde_json::json!({ "path": event_path, "output": output }),
+            );
+            show_toast(app, "success", &i18n::t(Msg::FileSaved));
+            Ok(output)
+        }
+        Err(e) => {
+            error!(error = %e, "File translation failed");
+            let error = CommandError::from(&e);
+            let _ = app.emit(
+                "file-translation-failed",
+                serde_json::json!({
+                    "path": event_path,
+                    "code": error.code(),
+                    "error": e.to_string(),
+                }),
+            );
+            let mut message = error.toast();
+            if message.is_empty() {
+                message = i18n::t(Msg::FileFailed);
+            }
+            show_toast(app, "error", &message);
+            Err(error)
+        }
+    })
+}
+
+fn pick_file_to_translate(app: &AppHandle) {
+    let handle = app.clone();
+    app.dialog()
+        .file()
+        .set_title(i18n::t(Msg::DialogTranslateFile))
+        .add_filter(i18n::t(Msg::FilterText), files::TEXT_EXTENSIONS)
+        .add_filter(i18n::t(Msg::FilterSubtitles), files::SUBTITLE_EXTENSIONS)
+        .pick_file(move |picked| {
+            let Some(path) = picked.and_then(|picked| picked.into_path().ok()) else {
+                return;
+            };
+            tauri::async_runtime::spawn(async move {
+                let config = handle.state::<AppState>().config();
+                let _ = run_file_translation(&handle, config, path).await;
+            });
+        });
+}
+
+fn spawn_action(app: &AppHandle, action: Action) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let _ = run_action(app.clone(), state, action, false).await;
+    });
+}
+
+fn spawn_selection_action(app: &AppHandle, action: Action) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let _ = run_selection_action(app.clone(), state, action, false).await;
+    });
+}
+
+/// Handles `thirdspace://translate?text=...&lang=...` when `deep_links` is on. Any web page
+/// can open such a link, so each one is confirmed first and its result only goes to the
+/// result window. `lang` overrides the target language for that request.
+fn handle_deep_link(app: &AppHandle, url: &tauri::Url) {
+    // The URL carries the text, so only its shape is logged
+    if url.scheme() != DEEP_LINK_SCHEME || url.host_str() != Some("translate") {
+        warn!(
+            scheme = url.scheme(),
+            host = ?url.host_str(),
+            "Unsupported deep link"
+        );
+        return;
+    }
+    let config = app.state::<AppState>().config();
+    if !config.deep_links {
+        warn!("Deep link ignored, deep links are off");
+        show_toast(app, "error", &i18n::t(Msg::DeepLinksOff));
+        return;
+    }
+    let (mut text, mut language) = (None, None);
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "text" => text = Some(value.into_owned()),
+            "lang" => language = Some(value.into_owned()),
+            _ => {}
+        }
+    }
+    let Some(text) = text.filter(|text| !text.trim().is_empty()) else {
+        show_toast(app, "error", &i18n::t(Msg::MissingText));
+        return;
+    };
+    let language = language.filter(|language| !language.trim().is_empty());
+    info!(text_len = text.len(), language = ?language, "Deep link received");
+    // Held input is released onto the clipboard, so oversized text is refused instead
+    let length = text.chars().count();
+    let limit = config.max_input_chars;
+    if limit > 0 && length > limit {
+        show_toast(app, "error", &i18n::t(Msg::InputTooLong(length, limit)));
+        return;
+    }
+
+    let preview = match text.char_indices().nth(DEEP_LINK_PREVIEW_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.clone(),
+    };
+    let handle = app.clone();
+    app.dialog()
+        .message(i18n::t(Msg::DeepLinkConfirm(&preview)))
+        .title(i18n::t(Msg::DialogDeepLink))
+        .buttons(MessageDialogButtons::OkCancel)
+        .show(move |confirmed| {
+            if !confirmed {
+                debug!("Deep link declined");
+                return;
+            }
+            tauri::async_runtime::spawn(async move {
+                let _ = execute_action(
+                    handle.clone(),
+                    handle.state::<AppState>(),
+                    Action::Translate,
+                    false,
+                    Input::from(text),
+                    language,
+                    Delivery::Window,
+                )
+                .await;
+            });
+        });
+}
+
+fn spawn_history_translation(app: &AppHandle, index: usize) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let Some(text) = state.clipboard_history.get(index) else {
+            return;
+        };
+        let _ = run_input(app.clone(), state, Action::Translate, false, text).await;
+    });
+}
+
+/// Routes a request to the fastest favorite model and turns reasoning off.
+fn apply_fast_mode(config: &mut Config, latency: &LatencyTracker) {
+    if let Some(model) = latency.fastest(&config.favorite_models) {
+        config.model = model.clone();
+    }
+    config.reasoning = ReasoningEffort::Off;
+}
+
+fn build_tray_menu(app: &AppHandle, config: &Config) -> tauri::Result<Menu<Wry>> {
+    let translate_item = MenuItem::with_id(
+        app,
+        "translate",
+        i18n::t(Msg::MenuTranslate),
+        true,
+        None::<&str>,
+    )?;
+    let selection_item = MenuItem::with_id(
+        app,
+        "translate_selection",
+        i18n::t(Msg::MenuTranslateSelection),
+        true,
+        None::<&str>,
+    )?;
+    let commit_item = MenuItem::with_id(
+        app,
+        "translate_commit",
+        i18n::t(Msg::MenuTranslateCommit),
+        true,
+        None::<&str>,
+    )?;
+    let file_item = MenuItem::with_id(
+        app,
+        "translate_file",
+        i18n::t(Msg::MenuTranslateFile),
+        true,
+        None::<&str>,
+    )?;
+    let fast_mode = CheckMenuItem::with_id(
+        app,
+        "fast_mode",
+        i18n::t(Msg::MenuFastMode),
+        true,
+        config.fast_mode,
+        None::<&str>,
+    )?;
+    let summarize_item = MenuItem::with_id(
+        app,
+        "summarize",
+        i18n::t(Msg::MenuSummarize),
+        true,
+        None::<&str>,
+    )?;
+    let proofread_item = MenuItem::with_id(
+        app,
+        "proofread",
+        i18n::t(Msg::MenuProofread),
+        true,
+        None::<&str>,
+    )?;
+    let mut tone_items = Vec::with_capacity(Tone::ALL.len());
+    for tone in Tone::ALL {
+        tone_items.push(MenuItem::with_id(
+            app,
+            format!("{}{}", REWRITE_MENU_PREFIX, tone.id()),
+            i18n::t(Msg::MenuTone(tone)),
+            true,
+            None::<&str>,
+        )?);
+    }
+    let tone_refs: Vec<&dyn IsMenuItem<Wry>> = tone_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let rewrite_menu =
+        Submenu::with_id_and_items(app, "rewrite", i18n::t(Msg::MenuRewrite), true, &tone_refs)?;
+    let mut custom_items = Vec::with_capacity(config.actions.len());
+    for (idx, action) in config.actions.iter().enumerate() {
+        custom_items.push(MenuItem::with_id(
+            app,
+            format!("{}{}", CUSTOM_ACTION_MENU_PREFIX, idx),
+            &action.name,
+            true,
+            None::<&str>,
+        )?);
+    }
+    let custom_refs: Vec<&dyn IsMenuItem<Wry>> = custom_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let custom_menu = Submenu::with_id_and_items(
+        app,
+        "actions",
+        i18n::t(Msg::MenuActions),
+        !custom_refs.is_empty(),
+        &custom_refs,
+    )?;
+    let history = app.state::<AppState>().clipboard_history.entries();
+    let mut history_items = Vec::with_capacity(history.len());
+    for (idx, entry) in history.iter().enumerate() {
+        history_items.push(MenuItem::with_id(
+            app,
+            format!("{}{}", HISTORY_MENU_PREFIX, idx),
+            history::preview(entry, HISTORY_PREVIEW_CHARS),
+            true,
+            None::<&str>,
+        )?);
+    }
+    let history_refs: Vec<&dyn IsMenuItem<Wry>> = history_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let history_menu = Submenu::with_id_and_items(
+        app,
+        "history",
+        i18n::t(Msg::MenuTranslateRecent),
+        !history_refs.is_empty(),
+        &history_refs,
+    )?;
+    let pinned = app.state::<AppState>().pinned.lock().unwrap().clone();
+    let mut pinned_items = Vec::with_capacity(pinned.len());
+    for (idx, record) in pinned.iter().enumerate() {
+        pinned_items.push(MenuItem::with_id(
+            app,
+            format!("{}{}", PINNED_MENU_PREFIX, idx),
+            history::preview(&record.translation, HISTORY_PREVIEW_CHARS),
+            true,
+            None::<&str>,
+        )?);
+    }
+    let pinned_refs: Vec<&dyn IsMenuItem<Wry>> = pinned_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let pinned_menu = Submenu::with_id_and_items(
+        app,
+        "pinned",
+        i18n::t(Msg::MenuPinned),
+        !pinned_refs.is_empty(),
+        &pinned_refs,
+    )?;
+    let can_undo = app
+        .state::<AppState>()
+        .undo_snapshot
+        .lock()
+        .unwrap()
+        .is_some();
+    let undo_item = MenuItem::with_id(app, "undo", i18n::t(Msg::MenuUndo), can_undo, None::<&str>)?;
+    let capture_item = MenuItem::with_id(
+        app,
+        "capture_region",
+        i18n::t(Msg::MenuCaptureRegion),
+        true,
+        None::<&str>,
+    )?;
+    let quick_item = MenuItem::with_id(
+        app,
+        "quick_translate",
+        i18n::t(Msg::MenuQuickTranslate),
+        true,
+        None::<&str>,
+    )?;
+    let pause_label = if *app.state::<AppState>().paused.lock().unwrap() {
+        Msg::MenuResume
+    } else {
+        Msg::MenuPause
+    };
+    let pause_item = MenuItem::with_id(app, "pause", i18n::t(pause_label), true, None::<&str>)?;
+    let update_item = MenuItem::with_id(
+        app,
+        "check_updates",
+        i18n::t(Msg::MenuCheckUpdates),
+        true,
+        None::<&str>,
+    )?;
+    let settings = MenuItem::with_id(
+        app,
+        "settings",
+        i18n::t(Msg::MenuSettings),
+        true,
+        None::<&str>,
+    )?;
+    let quit = MenuItem::with_id(app, "quit", i18n::t(Msg::MenuQuit), true, None::<&str>)?;
 
-    // Save config
-    *state.config.lock().unwrap() = new_config.clone();
-    config::save(&new_config).map_err(|e| e.to_string())?;
+    // Keep the active language visible even if it was typed in settings
+    // and never added to the configured list.
+    let mut languages = config.languages.clone();
+    let active = config.target_language.trim();
+    if !active.is_empty() && !languages.iter().any(|lang| lang.trim() == active) {
+        languages.insert(0, active.to_string());
+    }
 
-    info!(
-        model = %new_config.model,
-        target_language = %new_config.target_language,
-        reasoning = new_config.reasoning_enabled,
-        hotkey = %new_config.hotkey,
-        autostart = new_config.autostart,
-        "Settings saved"
-    );
+    let mut language_items = Vec::with_capacity(languages.len());
+    for lang in languages
+        .iter()
+        .map(|lang| lang.trim())
+        .filter(|lang| !lang.is_empty())
+    {
+        language_items.push(CheckMenuItem::with_id(
+            app,
+            format!("{}{}", LANGUAGE_MENU_PREFIX, lang),
+            lang,
+            true,
+            lang == active,
+            None::<&str>,
+        )?);
+    }
+    let separator = PredefinedMenuItem::separator(app)?;
+    let next_language = MenuItem::with_id(
+        app,
+        "next_language",
+        i18n::t(Msg::MenuNextLanguage),
+        true,
+        None::<&str>,
+    )?;
+    let mut language_refs: Vec<&dyn IsMenuItem<Wry>> = language_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    if language_items.len() > 1 {
+        language_refs.push(&separator);
+        language_refs.push(&next_language);
+    }
+    let language_menu = Submenu::with_id_and_items(
+        app,
+        "language",
+        i18n::t(Msg::MenuTargetLanguage),
+        !language_refs.is_empty(),
+        &language_refs,
+    )?;
 
-    show_toast(&app, "success", "Saved");
-    Ok(())
+    Menu::with_items(
+        app,
+        &[
+            &translate_item,
+            &selection_item,
+            &commit_item,
+            &file_item,
+            &capture_item,
+            &history_menu,
+            &pinned_menu,
+            &undo_item,
+            &summarize_item,
+            &proofread_item,
+            &rewrite_menu,
+            &custom_menu,
+            &language_menu,
+            &fast_mode,
+            &quick_item,
+            &pause_item,
+            &update_item,
+            &settings,
+            &quit,
+        ],
+    )
 }
 
-#[tauri::command]
-fn pause_hotkey(app: AppHandle, state: tauri::State<'_, AppState>) -> Result<(), String> {
-    let shortcut = state.current_shortcut.lock().unwrap();
-    if let Some(s) = shortcut.as_ref() {
-        app.global_shortcut()
-            .unregister(*s)
-            .map_err(|e| e.to_string())?;
-        debug!("Hotkey paused for recording");
+fn refresh_tray_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let config = app.state::<AppState>().config();
+    match build_tray_menu(app, &config) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                error!(error = %e, "Failed to update tray menu");
+            }
+        }
+        Err(e) => error!(error = %e, "Failed to build tray menu"),
     }
-    Ok(())
+    if let Err(e) = tray.set_show_menu_on_left_click(config.tray_click == TrayClick::Menu) {
+        warn!(error = %e, "Failed to update tray click behavior");
+    }
+    refresh_tray_tooltip(app);
 }
 
-#[tauri::command]
-fn resume_hotkey(app: AppHandle, state: tauri::State<'_, AppState>) -> Result<(), String> {
-    let shortcut = state.current_shortcut.lock().unwrap();
-    if let Some(s) = shortcut.as_ref() {
-        app.global_shortcut()
-            .register(*s)
-            .map_err(|e| e.to_string())?;
-        debug!("Hotkey resumed after recording");
+/// Runs `tray_click` for a left click on the tray icon; `Menu` is left to the OS.
+fn handle_tray_click(app: &AppHandle) {
+    let tray_click = app.state::<AppState>().config().tray_click;
+    debug!(tray_click = ?tray_click, "Tray icon clicked");
+    match tray_click {
+        TrayClick::Menu => {}
+        TrayClick::Translate => spawn_action(app, Action::Translate),
+        TrayClick::QuickTranslate => show_quick_translate(app),
+        TrayClick::Settings => {
+            open_settings(app);
+        }
     }
-    Ok(())
 }
 
-#[tauri::command]
-async fn fetch_models(state: tauri::State<'_, AppState>) -> Result<Vec<ModelInfo>, String> {
-    // Check if we have cached models
-    {
-        let cache = state.models_cache.lock().unwrap();
-        if let Some(models) = cache.as_ref() {
-            debug!(count = models.len(), "Returning cached models");
-            return Ok(models.clone());
-        }
+/// App name and pause state, the target language and model, and how the last request
+/// went, one per line.
+fn tray_tooltip(app: &AppHandle) -> String {
+    let state = app.state::<AppState>();
+    let config = state.config();
+    let mut lines = vec![if *state.paused.lock().unwrap() {
+        format!("ThirdSpace ({})", i18n::t(Msg::Paused))
+    } else {
+        "ThirdSpace".to_string()
+    }];
+    let target = config.target_language.trim();
+    lines.push(if target.is_empty() {
+        config.model.clone()
+    } else {
+        format!("{} · {}", target, config.model)
+    });
+    if let Some(outcome) = *state.last_outcome.lock().unwrap() {
+        let secs = format!("{:.1}", outcome.duration_ms as f64 / 1000.0);
+        lines.push(i18n::t(if outcome.ok {
+            Msg::TooltipLastDone(&secs)
+        } else {
+            Msg::TooltipLastFailed(&secs)
+        }));
     }
+    lines.join("\n")
+}
 
-    // Get API key from config
-    let api_key = {
-        let config = state.config.lock().unwrap();
-        config.api_key.clone()
+fn refresh_tray_tooltip(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
     };
-
-    if api_key.trim().is_empty() {
-        return Err("API key not configured".to_string());
+    if let Err(e) = tray.set_tooltip(Some(tray_tooltip(app))) {
+        warn!(error = %e, "Failed to update tray tooltip");
     }
+}
 
-    // Fetch from OpenRouter
-    let models = openrouter::fetch_models(&api_key)
-        .await
-        .map_err(|e| e.to_string())?;
+fn set_target_language(app: &AppHandle, language: &str) {
+    let state = app.state::<AppState>();
+    let new_config = state.update_config(|config| config.target_language = language.to_string());
 
-    // Cache the results
-    {
-        let mut cache = state.models_cache.lock().unwrap();
-        *cache = Some(models.clone());
+    if let Err(e) = config::save(&new_config) {
+        error!(error = %e, "Failed to save target language");
+        show_toast(app, "error", &i18n::t(Msg::SaveFailed));
+        return;
     }
 
-    info!(count = models.len(), "Models fetched and cached");
-    Ok(models)
+    refresh_tray_menu(app);
+    info!(target_language = %language, "Target language switched");
+    show_toast(app, "success", language);
 }
 
-#[tauri::command]
-async fn translate(app: AppHandle, state: tauri::State<'_, AppState>) -> Result<(), String> {
-    {
-        let in_flight = state.translate_in_flight.lock().unwrap();
-        if *in_flight {
-            debug!("Translation requested while busy");
-            show_toast(&app, "error", "Busy");
-            return Err("Translation already in progress".to_string());
-        }
+/// Switches the target to the language after the current one in `Config::languages`,
+/// wrapping around. A target outside the list moves to the first entry.
+fn cycle_target_language(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let next = {
+        let config = state.config();
+        let languages: Vec<&str> = config
+            .languages
+            .iter()
+            .map(|lang| lang.trim())
+            .filter(|lang| !lang.is_empty())
+            .collect();
+        let current = languages
+            .iter()
+            .position(|lang| lang.eq_ignore_ascii_case(config.target_language.trim()));
+        let next = match current {
+            Some(idx) => languages.get((idx + 1) % languages.len()),
+            None => languages.first(),
+        };
+        next.map(|lang| lang.to_string())
+    };
+    match next {
+        Some(language) => set_target_language(app, &language),
+        None => show_toast(app, "error", &i18n::t(Msg::NoLanguages)),
     }
+}
 
-    // Read clipboard
-    let input = app
-        .clipboard()
-        .read_text()
-        .map_err(|e| {
-            error!(error = %e, "Clipboard read failed");
-            show_toast(&app, "error", "Clipboard failed");
-            e.to_string()
-        })?;
-
-    if input.trim().is_empty() {
-        debug!("Clipboard was empty");
-        show_toast(&app, "error", "Clipboard empty");
-        return Err("Clipboard is empty".to_string());
-    }
+fn toggle_fast_mode(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let new_config = state.update_config(|config| config.fast_mode = !config.fast_mode);
 
-    let config = state.config.lock().unwrap().clone();
-    if config.target_language.trim().is_empty() {
-        debug!("Missing target language");
-        show_toast(&app, "error", "Missing language");
-        return Err("Target language not set".to_string());
+    if let Err(e) = config::save(&new_config) {
+        error!(error = %e, "Failed to save fast mode");
+        show_toast(app, "error", &i18n::t(Msg::SaveFailed));
+        return;
     }
 
-    // Mark as in-flight
-    *state.translate_in_flight.lock().unwrap() = true;
-    show_toast(&app, "processing", "");
-    let request_id = next_request_id();
-    let span = tracing::info_span!(
-        "translation",
-        request_id,
-        model = %config.model,
-        target_language = %config.target_language,
-        reasoning = config.reasoning_enabled,
-        input_len = input.len()
+    refresh_tray_menu(app);
+    info!(
+        fast_mode = new_config.fast_mode,
+        "Fast mode toggled from tray"
     );
-    span.in_scope(|| {
-        info!("Translation triggered");
-    });
+    let title = if new_config.fast_mode {
+        Msg::FastModeOn
+    } else {
+        Msg::FastModeOff
+    };
+    show_toast(app, "success", &i18n::t(title));
+}
 
-    let result = openrouter::translate(&config, &input)
-        .instrument(span.clone())
-        .await;
+fn toggle_pause(app: &AppHandle) {
+    let paused = *app.state::<AppState>().paused.lock().unwrap();
+    apply_pause(app, !paused);
+}
 
-    // Mark as complete
-    *state.translate_in_flight.lock().unwrap() = false;
+/// Pauses or resumes ThirdSpace as a whole: every hotkey but the pause toggle and the
+/// clipboard watcher go quiet, and the tray icon turns grey. Unlike `pause_hotkey`, which
+/// only covers recording a new hotkey, this lasts until resumed.
+fn apply_pause(app: &AppHandle, paused: bool) {
+    let state = app.state::<AppState>();
+    if std::mem::replace(&mut *state.paused.lock().unwrap(), paused) == paused {
+        return;
+    }
 
-    span.in_scope(|| match result {
-        Ok(translated) => {
-            app.clipboard()
-                .write_text(&translated)
-                .map_err(|e| {
-                    error!(error = %e, "Clipboard write failed");
-                    show_toast(&app, "error", "Clipboard failed");
-                    e.to_string()
-                })?;
-            info!(translated_len = translated.len(), "Translation applied");
-            show_toast(&app, "success", "");
-            Ok(())
+    for shortcut in pausable_shortcuts(&state) {
+        let result = if paused {
+            app.global_shortcut().unregister(shortcut)
+        } else {
+            app.global_shortcut().register(shortcut)
+        };
+        if let Err(e) = result {
+            warn!(error = %e, paused, "Failed to toggle hotkey");
         }
-        Err(e) => {
-            error!(error = %e, "Translation failed");
-            show_toast(&app, "error", "");
-            Err(e.to_string())
+    }
+    if let (Some(tray), Some(icon)) = (app.tray_by_id(TRAY_ID), app.default_window_icon()) {
+        let icon = if paused {
+            greyed_icon(icon)
+        } else {
+            icon.clone()
+        };
+        if let Err(e) = tray.set_icon(Some(icon)) {
+            error!(error = %e, "Failed to update tray icon");
         }
-    })
+    }
+    refresh_tray_menu(app);
+    let _ = app.emit("pause-changed", serde_json::json!({ "paused": paused }));
+
+    info!(paused, "Pause toggled");
+    let title = if paused { Msg::Paused } else { Msg::Resumed };
+    show_toast(app, "success", &i18n::t(title));
 }
 
-fn show_toast(app: &AppHandle, kind: &str, title: &str) {
-    const TOAST_WIDTH: f64 = 200.0;
-    const TOAST_HEIGHT: f64 = 56.0;
-    const MARGIN: f64 = 16.0;
-    const TASKBAR_HEIGHT: f64 = 48.0;
+/// Greyscale, half-transparent copy of the tray icon shown while paused.
+fn greyed_icon(icon: &tauri::image::Image<'_>) -> tauri::image::Image<'static> {
+    let rgba = icon
+        .rgba()
+        .chunks_exact(4)
+        .flat_map(|px| {
+            let luma = ((px[0] as u32 * 30 + px[1] as u32 * 59 + px[2] as u32 * 11) / 100) as u8;
+            [luma, luma, luma, px[3] / 2]
+        })
+        .collect();
+    tauri::image::Image::new_owned(rgba, icon.width(), icon.height())
+}
 
-    // Create or get toast window
-    let (toast, is_new) = match app.get_webview_window("toast") {
-        Some(w) => (w, false),
-        None => {
-            // Pass initial state via URL query params
-            let url = format!("toast.html?kind={}&title={}", kind, urlencoding(title));
-            match WebviewWindowBuilder::new(app, "toast", WebviewUrl::App(url.into()))
-                .title("Toast")
-                .decorations(false)
-                .transparent(true)
-                .shadow(false)
-                .always_on_top(true)
-                .skip_taskbar(true)
-                .resizable(false)
-                .inner_size(TOAST_WIDTH, TOAST_HEIGHT)
-                .visible(false)
-                .build()
-            {
-                Ok(w) => (w, true),
-                Err(e) => {
-                    error!(error = %e, "Failed to create toast window");
-                    return;
-                }
-            }
-        }
+/// Stores the result and shows it in the result window, creating it on first use.
+/// A new window fetches the result via `get_result`; an open one gets an event.
+fn show_result(app: &AppHandle, result: TranslationResult) {
+    *app.state::<AppState>().last_result.lock().unwrap() = Some(result.clone());
+
+    if let Some(window) = app.get_webview_window("result") {
+        let _ = window.emit("result-updated", &result);
+        let _ = window.show();
+        let _ = window.set_focus();
+        return;
+    }
+
+    match WebviewWindowBuilder::new(app, "result", WebviewUrl::App("result.html".into()))
+        .title(i18n::t(Msg::WindowResult))
+        .inner_size(420.0, 300.0)
+        .min_inner_size(320.0, 200.0)
+        .always_on_top(true)
+        .center()
+        .build()
+    {
+        Ok(_) => debug!("Result window opened"),
+        Err(e) => error!(error = %e, "Result window failed"),
+    }
+}
+
+/// Holds `review` and shows it in the review window, creating it on first use. A review
+/// still waiting is replaced.
+fn show_review(app: &AppHandle, review: PendingReview) {
+    let item = ReviewItem {
+        source: review.input.text.clone(),
+        translation: review.translation.clone(),
+        target_language: review.config.target_language.clone(),
     };
+    let replaced = app
+        .state::<AppState>()
+        .pending_review
+        .lock()
+        .unwrap()
+        .replace(review);
+    if let Some(replaced) = replaced {
+        debug!(
+            request_id = replaced.request_id,
+            "Unreviewed translation replaced"
+        );
+    }
 
-    // Position toast at bottom-right corner
-    if let Some(monitor) = toast.primary_monitor().ok().flatten() {
-        let screen_size = monitor.size();
-        let scale = monitor.scale_factor();
-        let screen_w = screen_size.width as f64 / scale;
-        let screen_h = screen_size.height as f64 / scale;
-        let x = screen_w - TOAST_WIDTH - MARGIN;
-        let y = screen_h - TOAST_HEIGHT - MARGIN - TASKBAR_HEIGHT;
-        let _ = toast.set_position(tauri::PhysicalPosition::new(
-            (x * scale) as i32,
-            (y * scale) as i32,
-        ));
+    if let Some(window) = app.get_webview_window("review") {
+        let _ = window.emit("review-updated", &item);
+        let _ = window.show();
+        let _ = window.set_focus();
+        return;
     }
 
-    // Update toast content via event (only for existing windows)
-    if !is_new {
-        let _ = toast.emit("update-toast", serde_json::json!({
-            "kind": kind,
-            "title": title
-        }));
+    match WebviewWindowBuilder::new(app, "review", WebviewUrl::App("review.html".into()))
+        .title(i18n::t(Msg::WindowReview))
+        .inner_size(460.0, 360.0)
+        .min_inner_size(320.0, 240.0)
+        .always_on_top(true)
+        .center()
+        .build()
+    {
+        Ok(_) => debug!("Review window opened"),
+        Err(e) => error!(error = %e, "Review window failed"),
+    }
+}
+
+/// Offers the alternatives of the last translation in the chooser popup, creating it on
+/// first use. Picking one calls `choose_candidate`.
+fn show_candidates(app: &AppHandle, candidates: Vec<String>) {
+    *app.state::<AppState>().candidates.lock().unwrap() = candidates.clone();
+
+    if let Some(window) = app.get_webview_window("candidates") {
+        let _ = window.emit("candidates-updated", &candidates);
+        let _ = window.show();
+        let _ = window.set_focus();
+        return;
+    }
+
+    match WebviewWindowBuilder::new(app, "candidates", WebviewUrl::App("candidates.html".into()))
+        .title(i18n::t(Msg::WindowCandidates))
+        .inner_size(420.0, 320.0)
+        .min_inner_size(320.0, 200.0)
+        .always_on_top(true)
+        .center()
+        .build()
+    {
+        Ok(_) => debug!("Candidates window opened"),
+        Err(e) => error!(error = %e, "Candidates window failed"),
     }
+}
 
-    // Show toast
-    let _ = toast.show();
+/// Shows the quick translate popup, which translates typed text through
+/// `translate_text` instead of the clipboard pipeline.
+fn show_quick_translate(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("quick") {
+        let _ = window.emit("quick-translate-opened", ());
+        let _ = window.show();
+        let _ = window.set_focus();
+        debug!("Quick translate window reused");
+        return;
+    }
 
-    // Schedule hide after duration
-    let app_handle = app.clone();
-    std::thread::spawn(move || {
-        std::thread::sleep(Duration::from_millis(TOAST_DURATION_MS));
-        if let Some(toast) = app_handle.get_webview_window("toast") {
-            let _ = toast.hide();
+    match WebviewWindowBuilder::new(app, "quick", WebviewUrl::App("quick.html".into()))
+        .title(i18n::t(Msg::WindowQuickTranslate))
+        .inner_size(420.0, 320.0)
+        .min_inner_size(320.0, 240.0)
+        .always_on_top(true)
+        .center()
+        .build()
+    {
+        Ok(_) => info!("Quick translate window opened"),
+        Err(e) => {
+            error!(error = %e, "Quick translate window failed");
+            show_toast(app, "error", &i18n::t(Msg::QuickTranslateFailed));
         }
-    });
+    }
 }
 
 fn open_settings(app: &AppHandle) {
@@ -313,18 +3516,34 @@ fn open_settings(app: &AppHandle) {
         return;
     }
 
-    match WebviewWindowBuilder::new(app, "settings", WebviewUrl::App("settings.html".into()))
-        .title("ThirdSpace Settings")
-        .inner_size(480.0, 520.0)
-        .resizable(false)
-        .maximizable(false)
-        .center()
-        .build()
-    {
-        Ok(_) => info!("Settings window opened"),
+    let saved = geometry::restore(app, "settings");
+    let builder =
+        WebviewWindowBuilder::new(app, "settings", WebviewUrl::App("settings.html".into()))
+            .title(i18n::t(Msg::WindowSettings))
+            .min_inner_size(SETTINGS_MIN_WIDTH, SETTINGS_MIN_HEIGHT)
+            .maximizable(false);
+    let builder = match saved {
+        Some(saved) => builder
+            .inner_size(
+                saved.width.max(SETTINGS_MIN_WIDTH),
+                saved.height.max(SETTINGS_MIN_HEIGHT),
+            )
+            .position(saved.x, saved.y),
+        None => builder.inner_size(480.0, 520.0).center(),
+    };
+    match builder.build() {
+        Ok(window) => {
+            let handle = window.clone();
+            window.on_window_event(move |event| {
+                if let tauri::WindowEvent::CloseRequested { .. } = event {
+                    geometry::remember(&handle);
+                }
+            });
+            info!(restored = saved.is_some(), "Settings window opened");
+        }
         Err(e) => {
             error!(error = %e, "Settings window failed");
-            show_toast(app, "error", "Settings failed");
+            show_toast(app, "error", &i18n::t(Msg::SettingsFailed));
         }
     }
 }
@@ -333,24 +3552,183 @@ fn update_hotkey(
     app: &AppHandle,
     state: &tauri::State<'_, AppState>,
     hotkey_str: &str,
-) -> Result<(), String> {
+    fast_modifier: &str,
+) -> Result<(), CommandError> {
     let new_shortcut = parse_shortcut(hotkey_str)?;
+    let new_fast_shortcut = fast_variant(&new_shortcut, fast_modifier)?;
 
-    // Unregister old shortcut
-    if let Some(old_shortcut) = state.current_shortcut.lock().unwrap().take() {
-        let _ = app.global_shortcut().unregister(old_shortcut);
+    // Unregister old shortcuts
+    let old_shortcut = state.current_shortcut.lock().unwrap().take();
+    let old_fast_shortcut = state.fast_shortcut.lock().unwrap().take();
+    for old in old_shortcut.iter().chain(&old_fast_shortcut) {
+        let _ = app.global_shortcut().unregister(*old);
     }
 
-    // Register new shortcut
-    app.global_shortcut()
-        .register(new_shortcut)
-        .map_err(|e| format!("Failed to register hotkey: {}", e))?;
+    // Register new shortcut, going back to the old ones if it is taken
+    if let Err(e) = app.global_shortcut().register(new_shortcut) {
+        for old in old_shortcut.iter().chain(&old_fast_shortcut) {
+            let _ = app.global_shortcut().register(*old);
+        }
+        *state.current_shortcut.lock().unwrap() = old_shortcut;
+        *state.fast_shortcut.lock().unwrap() = old_fast_shortcut;
+        return Err(i18n::t(Msg::HotkeyRegisterFailed(&e.to_string())).into());
+    }
 
     *state.current_shortcut.lock().unwrap() = Some(new_shortcut);
     info!(hotkey = %hotkey_str, "Hotkey updated");
+
+    // The fast-mode variant is optional; a conflict must not block the main hotkey
+    if let Some(fast_shortcut) = new_fast_shortcut {
+        match app.global_shortcut().register(fast_shortcut) {
+            Ok(()) => {
+                *state.fast_shortcut.lock().unwrap() = Some(fast_shortcut);
+                debug!(modifier = %fast_modifier, "Fast mode hotkey registered");
+            }
+            Err(e) => error!(error = %e, "Failed to register fast mode hotkey"),
+        }
+    }
+    Ok(())
+}
+
+/// Parses every hotkey in `config` without registering any.
+fn check_hotkeys(config: &Config) -> Result<(), CommandError> {
+    let shortcut = parse_shortcut(&config.hotkey)?;
+    fast_variant(&shortcut, &config.fast_mode_modifier)?;
+    for (action, hotkey) in action_hotkeys(config) {
+        parse_shortcut(&hotkey).map_err(|e| i18n::t(Msg::InvalidHotkey(action.name(), &e)))?;
+    }
+    for (utility, hotkey) in utility_hotkeys(config) {
+        parse_shortcut(&hotkey).map_err(|e| i18n::t(Msg::InvalidHotkey(utility.name(), &e)))?;
+    }
+    Ok(())
+}
+
+/// Hotkeys bound to actions other than the main translate shortcut.
+fn action_hotkeys(config: &Config) -> Vec<(Action, String)> {
+    let custom = config
+        .actions
+        .iter()
+        .enumerate()
+        .map(|(idx, action)| (Action::Custom(idx), &action.shortcut));
+    [
+        (Action::Summarize, &config.summarize_hotkey),
+        (Action::Proofread, &config.proofread_hotkey),
+        (Action::Rewrite(config.rewrite_tone), &config.rewrite_hotkey),
+    ]
+    .into_iter()
+    .chain(custom)
+    .filter(|(_, hotkey)| !hotkey.trim().is_empty())
+    .map(|(action, hotkey)| (action, hotkey.clone()))
+    .collect()
+}
+
+fn update_action_hotkeys(
+    app: &AppHandle,
+    state: &tauri::State<'_, AppState>,
+    config: &Config,
+) -> Result<(), CommandError> {
+    let mut bindings = Vec::new();
+    for (action, hotkey) in action_hotkeys(config) {
+        let shortcut =
+            parse_shortcut(&hotkey).map_err(|e| i18n::t(Msg::InvalidHotkey(action.name(), &e)))?;
+        bindings.push((shortcut, action));
+    }
+
+    let mut registered = state.action_shortcuts.lock().unwrap();
+    for (old_shortcut, _) in registered.drain(..) {
+        let _ = app.global_shortcut().unregister(old_shortcut);
+    }
+    for (shortcut, action) in bindings {
+        match app.global_shortcut().register(shortcut) {
+            Ok(()) => {
+                info!(action = action.name(), "Action hotkey registered");
+                registered.push((shortcut, action));
+            }
+            Err(e) => {
+                error!(error = %e, action = action.name(), "Failed to register action hotkey")
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Hotkeys for app commands that do not run a clipboard pipeline.
+fn utility_hotkeys(config: &Config) -> Vec<(Utility, String)> {
+    [
+        (Utility::CycleLanguage, &config.cycle_language_hotkey),
+        (Utility::Undo, &config.undo_hotkey),
+        (Utility::QuickTranslate, &config.quick_translate_hotkey),
+        (Utility::CaptureRegion, &config.capture_hotkey),
+        (Utility::Dictate, &config.dictation_hotkey),
+        (Utility::TogglePause, &config.toggle_pause_hotkey),
+        (
+            Utility::TranslateSelection,
+            &config.translate_selection_hotkey,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, hotkey)| !hotkey.trim().is_empty())
+    .map(|(utility, hotkey)| (utility, hotkey.clone()))
+    .collect()
+}
+
+fn update_utility_hotkeys(
+    app: &AppHandle,
+    state: &tauri::State<'_, AppState>,
+    config: &Config,
+) -> Result<(), CommandError> {
+    let mut bindings = Vec::new();
+    for (utility, hotkey) in utility_hotkeys(config) {
+        let shortcut =
+            parse_shortcut(&hotkey).map_err(|e| i18n::t(Msg::InvalidHotkey(utility.name(), &e)))?;
+        bindings.push((shortcut, utility));
+    }
+
+    let mut registered = state.utility_shortcuts.lock().unwrap();
+    for (old_shortcut, _) in registered.drain(..) {
+        let _ = app.global_shortcut().unregister(old_shortcut);
+    }
+    for (shortcut, utility) in bindings {
+        match app.global_shortcut().register(shortcut) {
+            Ok(()) => {
+                info!(utility = utility.name(), "Utility hotkey registered");
+                registered.push((shortcut, utility));
+            }
+            Err(e) => {
+                error!(error = %e, utility = utility.name(), "Failed to register utility hotkey")
+            }
+        }
+    }
     Ok(())
 }
 
+/// The main shortcut with the fast-mode modifier added, if it adds anything.
+fn fast_variant(shortcut: &Shortcut, modifier: &str) -> Result<Option<Shortcut>, String> {
+    let modifier = modifier.trim();
+    if modifier.is_empty() {
+        return Ok(None);
+    }
+    let extra = parse_modifier(&modifier.to_ascii_lowercase())
+        .ok_or_else(|| i18n::t(Msg::UnknownModifier(modifier)))?;
+    if shortcut.mods.contains(extra) {
+        return Ok(None);
+    }
+    Ok(Some(Shortcut::new(
+        Some(shortcut.mods | extra),
+        shortcut.key,
+    )))
+}
+
+fn parse_modifier(token: &str) -> Option<Modifiers> {
+    match token {
+        "ctrl" | "control" => Some(Modifiers::CONTROL),
+        "alt" | "option" => Some(Modifiers::ALT),
+        "shift" => Some(Modifiers::SHIFT),
+        "win" | "super" | "meta" | "cmd" | "command" => Some(Modifiers::SUPER),
+        _ => None,
+    }
+}
+
 fn parse_shortcut(input: &str) -> Result<Shortcut, String> {
     let tokens: Vec<&str> = input
         .split('+')
@@ -363,21 +3741,17 @@ fn parse_shortcut(input: &str) -> Result<Shortcut, String> {
 
     for token in tokens {
         let lower = token.to_ascii_lowercase();
-        match lower.as_str() {
-            "ctrl" | "control" => modifiers |= Modifiers::CONTROL,
-            "alt" | "option" => modifiers |= Modifiers::ALT,
-            "shift" => modifiers |= Modifiers::SHIFT,
-            "win" | "super" | "meta" | "cmd" | "command" => modifiers |= Modifiers::SUPER,
-            _ => {
-                if key_code.is_some() {
-                    return Err("Multiple keys specified".to_string());
-                }
-                key_code = Some(parse_key_code(&lower)?);
-            }
+        if let Some(modifier) = parse_modifier(&lower) {
+            modifiers |= modifier;
+            continue;
+        }
+        if key_code.is_some() {
+            return Err(i18n::t(Msg::MultipleKeys));
         }
+        key_code = Some(parse_key_code(&lower)?);
     }
 
-    let code = key_code.ok_or_else(|| "No key specified".to_string())?;
+    let code = key_code.ok_or_else(|| i18n::t(Msg::NoKey))?;
     Ok(Shortcut::new(Some(modifiers), code))
 }
 
@@ -413,7 +3787,7 @@ fn parse_key_code(key: &str) -> Result<Code, String> {
                 'x' => Ok(Code::KeyX),
                 'y' => Ok(Code::KeyY),
                 'z' => Ok(Code::KeyZ),
-                _ => Err(format!("Unknown key: {}", key)),
+                _ => Err(i18n::t(Msg::UnknownKey(key))),
             };
         }
         if ch.is_ascii_digit() {
@@ -428,7 +3802,7 @@ fn parse_key_code(key: &str) -> Result<Code, String> {
                 '7' => Ok(Code::Digit7),
                 '8' => Ok(Code::Digit8),
                 '9' => Ok(Code::Digit9),
-                _ => Err(format!("Unknown key: {}", key)),
+                _ => Err(i18n::t(Msg::UnknownKey(key))),
             };
         }
     }
@@ -449,11 +3823,44 @@ fn parse_key_code(key: &str) -> Result<Code, String> {
                 10 => Ok(Code::F10),
                 11 => Ok(Code::F11),
                 12 => Ok(Code::F12),
-                _ => Err(format!("Unknown function key: {}", key)),
+                13 => Ok(Code::F13),
+                14 => Ok(Code::F14),
+                15 => Ok(Code::F15),
+                16 => Ok(Code::F16),
+                17 => Ok(Code::F17),
+                18 => Ok(Code::F18),
+                19 => Ok(Code::F19),
+                20 => Ok(Code::F20),
+                21 => Ok(Code::F21),
+                22 => Ok(Code::F22),
+                23 => Ok(Code::F23),
+                24 => Ok(Code::F24),
+                _ => Err(i18n::t(Msg::UnknownFunctionKey(key))),
             };
         }
     }
 
+    // Numpad digits: Num0 or Numpad0
+    if let Some(digit) = key
+        .strip_prefix("numpad")
+        .or_else(|| key.strip_prefix("num"))
+        .filter(|rest| rest.len() == 1)
+        .and_then(|rest| rest.parse::<u8>().ok())
+    {
+        return Ok(match digit {
+            0 => Code::Numpad0,
+            1 => Code::Numpad1,
+            2 => Code::Numpad2,
+            3 => Code::Numpad3,
+            4 => Code::Numpad4,
+            5 => Code::Numpad5,
+            6 => Code::Numpad6,
+            7 => Code::Numpad7,
+            8 => Code::Numpad8,
+            _ => Code::Numpad9,
+        });
+    }
+
     // Special keys
     match key {
         "space" | "spacebar" => Ok(Code::Space),
@@ -471,98 +3878,135 @@ fn parse_key_code(key: &str) -> Result<Code, String> {
         "down" | "arrowdown" => Ok(Code::ArrowDown),
         "left" | "arrowleft" => Ok(Code::ArrowLeft),
         "right" | "arrowright" => Ok(Code::ArrowRight),
-        _ => Err(format!("Unknown key: {}", key)),
+        // Numpad operators; `+` separates keys, so NumpadAdd has no symbol form
+        "numadd" | "numpadadd" | "numplus" => Ok(Code::NumpadAdd),
+        "numsubtract" | "numpadsubtract" | "numminus" => Ok(Code::NumpadSubtract),
+        "nummultiply" | "numpadmultiply" => Ok(Code::NumpadMultiply),
+        "numdivide" | "numpaddivide" => Ok(Code::NumpadDivide),
+        "numdecimal" | "numpaddecimal" => Ok(Code::NumpadDecimal),
+        "numenter" | "numpadenter" => Ok(Code::NumpadEnter),
+        // Punctuation, by US layout position
+        "," | "comma" => Ok(Code::Comma),
+        "." | "period" => Ok(Code::Period),
+        "/" | "slash" => Ok(Code::Slash),
+        ";" | "semicolon" => Ok(Code::Semicolon),
+        "'" | "quote" => Ok(Code::Quote),
+        "[" | "bracketleft" => Ok(Code::BracketLeft),
+        "]" | "bracketright" => Ok(Code::BracketRight),
+        "\\" | "backslash" => Ok(Code::Backslash),
+        "`" | "backquote" | "backtick" => Ok(Code::Backquote),
+        "-" | "minus" => Ok(Code::Minus),
+        "=" | "equal" => Ok(Code::Equal),
+        // Media keys
+        "mediaplaypause" | "playpause" => Ok(Code::MediaPlayPause),
+        "mediastop" => Ok(Code::MediaStop),
+        "mediatracknext" | "medianext" | "nexttrack" => Ok(Code::MediaTrackNext),
+        "mediatrackprevious" | "mediaprev" | "prevtrack" => Ok(Code::MediaTrackPrevious),
+        "audiovolumeup" | "volumeup" => Ok(Code::AudioVolumeUp),
+        "audiovolumedown" | "volumedown" => Ok(Code::AudioVolumeDown),
+        "audiovolumemute" | "volumemute" | "mute" => Ok(Code::AudioVolumeMute),
+        _ => Err(i18n::t(Msg::UnknownKey(key))),
     }
 }
 
-fn build_log_filter() -> tracing_subscriber::EnvFilter {
-    tracing_subscriber::EnvFilter::try_from_env("THIRDSPACE_LOG")
-        .or_else(|_| tracing_subscriber::EnvFilter::try_from_default_env())
-        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(DEFAULT_LOG_FILTER))
+fn build_log_filter() -> EnvFilter {
+    EnvFilter::try_from_env("THIRDSPACE_LOG")
+        .or_else(|_| EnvFilter::try_from_default_env())
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER))
 }
 
-fn cleanup_old_logs(log_dir: &Path) {
-    let cutoff = match SystemTime::now()
-        .checked_sub(Duration::from_secs(LOG_RETENTION_DAYS * 24 * 60 * 60))
-    {
-        Some(time) => time,
-        None => return,
-    };
-
-    let entries = match std::fs::read_dir(log_dir) {
-        Ok(entries) => entries,
-        Err(_) => return,
+fn setup_logging() {
+    let Ok(log_dir) = config::logs_dir() else {
+        return;
     };
-
-    for entry in entries.flatten() {
-        let path = entry.path();
-        let file_name = match path.file_name().and_then(|name| name.to_str()) {
-            Some(name) => name,
-            None => continue,
-        };
-        if !file_name.starts_with(LOG_FILE_PREFIX) {
-            continue;
-        }
-        let modified = match entry.metadata().and_then(|meta| meta.modified()) {
-            Ok(time) => time,
-            Err(_) => continue,
-        };
-        if modified < cutoff {
-            let _ = std::fs::remove_file(path);
-        }
-    }
-}
-
-fn setup_logging() -> Option<tracing_appender::non_blocking::WorkerGuard> {
-    let log_dir = config::logs_dir().ok()?;
-    let filter = build_log_filter();
+    let (filter, handle) = reload::Layer::new(build_log_filter());
+    let _ = LOG_FILTER.set(handle);
     if std::fs::create_dir_all(&log_dir).is_err() {
-        let _ = tracing_subscriber::fmt()
-            .with_ansi(false)
-            .with_env_filter(build_log_filter())
+        let _ = tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer().with_ansi(false))
             .try_init();
-        return None;
+        return;
     }
 
-    cleanup_old_logs(&log_dir);
-
-    let file_appender = tracing_appender::rolling::daily(&log_dir, LOG_FILE_PREFIX);
-    let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
+    let (non_blocking, guard) =
+        tracing_appender::non_blocking(logs::LogWriter::new(log_dir.clone()));
+    let (otlp, otlp_error) = match telemetry::layer() {
+        Ok(layer) => (layer, None),
+        Err(e) => (None, Some(e)),
+    };
+    let otlp_enabled = otlp.is_some();
 
-    tracing_subscriber::fmt()
-        .with_writer(non_blocking)
-        .with_ansi(false)
-        .with_target(true)
-        .with_level(true)
-        .with_thread_ids(true)
-        .with_thread_names(true)
-        .with_file(true)
-        .with_line_number(true)
-        .with_env_filter(filter)
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            fmt::layer()
+                .with_writer(non_blocking)
+                .with_ansi(false)
+                .with_target(true)
+                .with_level(true)
+                .with_thread_ids(true)
+                .with_thread_names(true)
+                .with_file(true)
+                .with_line_number(true),
+        )
+        .with(otlp)
         .init();
 
     info!(
         log_dir = %log_dir.display(),
-        retention_days = LOG_RETENTION_DAYS,
+        otlp = otlp_enabled,
         "Logging initialized"
     );
-    Some(guard)
+    if let Some(e) = otlp_error {
+        warn!(error = %e, "OTLP trace export not started");
+    }
+    *LOG_GUARD.lock().unwrap() = Some(guard);
+}
+
+/// Runs a command-line subcommand if one was given, returning its exit code.
+/// Returns `None` when the tray app should start normally.
+pub fn run_cli() -> Option<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    cli::run(&args)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let migrate_result = config::migrate_legacy_data();
-    let _log_guard = setup_logging();
+    setup_logging();
     if let Err(err) = migrate_result {
         error!(error = %err, "Legacy data migration failed");
     }
 
-    let config = config::load().unwrap_or_default();
+    let first_run = onboarding::is_first_run();
+    let mut config = config::load().unwrap_or_default();
+    if bridge::ensure_token(&mut config) {
+        if let Err(e) = config::save(&config) {
+            warn!(error = %e, "Failed to save the browser bridge token");
+        }
+    }
+    logs::set_limits(&config);
+    i18n::set_language(&config.ui_language);
+    openrouter::set_log_content(config.log_content);
+    openrouter::set_api_base(&config.api_base_url);
+    audit::configure(&config);
+    let initial_config = config.clone();
     let initial_hotkey = config.hotkey.clone();
+    let initial_fast_modifier = config.fast_mode_modifier.clone();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        // A deep link opened while running starts a second process; this forwards its
+        // URL to the running one's deep link handler and exits
+        .plugin(tauri_plugin_single_instance::init(|_app, argv, _cwd| {
+            debug!(args = argv.len(), "Second instance started");
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
             Some(vec!["--autostart"]),
@@ -570,17 +4014,54 @@ pub fn run() {
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
                 .with_handler(|app, shortcut, event| {
+                    if event.state == ShortcutState::Released {
+                        let dictating = app
+                            .state::<AppState>()
+                            .utility_shortcuts
+                            .lock()
+                            .unwrap()
+                            .iter()
+                            .any(|(bound, utility)| {
+                                bound == shortcut && *utility == Utility::Dictate
+                            });
+                        if dictating {
+                            dictation::stop(app);
+                        }
+                        return;
+                    }
                     if event.state == ShortcutState::Pressed {
                         let state = app.state::<AppState>();
-                        let is_our_shortcut = {
-                            let guard = state.current_shortcut.lock().unwrap();
-                            guard.as_ref().map_or(false, |current| shortcut == current)
+                        let is_main =
+                            state.current_shortcut.lock().unwrap().as_ref() == Some(shortcut);
+                        let is_fast =
+                            state.fast_shortcut.lock().unwrap().as_ref() == Some(shortcut);
+                        let utility = state
+                            .utility_shortcuts
+                            .lock()
+                            .unwrap()
+                            .iter()
+                            .find(|(bound, _)| bound == shortcut)
+                            .map(|(_, utility)| *utility);
+                        if let Some(utility) = utility {
+                            utility.run(app);
+                            return;
+                        }
+                        let action = if is_main || is_fast {
+                            Some(Action::Translate)
+                        } else {
+                            state
+                                .action_shortcuts
+                                .lock()
+                                .unwrap()
+                                .iter()
+                                .find(|(bound, _)| bound == shortcut)
+                                .map(|(_, action)| *action)
                         };
-                        if is_our_shortcut {
+                        if let Some(action) = action {
                             let app = app.clone();
                             tauri::async_runtime::spawn(async move {
                                 let state = app.state::<AppState>();
-                                let _ = translate(app.clone(), state).await;
+                                let _ = run_action(app.clone(), state, action, is_fast).await;
                             });
                         }
                     }
@@ -588,62 +4069,238 @@ pub fn run() {
                 .build(),
         )
         .manage(AppState {
-            config: Mutex::new(config),
-            translate_in_flight: Mutex::new(false),
+            http: Mutex::new(http_client(&config)),
+            config: ArcSwap::from_pointee(config),
+            requests: RequestRegistry::default(),
             current_shortcut: Mutex::new(None),
+            fast_shortcut: Mutex::new(None),
+            action_shortcuts: Mutex::new(Vec::new()),
+            utility_shortcuts: Mutex::new(Vec::new()),
+            undo_snapshot: Mutex::new(None),
+            capture: Mutex::new(None),
+            dictation: dictation::Dictation::default(),
+            paused: Mutex::new(false),
+            pending_update: Mutex::new(None),
             models_cache: Mutex::new(None),
+            toasts: ToastManager::default(),
+            latency: LatencyTracker::default(),
+            metrics: Metrics::default(),
+            offline_queue: queue::OfflineQueue::default(),
+            bridge: Mutex::new(None),
+            last_result: Mutex::new(None),
+            candidates: Mutex::new(Vec::new()),
+            last_translation: Mutex::new(None),
+            last_outcome: Mutex::new(None),
+            results: ResultCache::default(),
+            pinned: Mutex::new(journal::pinned().unwrap_or_else(|e| {
+                warn!(error = %e, "Failed to load pinned translations");
+                Vec::new()
+            })),
+            pending_input: Mutex::new(None),
+            pending_cost: Mutex::new(None),
+            pending_review: Mutex::new(None),
+            clipboard_history: ClipboardHistory::default(),
         })
         .setup(move |app| {
             // Setup system tray
-            let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let settings = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
-            let translate_item =
-                MenuItem::with_id(app, "translate", "Translate", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&translate_item, &settings, &quit])?;
+            let menu = {
+                let config = app.state::<AppState>().config();
+                build_tray_menu(app.handle(), &config)?
+            };
 
-            TrayIconBuilder::new()
+            // A menu bar extra on macOS: no Dock icon, and a template icon that follows
+            // the light/dark menu bar
+            #[cfg(target_os = "macos")]
+            app.set_activation_policy(tauri::ActivationPolicy::Accessory);
+
+            TrayIconBuilder::with_id(TRAY_ID)
                 .icon(app.default_window_icon().unwrap().clone())
+                .icon_as_template(cfg!(target_os = "macos"))
+                .tooltip(tray_tooltip(app.handle()))
                 .menu(&menu)
+                .show_menu_on_left_click(initial_config.tray_click == TrayClick::Menu)
+                .on_tray_icon_event(|tray, event| {
+                    if let TrayIconEvent::Click {
+                        button: MouseButton::Left,
+                        button_state: MouseButtonState::Up,
+                        ..
+                    } = event
+                    {
+                        handle_tray_click(tray.app_handle());
+                    }
+                })
                 .on_menu_event(|app, event| match event.id.as_ref() {
-                    "translate" => {
-                        let app = app.clone();
-                        tauri::async_runtime::spawn(async move {
-                            let state = app.state::<AppState>();
-                            let _ = translate(app.clone(), state).await;
-                        });
+                    "translate" => spawn_action(app, Action::Translate),
+                    "translate_selection" => spawn_selection_action(app, Action::Translate),
+                    "translate_commit" => spawn_action(app, Action::CommitMessage),
+                    "translate_file" => pick_file_to_translate(app),
+                    "capture_region" => capture::start(app),
+                    "summarize" => spawn_action(app, Action::Summarize),
+                    "proofread" => spawn_action(app, Action::Proofread),
+                    "fast_mode" => {
+                        toggle_fast_mode(app);
+                    }
+                    "next_language" => cycle_target_language(app),
+                    "undo" => {
+                        let _ = undo_last_translation(app);
                     }
+                    "quick_translate" => show_quick_translate(app),
+                    "pause" => toggle_pause(app),
+                    "check_updates" => updater::check_from_tray(app),
                     "settings" => {
                         open_settings(app);
                     }
-                    "quit" => {
-                        app.exit(0);
+                    "quit" => shutdown::request_quit(app),
+                    id => {
+                        if let Some(language) = id.strip_prefix(LANGUAGE_MENU_PREFIX) {
+                            set_target_language(app, language);
+                        } else if let Some(tone) =
+                            id.strip_prefix(REWRITE_MENU_PREFIX).and_then(Tone::from_id)
+                        {
+                            spawn_action(app, Action::Rewrite(tone));
+                        } else if let Some(idx) = id
+                            .strip_prefix(CUSTOM_ACTION_MENU_PREFIX)
+                            .and_then(|idx| idx.parse().ok())
+                        {
+                            spawn_action(app, Action::Custom(idx));
+                        } else if let Some(index) = id
+                            .strip_prefix(HISTORY_MENU_PREFIX)
+                            .and_then(|index| index.parse().ok())
+                        {
+                            spawn_history_translation(app, index);
+                        } else if let Some(index) = id
+                            .strip_prefix(PINNED_MENU_PREFIX)
+                            .and_then(|index| index.parse().ok())
+                        {
+                            copy_pinned(app, index);
+                        }
                     }
-                    _ => {}
                 })
                 .build(app)?;
 
             // Register initial hotkey
             let state = app.state::<AppState>();
-            if let Ok(shortcut) = parse_shortcut(&initial_hotkey) {
-                if app.global_shortcut().register(shortcut).is_ok() {
-                    *state.current_shortcut.lock().unwrap() = Some(shortcut);
-                    info!(hotkey = %initial_hotkey, "Hotkey registered");
+            if let Err(e) = update_hotkey(
+                app.handle(),
+                &state,
+                &initial_hotkey,
+                &initial_fast_modifier,
+            ) {
+                error!(error = %e, hotkey = %initial_hotkey, "Hotkey registration failed");
+            }
+            if let Err(e) = update_action_hotkeys(app.handle(), &state, &initial_config) {
+                error!(error = %e, "Action hotkey registration failed");
+            }
+            if let Err(e) = update_utility_hotkeys(app.handle(), &state, &initial_config) {
+                error!(error = %e, "Utility hotkey registration failed");
+            }
+
+            // Installers register the scheme; this covers portable and dev builds
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                warn!(error = %e, "Deep link scheme registration failed");
+            }
+            let deep_link_app = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    handle_deep_link(&deep_link_app, &url);
                 }
+            });
+            // Launched by a link rather than already running
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                for url in urls {
+                    handle_deep_link(app.handle(), &url);
+                }
+            }
+
+            bridge::restart(app.handle(), &initial_config);
+            scheduler::start(app.handle().clone());
+            clipboard::watch(
+                app.handle().clone(),
+                &[history::SUBSCRIBER, copybutton::SUBSCRIBER],
+            );
+            doubletap::ensure_listening(app.handle(), &initial_config);
+            queue::watch(app.handle().clone());
+            watcher::watch_config(app.handle().clone());
+            if first_run {
+                onboarding::show(app.handle());
             }
 
-            info!("ThirdSpace started");
+            info!(first_run, "ThirdSpace started");
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![get_config, save_config, translate, pause_hotkey, resume_hotkey, fetch_models])
+        .invoke_handler(tauri::generate_handler![
+            get_config,
+            get_policy,
+            get_languages,
+            save_config,
+            validate_config,
+            validate_api_key,
+            get_account_status,
+            export_settings,
+            import_settings,
+            translate_clipboard,
+            translate_selection,
+            translate_commit_message,
+            open_quick_translate,
+            translate_text,
+            summarize,
+            proofread,
+            rewrite,
+            run_custom_action,
+            translate_file,
+            get_clipboard_history,
+            translate_history_entry,
+            confirm_pending_input,
+            discard_pending_input,
+            get_result,
+            get_review,
+            accept_translation,
+            reject_translation,
+            get_candidates,
+            choose_candidate,
+            get_last_translation,
+            undo_translation,
+            search_history,
+            get_metrics,
+            get_usage,
+            get_active_requests,
+            run_diagnostics,
+            export_diagnostics,
+            start_capture,
+            get_capture,
+            capture_region,
+            cancel_capture,
+            set_log_level,
+            export_history,
+            pin_translation,
+            get_pinned,
+            validate_hotkey,
+            pause_hotkey,
+            resume_hotkey,
+            set_paused,
+            is_paused,
+            check_for_updates,
+            check_latest_version,
+            install_update,
+            fetch_models,
+            get_models,
+            get_onboarding,
+            complete_onboarding,
+        ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
-        .run(|_app, event| {
+        .run(|_app, event| match event {
             // Only prevent exit when closing windows (code is None)
             // Allow exit when explicitly called via app.exit() (code is Some)
-            if let tauri::RunEvent::ExitRequested { api, code, .. } = event {
-                if code.is_none() {
-                    api.prevent_exit();
-                }
+            tauri::RunEvent::ExitRequested {
+                api, code: None, ..
+            } => api.prevent_exit(),
+            tauri::RunEvent::Exit => {
+                info!("Exiting");
+                telemetry::shutdown();
+                drop(LOG_GUARD.lock().unwrap().take());
             }
+            _ => {}
         });
 }
@@ -0,0 +1,165 @@
+use crate::config::Config;
+use crate::usage;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const LOG_FILE_PREFIX: &str = "thirdspace.log";
+const MB: u64 = 1024 * 1024;
+
+// Limits from the config; the defaults apply until `set_limits` runs after config::load
+static RETENTION_DAYS: AtomicU64 = AtomicU64::new(14);
+static MAX_FILE_BYTES: AtomicU64 = AtomicU64::new(10 * MB);
+static MAX_TOTAL_BYTES: AtomicU64 = AtomicU64::new(100 * MB);
+
+/// Applies `log_retention_days`, `log_max_file_mb` and `log_max_total_mb`.
+pub fn set_limits(config: &Config) {
+    RETENTION_DAYS.store(config.log_retention_days, Ordering::Relaxed);
+    MAX_FILE_BYTES.store(config.log_max_file_mb.saturating_mul(MB), Ordering::Relaxed);
+    MAX_TOTAL_BYTES.store(
+        config.log_max_total_mb.saturating_mul(MB),
+        Ordering::Relaxed,
+    );
+}
+
+/// Log file writer that starts a new `thirdspace.log.YYYY-MM-DD` each UTC day, like
+/// `tracing_appender::rolling::daily`, and also whenever the current file reaches
+/// `log_max_file_mb`. A full file is renamed to `thirdspace.log.YYYY-MM-DD.N`.
+pub struct LogWriter {
+    dir: PathBuf,
+    date: String,
+    file: Option<File>,
+    size: u64,
+}
+
+impl LogWriter {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            date: String::new(),
+            file: None,
+            size: 0,
+        }
+    }
+
+    fn path(&self, date: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", LOG_FILE_PREFIX, date))
+    }
+
+    fn current(&mut self) -> io::Result<&mut File> {
+        let today = today();
+        let max = MAX_FILE_BYTES.load(Ordering::Relaxed);
+        let full = max > 0 && self.size >= max;
+        if self.file.is_none() || today != self.date || full {
+            // Closed first: Windows cannot rename a file that is still open
+            self.file = None;
+            if full && today == self.date {
+                // Keep appending to the full file rather than losing lines
+                let _ = self.roll_over();
+            }
+            let path = self.path(&today);
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            self.size = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+            self.date = today;
+            self.file = Some(file);
+        }
+        Ok(self.file.as_mut().expect("log file was just opened"))
+    }
+
+    /// Moves today's full file aside under the first free number and trims the directory.
+    fn roll_over(&self) -> io::Result<()> {
+        let current = self.path(&self.date);
+        let mut number = 1;
+        let target = loop {
+            let candidate = self
+                .dir
+                .join(format!("{}.{}.{}", LOG_FILE_PREFIX, self.date, number));
+            if !candidate.exists() {
+                break candidate;
+            }
+            number += 1;
+        };
+        fs::rename(&current, &target)?;
+        cleanup_old_logs(&self.dir);
+        Ok(())
+    }
+}
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.current()?.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Deletes log files older than `log_retention_days`, then the oldest files until the
+/// directory fits in `log_max_total_mb`. The newest file is the one being written and
+/// is always kept.
+pub fn cleanup_old_logs(log_dir: &Path) {
+    let files = log_files(log_dir);
+    let retention_days = RETENTION_DAYS.load(Ordering::Relaxed);
+    let cutoff = (retention_days > 0)
+        .then(|| SystemTime::now().checked_sub(Duration::from_secs(retention_days * 24 * 60 * 60)))
+        .flatten();
+    let budget = MAX_TOTAL_BYTES.load(Ordering::Relaxed);
+
+    let mut total = 0u64;
+    for (index, (path, modified, len)) in files.iter().enumerate() {
+        total += len;
+        let expired = cutoff.is_some_and(|cutoff| *modified < cutoff);
+        let over_budget = budget > 0 && total > budget;
+        if index > 0 && (expired || over_budget) && fs::remove_file(path).is_ok() {
+            total -= len;
+        }
+    }
+}
+
+/// The newest `count` log files, newest first.
+pub fn recent_files(log_dir: &Path, count: usize) -> Vec<PathBuf> {
+    log_files(log_dir)
+        .into_iter()
+        .take(count)
+        .map(|(path, _, _)| path)
+        .collect()
+}
+
+/// Every log file with its modification time and size, newest first.
+fn log_files(log_dir: &Path) -> Vec<(PathBuf, SystemTime, u64)> {
+    let Ok(entries) = fs::read_dir(log_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(PathBuf, SystemTime, u64)> = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+        })
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            Some((entry.path(), meta.modified().ok()?, meta.len()))
+        })
+        .collect();
+    files.sort_by_key(|&(_, modified, _)| std::cmp::Reverse(modified));
+    files
+}
+
+fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let (year, month, day) = usage::civil_date(secs);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
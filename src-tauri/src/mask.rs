@@ -0,0 +1,172 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+const PLACEHOLDER_OPEN: char = '⟦';
+const PLACEHOLDER_CLOSE: char = '⟧';
+
+/// Fenced code blocks, inline code, URLs, emails, HTML comments, HTML tags, template
+/// variables (`{{name}}`, `${name}`, `{name}`, `{0}`), printf specifiers (`%s`, `%1$d`,
+/// `%.2f`) and emoji shortcodes (`:smile:`), in priority order.
+static PROTECTED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?s)```.*?```|`[^`\n]+`|https?://[^\s<>"'`]+|www\.[^\s<>"'`]+|[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)+|<!--.*?-->|</?[A-Za-z][A-Za-z0-9:-]*(?:\s[^<>]*)?/?>|\{\{[^{}\n]+\}\}|\$\{[^{}\n]+\}|\{[A-Za-z0-9_.]+\}|%(?:\d+\$)?[-+0#]*\d*(?:\.\d+)?[sdifuxXoeEgGc@]|\B:[a-z0-9_+-]*[a-z][a-z0-9_+-]*:\B"#,
+    )
+    .expect("valid protected-span regex")
+});
+
+/// Markup in an HTML document: style/script blocks, comments, tags and entities.
+static HTML_MARKUP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?is)<style\b.*?</style>|<script\b.*?</script>|<!--.*?-->|<![^>]*>|</?[A-Za-z][A-Za-z0-9:-]*(?:\s[^<>]*)?/?>|&(?:[a-z]+|#[0-9]+|#x[0-9a-f]+);|https?://[^\s<>"']+"#,
+    )
+    .expect("valid HTML markup regex")
+});
+
+/// Text with code, URLs and markup swapped out for numbered placeholders.
+#[derive(Debug, Clone, Default)]
+pub struct Masked {
+    pub text: String,
+    spans: Vec<String>,
+    /// Where the text after the last placeholder starts.
+    tail: usize,
+}
+
+impl Masked {
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Bytes of the original text held in protected spans.
+    pub fn protected_len(&self) -> usize {
+        self.spans.iter().map(String::len).sum()
+    }
+
+    pub fn push_text(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    /// Adds a protected span. Spans separated only by spaces share one placeholder, so
+    /// runs of markup do not drown the text in placeholders; line breaks stay visible.
+    pub fn push_span(&mut self, span: &str) {
+        let gap = &self.text[self.tail..];
+        if let Some(previous) = self.spans.last_mut() {
+            if gap.trim().is_empty() && !gap.contains('\n') {
+                previous.push_str(gap);
+                previous.push_str(span);
+                self.text.truncate(self.tail);
+                return;
+            }
+        }
+        self.text.push_str(&placeholder(self.spans.len()));
+        self.spans.push(span.to_string());
+        self.tail = self.text.len();
+    }
+
+    /// Puts the original spans back into the model output.
+    pub fn restore(&self, output: &str) -> String {
+        self.restore_with(output, str::to_string)
+    }
+
+    /// Like `restore`, but passes the text between placeholders through `escape`.
+    pub fn restore_with(&self, output: &str, escape: impl Fn(&str) -> String) -> String {
+        self.rebuild(output, escape, |span| span)
+    }
+
+    /// The model output with every placeholder removed.
+    pub fn strip(&self, output: &str) -> String {
+        self.rebuild(output, str::to_string, |_| "")
+    }
+
+    fn rebuild<'a>(
+        &'a self,
+        output: &str,
+        text: impl Fn(&str) -> String,
+        span: impl Fn(&'a str) -> &'a str,
+    ) -> String {
+        let mut rebuilt = String::with_capacity(output.len());
+        let mut rest = output;
+        while let Some(open) = rest.find(PLACEHOLDER_OPEN) {
+            let after = &rest[open + PLACEHOLDER_OPEN.len_utf8()..];
+            let found = after.find(PLACEHOLDER_CLOSE).and_then(|close| {
+                let idx: usize = after[..close].parse().ok()?;
+                Some((self.spans.get(idx)?, close))
+            });
+            match found {
+                Some((original, close)) => {
+                    rebuilt.push_str(&text(&rest[..open]));
+                    rebuilt.push_str(span(original));
+                    rest = &after[close + PLACEHOLDER_CLOSE.len_utf8()..];
+                }
+                None => {
+                    let end = open + PLACEHOLDER_OPEN.len_utf8();
+                    rebuilt.push_str(&text(&rest[..end]));
+                    rest = &rest[end..];
+                }
+            }
+        }
+        rebuilt.push_str(&text(rest));
+        rebuilt
+    }
+
+    /// Placeholders the model dropped from its output.
+    pub fn missing(&self, output: &str) -> Vec<String> {
+        (0..self.spans.len())
+            .map(placeholder)
+            .filter(|placeholder| !output.contains(placeholder.as_str()))
+            .collect()
+    }
+}
+
+fn placeholder(idx: usize) -> String {
+    format!("{PLACEHOLDER_OPEN}{idx}{PLACEHOLDER_CLOSE}")
+}
+
+/// Whether `input` can be masked without clashing with text that looks like a placeholder.
+pub fn can_mask(input: &str) -> bool {
+    !input.contains(PLACEHOLDER_OPEN) && !input.contains(PLACEHOLDER_CLOSE)
+}
+
+/// Replaces spans that must survive translation verbatim with placeholders.
+/// Input that already contains the placeholder brackets is passed through untouched.
+pub fn mask(input: &str) -> Masked {
+    mask_with(input, &PROTECTED)
+}
+
+/// Masks every tag and entity of an HTML fragment so only its text is translated.
+pub fn mask_html(html: &str) -> Masked {
+    mask_with(html, &HTML_MARKUP)
+}
+
+fn mask_with(input: &str, pattern: &Regex) -> Masked {
+    let mut masked = Masked::default();
+    if !can_mask(input) {
+        masked.push_text(input);
+        return masked;
+    }
+
+    let mut last = 0;
+    for found in pattern.find_iter(input) {
+        let span = trim_url_punctuation(found.as_str());
+        if span.is_empty() {
+            continue;
+        }
+        masked.push_text(&input[last..found.start()]);
+        masked.push_span(span);
+        last = found.start() + span.len();
+    }
+    masked.push_text(&input[last..]);
+    masked
+}
+
+/// Sentence punctuation right after a URL belongs to the text, not the link.
+fn trim_url_punctuation(span: &str) -> &str {
+    if span.starts_with("http") || span.starts_with("www.") {
+        span.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']'])
+    } else {
+        span
+    }
+}
@@ -0,0 +1,85 @@
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Requests kept per model; older ones roll off so the stats follow recent behavior.
+const SAMPLES_PER_MODEL: usize = 500;
+
+struct Sample {
+    duration_ms: u64,
+    success: bool,
+    input_len: usize,
+}
+
+/// Per-model request outcomes observed during this session.
+#[derive(Default)]
+pub struct Metrics {
+    samples: Mutex<HashMap<String, VecDeque<Sample>>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelStats {
+    pub model: String,
+    pub requests: usize,
+    pub failures: usize,
+    /// Failed share of `requests`, 0.0 to 1.0.
+    pub error_rate: f64,
+    /// Latency percentiles over successful requests; `None` until one succeeded.
+    pub p50_ms: Option<u64>,
+    pub p95_ms: Option<u64>,
+    pub avg_input_len: usize,
+}
+
+impl Metrics {
+    pub fn record(&self, model: &str, duration_ms: u128, success: bool, input_len: usize) {
+        let mut samples = self.samples.lock().unwrap();
+        let model_samples = samples.entry(model.to_string()).or_default();
+        if model_samples.len() == SAMPLES_PER_MODEL {
+            model_samples.pop_front();
+        }
+        model_samples.push_back(Sample {
+            duration_ms: duration_ms as u64,
+            success,
+            input_len,
+        });
+    }
+
+    /// Stats for every model that handled a request, sorted by model id.
+    pub fn stats(&self) -> Vec<ModelStats> {
+        let samples = self.samples.lock().unwrap();
+        let mut stats: Vec<ModelStats> = samples
+            .iter()
+            .map(|(model, samples)| {
+                let requests = samples.len();
+                let failures = samples.iter().filter(|sample| !sample.success).count();
+                let mut latencies: Vec<u64> = samples
+                    .iter()
+                    .filter(|sample| sample.success)
+                    .map(|sample| sample.duration_ms)
+                    .collect();
+                latencies.sort_unstable();
+                ModelStats {
+                    model: model.clone(),
+                    requests,
+                    failures,
+                    error_rate: failures as f64 / requests.max(1) as f64,
+                    p50_ms: percentile(&latencies, 50),
+                    p95_ms: percentile(&latencies, 95),
+                    avg_input_len: samples.iter().map(|sample| sample.input_len).sum::<usize>()
+                        / requests.max(1),
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| a.model.cmp(&b.model));
+        stats
+    }
+}
+
+/// Nearest-rank percentile of an ascending slice.
+fn percentile(sorted: &[u64], pct: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
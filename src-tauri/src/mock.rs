@@ -0,0 +1,157 @@
+use crate::config::Config;
+use crate::prompt;
+use anyhow::{anyhow, Result};
+use std::time::Duration;
+use tracing::info;
+
+/// Marker pairs the mock wraps its echo in when the system prompt asks for them.
+const MARKERS: [(&str, &str); 7] = [
+    (prompt::MARKER_START, prompt::MARKER_END),
+    (prompt::SUMMARY_START, prompt::SUMMARY_END),
+    (prompt::PROOFREAD_START, prompt::PROOFREAD_END),
+    (prompt::REWRITE_START, prompt::REWRITE_END),
+    (prompt::OUTPUT_START, prompt::OUTPUT_END),
+    (prompt::OCR_START, prompt::OCR_END),
+    (prompt::TRANSCRIPT_START, prompt::TRANSCRIPT_END),
+];
+
+/// Whether requests go to the mock instead of OpenRouter (`THIRDSPACE_BACKEND=mock`).
+pub fn enabled() -> bool {
+    std::env::var("THIRDSPACE_BACKEND").is_ok_and(|backend| backend.eq_ignore_ascii_case("mock"))
+}
+
+/// Answers a chat request the way a well-behaved model would: the user text, prefixed
+/// with the target language, inside the markers the system prompt asked for. The same
+/// input always gives the same output.
+///
+/// Directives anywhere in the input steer the reply:
+/// - `[mock:error]` fails the request
+/// - `[mock:delay=<ms>]` waits first (default `THIRDSPACE_MOCK_LATENCY_MS`, else none)
+/// - `[mock:no_markers]` leaves out the markers, exercising marker recovery
+pub async fn respond(
+    config: &Config,
+    system: &str,
+    user: &str,
+    structured: bool,
+) -> Result<String> {
+    let delay_ms = directive(user, "delay=")
+        .or_else(|| std::env::var("THIRDSPACE_MOCK_LATENCY_MS").ok())
+        .and_then(|ms| ms.parse().ok())
+        .unwrap_or(0);
+    if delay_ms > 0 {
+        tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+    }
+    info!(
+        model = %config.model,
+        input_len = user.len(),
+        delay_ms,
+        "Mock backend request"
+    );
+
+    if user.contains("[mock:error]") {
+        return Err(anyhow!("Mock backend error"));
+    }
+    let echo = format!("[{}] {}", config.target_language, user);
+    if user.contains("[mock:no_markers]") {
+        return Ok(echo);
+    }
+    if structured {
+        return Ok(serde_json::json!({ "translation": echo }).to_string());
+    }
+
+    let mut content = String::new();
+    for (start, end) in MARKERS {
+        if system.contains(start) {
+            content.push_str(&format!("{}\n{}\n{}\n", start, echo, end));
+        }
+    }
+    if system.contains(prompt::ROMANIZATION_START) {
+        content.push_str(&format!(
+            "{}\n{}\n{}\n",
+            prompt::ROMANIZATION_START,
+            user,
+            prompt::ROMANIZATION_END
+        ));
+    }
+    Ok(content)
+}
+
+/// The value of a `[mock:<name><value>]` directive.
+fn directive(input: &str, name: &str) -> Option<String> {
+    let start = input.find(&format!("[mock:{}", name))? + "[mock:".len() + name.len();
+    let len = input[start..].find(']')?;
+    Some(input[start..start + len].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::ResultCache;
+    use crate::clipboard::Input;
+    use crate::config::Config;
+    use crate::{openrouter, Action, ActionOutput, CacheKey};
+    use std::time::{Duration, Instant};
+
+    fn setup() -> (reqwest::Client, Config) {
+        std::env::set_var("THIRDSPACE_BACKEND", "mock");
+        (reqwest::Client::new(), Config::default())
+    }
+
+    #[tokio::test]
+    async fn translation_comes_back_from_inside_the_markers() {
+        let (client, config) = setup();
+        let translated = openrouter::translate(&client, &config, "Guten Morgen")
+            .await
+            .unwrap();
+        assert_eq!(translated, "[English] Guten Morgen");
+    }
+
+    #[tokio::test]
+    async fn reply_without_markers_is_recovered() {
+        let (client, config) = setup();
+        let translated = openrouter::translate(&client, &config, "Hallo [mock:no_markers]")
+            .await
+            .unwrap();
+        assert_eq!(translated, "[English] Hallo [mock:no_markers]");
+    }
+
+    #[tokio::test]
+    async fn error_directive_fails_the_request() {
+        let (client, config) = setup();
+        let result = openrouter::translate(&client, &config, "Hallo [mock:error]").await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn delay_directive_waits_before_answering() {
+        let (client, config) = setup();
+        let started = Instant::now();
+        openrouter::translate(&client, &config, "Hallo [mock:delay=50]")
+            .await
+            .unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn repeated_translation_is_served_from_the_cache() {
+        let (client, config) = setup();
+        let cache: ResultCache<CacheKey, ActionOutput> = ResultCache::default();
+        let input = Input::from("Guten Morgen".to_string());
+        let key = || CacheKey::new(Action::Translate, &config, &input);
+        assert!(cache.get(&key()).is_none());
+
+        let output = Action::Translate
+            .run(&client, &config, &input, &[])
+            .await
+            .unwrap();
+        cache.insert(key(), output, config.translation_cache_size);
+
+        let cached = cache.get(&key()).expect("repeat is a cache hit");
+        assert_eq!(cached.text, "[English] Guten Morgen");
+
+        let mut other = config.clone();
+        other.target_language = "Japanese".to_string();
+        assert!(cache
+            .get(&CacheKey::new(Action::Translate, &other, &input))
+            .is_none());
+    }
+}
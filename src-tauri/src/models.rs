@@ -0,0 +1,113 @@
+use crate::ModelInfo;
+use serde::{Deserialize, Serialize};
+
+/// Models shown per page of `get_models`.
+const PAGE_SIZE: usize = 20;
+/// Context length from which a model counts as long-context.
+const LONG_CONTEXT_TOKENS: u64 = 128_000;
+
+/// Order of `get_models` results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelSort {
+    /// OpenRouter's own order.
+    #[default]
+    Name,
+    /// Cheapest first; models without a fixed price last.
+    Price,
+}
+
+/// Narrows `get_models`; unset fields match every model.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ModelFilters {
+    /// Id prefix before the `/`, e.g. `google`.
+    pub provider: Option<String>,
+    pub reasoning: bool,
+    pub long_context: bool,
+    pub sort: ModelSort,
+}
+
+/// One page of matching models.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelPage {
+    pub models: Vec<ModelInfo>,
+    pub page: usize,
+    pub total: usize,
+    pub has_more: bool,
+    /// Every provider in the catalog, for the provider filter.
+    pub providers: Vec<String>,
+}
+
+pub fn provider(model: &ModelInfo) -> &str {
+    model
+        .id
+        .split_once('/')
+        .map_or("", |(provider, _)| provider)
+}
+
+/// Models whose id or name contains `query` (case-insensitive) and that pass `filters`,
+/// `page` counted from 0.
+pub fn search(
+    catalog: &[ModelInfo],
+    query: &str,
+    filters: &ModelFilters,
+    page: usize,
+) -> ModelPage {
+    let query = query.trim().to_lowercase();
+    let provider_filter = filters
+        .provider
+        .as_deref()
+        .map(str::trim)
+        .filter(|provider| !provider.is_empty());
+    let mut matches: Vec<&ModelInfo> = catalog
+        .iter()
+        .filter(|model| {
+            query.is_empty()
+                || model.id.to_lowercase().contains(&query)
+                || model.name.to_lowercase().contains(&query)
+        })
+        .filter(|model| {
+            provider_filter.is_none_or(|wanted| provider(model).eq_ignore_ascii_case(wanted))
+        })
+        .filter(|model| !filters.reasoning || model.supports_reasoning)
+        .filter(|model| {
+            !filters.long_context
+                || model
+                    .context_length
+                    .is_some_and(|tokens| tokens >= LONG_CONTEXT_TOKENS)
+        })
+        .collect();
+    if filters.sort == ModelSort::Price {
+        matches.sort_by(|a, b| price(a).total_cmp(&price(b)));
+    }
+
+    let mut providers: Vec<String> = catalog
+        .iter()
+        .map(|model| provider(model).to_string())
+        .filter(|provider| !provider.is_empty())
+        .collect();
+    providers.sort_unstable();
+    providers.dedup();
+
+    let total = matches.len();
+    let start = page.saturating_mul(PAGE_SIZE).min(total);
+    let end = (start + PAGE_SIZE).min(total);
+    ModelPage {
+        models: matches[start..end]
+            .iter()
+            .map(|&model| model.clone())
+            .collect(),
+        page,
+        total,
+        has_more: end < total,
+        providers,
+    }
+}
+
+/// USD per prompt plus completion token; unpriced models sort last.
+fn price(model: &ModelInfo) -> f64 {
+    model
+        .pricing
+        .map_or(f64::INFINITY, |pricing| pricing.prompt + pricing.completion)
+}
@@ -0,0 +1,134 @@
+use crate::config;
+use crate::error::CommandError;
+use crate::i18n::{self, Msg};
+use crate::toast::show_toast;
+use crate::{openrouter, AppState};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use tracing::{error, info, warn};
+
+/// One answer from the setup window. The window sends them in order; each is checked
+/// and applied to the live config, which is written to disk only by `Finish`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum Step {
+    ApiKey { key: String },
+    Model { model: String },
+    Hotkey { hotkey: String },
+    Finish,
+}
+
+/// What the setup window shows next, with the current values to prefill.
+#[derive(Debug, Clone, Serialize)]
+pub struct Progress {
+    /// `api_key`, `model`, `hotkey`, `finish` or `done`.
+    pub next: &'static str,
+    pub api_key_set: bool,
+    pub model: String,
+    pub hotkey: String,
+}
+
+/// No config.json yet: ThirdSpace has never been set up on this machine.
+pub fn is_first_run() -> bool {
+    config::config_path().is_ok_and(|path| !path.exists())
+}
+
+pub fn progress(app: &AppHandle, next: &'static str) -> Progress {
+    let config = app.state::<AppState>().config();
+    Progress {
+        next,
+        api_key_set: !config.api_key.trim().is_empty(),
+        model: config.model.clone(),
+        hotkey: config.hotkey.clone(),
+    }
+}
+
+/// Checks and applies one step, returning what comes next. Errors are shown in the
+/// window and the step can be retried.
+pub async fn complete(app: &AppHandle, step: Step) -> Result<Progress, CommandError> {
+    let state = app.state::<AppState>();
+    let next = match step {
+        Step::ApiKey { key } => {
+            let key = key.trim().to_string();
+            if key.is_empty() {
+                return Err(i18n::t(Msg::EnterApiKey).into());
+            }
+            match openrouter::check_key(&state.http(), &key).await {
+                Ok(openrouter::KeyCheck::Valid(_)) => {}
+                Ok(openrouter::KeyCheck::Rejected(reason)) => return Err(reason.into()),
+                Err(e) => {
+                    warn!(error = %e, "Onboarding key check failed");
+                    return Err(i18n::t(Msg::CouldNotReach(&e.to_string())).into());
+                }
+            }
+            state.update_config(|config| config.api_key = key.clone());
+            "model"
+        }
+        Step::Model { model } => {
+            let model = model.trim().to_string();
+            if model.is_empty() {
+                return Err(i18n::t(Msg::PickModel).into());
+            }
+            match crate::models_catalog(&state).await {
+                Ok(catalog) if !catalog.iter().any(|known| known.id == model) => {
+                    return Err(i18n::t(Msg::UnknownModel(&model)).into());
+                }
+                Ok(_) => {}
+                // Without the list the id cannot be checked; a typo shows on first use
+                Err(e) => warn!(error = %e, "Models unavailable during onboarding"),
+            }
+            state.update_config(|config| config.model = model.clone());
+            "hotkey"
+        }
+        Step::Hotkey { hotkey } => {
+            let hotkey = hotkey.trim().to_string();
+            let status = crate::validate_hotkey(app.clone(), hotkey.clone());
+            if !status.valid || !status.available {
+                return Err(status.message.into());
+            }
+            let fast_modifier = state.config().fast_mode_modifier.clone();
+            crate::update_hotkey(app, &state, &hotkey, &fast_modifier)?;
+            state.update_config(|config| config.hotkey = hotkey.clone());
+            "finish"
+        }
+        Step::Finish => {
+            let config = state.config();
+            config::save(&config).map_err(|e| {
+                error!(error = %e, "Failed to save onboarding config");
+                e.to_string()
+            })?;
+            crate::refresh_tray_menu(app);
+            info!(
+                model = %config.model,
+                hotkey = %config.hotkey,
+                "Onboarding completed"
+            );
+            if let Some(window) = app.get_webview_window("onboarding") {
+                let _ = window.close();
+            }
+            show_toast(app, "success", &i18n::t(Msg::Ready(&config.hotkey)));
+            "done"
+        }
+    };
+    Ok(progress(app, next))
+}
+
+/// Opens the setup window on first launch.
+pub fn show(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("onboarding") {
+        let _ = window.show();
+        let _ = window.set_focus();
+        return;
+    }
+    match WebviewWindowBuilder::new(app, "onboarding", WebviewUrl::App("onboarding.html".into()))
+        .title("Welcome to ThirdSpace")
+        .inner_size(460.0, 400.0)
+        .resizable(false)
+        .maximizable(false)
+        .center()
+        .build()
+    {
+        Ok(_) => info!("Onboarding window opened"),
+        Err(e) => error!(error = %e, "Onboarding window failed"),
+    }
+}
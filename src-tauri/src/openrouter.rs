@@ -1,35 +1,391 @@
-use crate::config::Config;
-use crate::prompt;
-use crate::ModelInfo;
+use crate::audit;
+use crate::automodel;
+use crate::chunk;
+use crate::clipboard::Formatted;
+use crate::config::{Config, CustomAction, ReasoningEffort};
+use crate::i18n::{self, Msg};
+use crate::mask::{self, Masked};
+use crate::policy;
+use crate::postformat;
+use crate::prompt::{self, ContextPair, Prompt};
+use crate::redact::Redactions;
+use crate::{ModelInfo, ModelPricing};
 use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::time::Instant;
-use tracing::{debug, error, info};
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+use tracing::{debug, error, info, warn, Instrument};
 
-const OPENROUTER_URL: &str = "https://openrouter.ai/api/v1/chat/completions";
-const OPENROUTER_MODELS_URL: &str = "https://openrouter.ai/api/v1/models";
+/// Where requests go unless `api_base_url` names another OpenRouter-compatible API.
+pub const DEFAULT_API_BASE: &str = "https://openrouter.ai/api/v1";
+const CHAT_PATH: &str = "chat/completions";
+const MODELS_PATH: &str = "models";
+const KEY_PATH: &str = "key";
+const CREDITS_PATH: &str = "credits";
+/// Idle pooled connections are kept this long between translations.
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Responses that needed marker recovery since startup; logged with each recovery.
+static MARKER_RECOVERIES: AtomicU64 = AtomicU64::new(0);
+/// Whether log previews may contain request and response text; see `Config::log_content`.
+static LOG_CONTENT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_log_content(enabled: bool) {
+    LOG_CONTENT.store(enabled, Ordering::Relaxed);
+}
+
+/// `Config::api_base_url` without its trailing slash; empty means `DEFAULT_API_BASE`.
+static API_BASE: RwLock<String> = RwLock::new(String::new());
+
+pub fn set_api_base(url: &str) {
+    *API_BASE.write().unwrap() = url.trim().trim_end_matches('/').to_string();
+}
+
+/// `path` under the configured API base.
+fn api_url(path: &str) -> String {
+    let base = API_BASE.read().unwrap();
+    let base = if base.is_empty() {
+        DEFAULT_API_BASE
+    } else {
+        base.as_str()
+    };
+    format!("{}/{}", base, path)
+}
+
+/// `host:port` every request goes to; checked by `run_diagnostics`.
+pub fn api_address() -> String {
+    let Ok(url) = reqwest::Url::parse(&api_url("")) else {
+        return String::new();
+    };
+    match (url.host_str(), url.port_or_known_default()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        _ => String::new(),
+    }
+}
+
+/// Optional request parameters each model takes, from the last models fetch.
+static MODEL_SUPPORT: LazyLock<Mutex<HashMap<String, ModelSupport>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy)]
+struct ModelSupport {
+    reasoning: bool,
+    structured_output: bool,
+}
+
+/// What the models list says `model` takes. Variants such as `:free` or `:nitro` fall back
+/// to the base model's entry; `None` when nothing was fetched yet or OpenRouter does not
+/// list the model.
+fn model_support(model: &str) -> Option<ModelSupport> {
+    let support = MODEL_SUPPORT.lock().unwrap();
+    support.get(model).copied().or_else(|| {
+        let (base, _) = model.split_once(':')?;
+        support.get(base).copied()
+    })
+}
+
+/// Whether translations for `config` ask for a JSON object: `structured_output` is on and
+/// the models list says the model takes `response_format` or `structured_outputs`. Other
+/// models get the marker prompt, which every model can follow.
+pub fn structured_output(config: &Config) -> bool {
+    config.structured_output
+        && model_support(&config.model).is_some_and(|support| support.structured_output)
+}
+
+/// Builds the HTTP client shared by every OpenRouter call, so connections and TLS
+/// sessions are reused. Proxies come from `proxy`, else from the usual
+/// `HTTPS_PROXY`/`ALL_PROXY` variables, unless `bypass_proxy` is set. A timeout of 0 in
+/// the config means none.
+pub fn http_client(config: &Config) -> Result<Client> {
+    let mut builder = Client::builder()
+        .user_agent(concat!("ThirdSpace/", env!("CARGO_PKG_VERSION")))
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tls_built_in_native_certs(config.use_system_certificates);
+    if !config.ca_bundle.trim().is_empty() {
+        for certificate in load_ca_bundle(config.ca_bundle.trim())? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    if config.bypass_proxy {
+        builder = builder.no_proxy();
+    } else if !config.proxy.trim().is_empty() {
+        let proxy = reqwest::Proxy::all(config.proxy.trim())
+            .with_context(|| format!("invalid proxy {}", config.proxy.trim()))?;
+        builder = builder.proxy(proxy);
+    }
+    if config.connect_timeout_secs > 0 {
+        builder = builder.connect_timeout(Duration::from_secs(config.connect_timeout_secs));
+    }
+    if config.request_timeout_secs > 0 {
+        builder = builder.timeout(Duration::from_secs(config.request_timeout_secs));
+    }
+    builder.build().context("build HTTP client")
+}
+
+/// Reads the root certificates in a PEM file; a file without any is an error.
+pub fn load_ca_bundle(path: &str) -> Result<Vec<reqwest::Certificate>> {
+    let pem = std::fs::read(path).with_context(|| format!("read {}", path))?;
+    let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+        .with_context(|| format!("parse certificates in {}", path))?;
+    if certificates.is_empty() {
+        return Err(anyhow!("no certificates in {}", path));
+    }
+    Ok(certificates)
+}
+
+/// Whether a request never reached OpenRouter (DNS, refused or timed-out connection).
+pub fn is_offline(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .any(reqwest::Error::is_connect)
+}
+
+/// OpenRouter answered with an error status; kept typed so callers can tell rate limits
+/// from other failures.
+#[derive(Debug)]
+pub struct StatusError {
+    pub status: reqwest::StatusCode,
+    /// A `preview` of the response body: errors are logged with `%e`, and moderation
+    /// errors echo the input back.
+    body: String,
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OpenRouter error {}: {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+/// The response arrived but the expected output could not be taken out of it.
+#[derive(Debug)]
+pub struct ExtractionError(String);
+
+impl fmt::Display for ExtractionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ExtractionError {}
+
+/// A request was attempted without an API key.
+#[derive(Debug)]
+pub struct MissingApiKey;
+
+impl fmt::Display for MissingApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("API key is empty")
+    }
+}
+
+impl std::error::Error for MissingApiKey {}
+
+fn status_error(status: reqwest::StatusCode, body: String) -> anyhow::Error {
+    StatusError { status, body }.into()
+}
+
+fn extraction_error(message: impl Into<String>) -> anyhow::Error {
+    ExtractionError(message.into()).into()
+}
+
+/// Whether a request failed because the connect or request timeout ran out.
+pub fn is_timeout(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .any(reqwest::Error::is_timeout)
+}
 
 #[derive(Debug, Serialize)]
 struct ChatRequest {
     model: String,
     messages: Vec<Message>,
-    reasoning: Reasoning,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning: Option<Reasoning>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<ResponseFormat>,
+}
+
+/// Asks for output matching a JSON schema. Models without structured output support
+/// ignore it and fall back to following the prompt.
+#[derive(Debug, Serialize)]
+struct ResponseFormat {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    json_schema: JsonSchema,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonSchema {
+    name: &'static str,
+    strict: bool,
+    schema: Value,
+}
+
+impl ResponseFormat {
+    fn translation() -> Self {
+        ResponseFormat {
+            kind: "json_schema",
+            json_schema: JsonSchema {
+                name: "translation",
+                strict: true,
+                schema: prompt::translation_schema(),
+            },
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Message {
     role: String,
-    content: String,
+    content: Content,
+}
+
+/// Message content: plain text, or text and images for vision requests.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Content {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentPart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrl },
+    InputAudio { input_audio: InputAudio },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ImageUrl {
+    url: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InputAudio {
+    /// Base64 without a data URL prefix.
+    data: String,
+    format: String,
+}
+
+impl Content {
+    /// The text of the message, without its images.
+    fn text(&self) -> &str {
+        match self {
+            Content::Text(text) => text,
+            Content::Parts(parts) => parts
+                .iter()
+                .find_map(|part| match part {
+                    ContentPart::Text { text } => Some(text.as_str()),
+                    ContentPart::ImageUrl { .. } | ContentPart::InputAudio { .. } => None,
+                })
+                .unwrap_or(""),
+        }
+    }
+}
+
+impl Content {
+    fn redact(&mut self, redactions: &mut Redactions) {
+        match self {
+            Content::Text(text) => *text = redactions.redact(text),
+            Content::Parts(parts) => {
+                for part in parts {
+                    if let ContentPart::Text { text } = part {
+                        *text = redactions.redact(text);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl From<String> for Content {
+    fn from(text: String) -> Self {
+        Content::Text(text)
+    }
+}
+
+/// OpenRouter's `reasoning` object: `enabled: false`, an `effort`, or a `max_tokens` budget.
 #[derive(Debug, Serialize)]
 struct Reasoning {
-    enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effort: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+}
+
+impl From<ReasoningEffort> for Reasoning {
+    fn from(effort: ReasoningEffort) -> Self {
+        let (enabled, effort, max_tokens) = match effort {
+            ReasoningEffort::Off => (Some(false), None, None),
+            ReasoningEffort::Low => (None, Some("low"), None),
+            ReasoningEffort::Medium => (None, Some("medium"), None),
+            ReasoningEffort::High => (None, Some("high"), None),
+            ReasoningEffort::Budget(tokens) => (None, None, Some(tokens)),
+        };
+        Reasoning {
+            enabled,
+            effort,
+            max_tokens,
+        }
+    }
+}
+
+/// The `reasoning` object for `config.model`, or `None` when the models list says the
+/// model does not take it; sending it anyway gets some providers to reject the request.
+/// Models the list does not know get the parameter as configured.
+fn reasoning_for(config: &Config) -> Option<Reasoning> {
+    if model_support(&config.model).is_some_and(|support| !support.reasoning) {
+        if config.reasoning != ReasoningEffort::Off {
+            debug!(
+                model = %config.model,
+                reasoning = ?config.reasoning,
+                "Reasoning omitted; model does not support it"
+            );
+        }
+        return None;
+    }
+    Some(config.reasoning.into())
 }
 
 #[derive(Debug, Deserialize)]
 struct ChatResponse {
     choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<ResponseUsage>,
+}
+
+/// Tokens a request used; `cost` is in USD.
+#[derive(Debug, Deserialize)]
+struct ResponseUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+    #[serde(default)]
+    cost: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -37,38 +393,594 @@ struct Choice {
     message: Message,
 }
 
-pub async fn translate(config: &Config, input: &str) -> Result<String> {
+pub async fn translate(client: &Client, config: &Config, input: &str) -> Result<String> {
+    translate_with_progress(client, config, input, |_, _| {}).await
+}
+
+/// Like `translate`, reporting `(chunks done, chunks total)` as the work advances.
+pub async fn translate_with_progress(
+    client: &Client,
+    config: &Config,
+    input: &str,
+    on_progress: impl Fn(usize, usize),
+) -> Result<String> {
+    if chunk::needs_split(input, config.chunk_max_chars) {
+        translate_chunked(client, config, input, on_progress).await
+    } else {
+        let translated = translate_single(client, config, input, &[]).await?;
+        on_progress(1, 1);
+        Ok(translated)
+    }
+}
+
+/// Translates paragraph-aligned chunks, at most `chunk_concurrency` at a time, and
+/// stitches them back together with the original separators.
+async fn translate_chunked(
+    client: &Client,
+    config: &Config,
+    input: &str,
+    on_progress: impl Fn(usize, usize),
+) -> Result<String> {
+    let chunks = chunk::split(input, config.chunk_max_chars);
+    let concurrency = config.chunk_concurrency.max(1);
+    info!(
+        input_len = input.len(),
+        chunks = chunks.len(),
+        concurrency,
+        "Translating long input in chunks"
+    );
+
+    let mut translated = vec![String::new(); chunks.len()];
+    let mut done = 0;
+    let confirmed = crate::usage::is_confirmed();
+    for (batch_idx, batch) in chunks.chunks(concurrency).enumerate() {
+        let mut tasks = JoinSet::new();
+        for (offset, piece) in batch.iter().enumerate() {
+            let idx = batch_idx * concurrency + offset;
+            let client = client.clone();
+            let config = config.clone();
+            let text = piece.text.clone();
+            tasks.spawn(
+                crate::usage::confirmed(confirmed, async move {
+                    if text.trim().is_empty() {
+                        return (idx, Ok(text));
+                    }
+                    (idx, translate_single(&client, &config, &text, &[]).await)
+                })
+                .in_current_span(),
+            );
+        }
+        while let Some(joined) = tasks.join_next().await {
+            let (idx, result) = joined.context("chunk translation task failed")?;
+            translated[idx] = result
+                .with_context(|| format!("translate chunk {} of {}", idx + 1, chunks.len()))?;
+            done += 1;
+            on_progress(done, chunks.len());
+        }
+    }
+
+    Ok(chunk::join(&chunks, &translated))
+}
+
+/// Translates the text of an HTML or RTF document, keeping its markup in place. Returns the
+/// rebuilt document and its plain-text flavor. Fails rather than return broken markup when
+/// the model loses a placeholder.
+pub async fn translate_formatted(
+    client: &Client,
+    config: &Config,
+    formatted: &Formatted,
+) -> Result<(Formatted, String)> {
+    let masked = formatted.mask();
+    if chunk::needs_split(&masked.text, config.chunk_max_chars) {
+        return Err(anyhow!("Formatted input too long for a single request"));
+    }
+    let prompt = prompt::build_prompt(
+        &masked.text,
+        &config.target_language,
+        config.translation_tone,
+        structured_output(config),
+    );
+    let translated = request_translation(client, config, &masked.text, prompt).await?;
+    let missing = masked.missing(&translated);
+    if !missing.is_empty() {
+        return Err(anyhow!("Lost {} markup spans", missing.len()));
+    }
+    info!(
+        kind = formatted.kind(),
+        spans = masked.len(),
+        "Formatted translation restored"
+    );
+    Ok(formatted.restore(&masked, &translated))
+}
+
+/// Like `translate`, with earlier messages of the conversation as context. Long inputs
+/// are chunked without it.
+pub async fn translate_in_context(
+    client: &Client,
+    config: &Config,
+    input: &str,
+    context: &[ContextPair],
+) -> Result<String> {
+    if context.is_empty() || chunk::needs_split(input, config.chunk_max_chars) {
+        return translate(client, config, input).await;
+    }
+    debug!(
+        pairs = context.len(),
+        "Translating with conversation context"
+    );
+    translate_single(client, config, input, context).await
+}
+
+async fn translate_single(
+    client: &Client,
+    config: &Config,
+    input: &str,
+    context: &[ContextPair],
+) -> Result<String> {
+    let masked = mask::mask(input);
+    let prompt = prompt::with_context(
+        prompt::build_prompt(
+            &masked.text,
+            &config.target_language,
+            config.translation_tone,
+            structured_output(config),
+        ),
+        context,
+    );
+    let translated = request_translation(client, config, &masked.text, prompt).await?;
+    Ok(unmask(&masked, &translated))
+}
+
+pub async fn translate_commit_message(
+    client: &Client,
+    config: &Config,
+    input: &str,
+) -> Result<String> {
+    let masked = mask::mask(input);
+    let prompt = prompt::build_commit_prompt(
+        &masked.text,
+        &config.target_language,
+        structured_output(config),
+    );
+    let translated = request_translation(client, config, &masked.text, prompt).await?;
+    let missing = masked.missing(&translated);
+    let translated = if missing.is_empty() {
+        unmask(&masked, &translated)
+    } else {
+        // A dropped placeholder would silently lose a code span or URL, so retry
+        // with the spans left in the text
+        warn!(missing = ?missing, "Model dropped masked spans, retrying unmasked");
+        let prompt =
+            prompt::build_commit_prompt(input, &config.target_language, structured_output(config));
+        request_translation(client, config, input, prompt).await?
+    };
+
+    let missing = prompt::missing_commit_references(input, &translated);
+    if !missing.is_empty() {
+        error!(missing = ?missing, "Commit references lost in translation");
+        return Err(anyhow!(
+            "Translation dropped references: {}",
+            missing.join(", ")
+        ));
+    }
+
+    Ok(translated)
+}
+
+pub async fn translate_romanized(
+    client: &Client,
+    config: &Config,
+    input: &str,
+) -> Result<prompt::Romanized> {
+    let masked = mask::mask(input);
+    let prompt = prompt::build_romanization_prompt(
+        &masked.text,
+        &config.target_language,
+        config.translation_tone,
+    );
+    let content = request_completion(client, config, &masked.text, prompt).await?;
+    let translation = unmask(&masked, &extract_logged(&content)?);
+    let romanization =
+        prompt::extract_romanization(&content).map(|romanized| masked.restore(&romanized));
+    if romanization.is_none() {
+        debug!("OpenRouter response has no romanization section");
+    }
+    Ok(prompt::Romanized {
+        translation,
+        romanization,
+    })
+}
+
+pub async fn summarize(client: &Client, config: &Config, input: &str) -> Result<String> {
+    let prompt = prompt::build_summary_prompt(input, &config.target_language);
+    let content = request_completion(client, config, input, prompt).await?;
+    let summary =
+        prompt::extract_summary(&content).ok_or_else(|| missing_markers(&content, "summary"))?;
+    info!(summary_len = summary.len(), "OpenRouter summary extracted");
+    Ok(summary)
+}
+
+pub async fn proofread(client: &Client, config: &Config, input: &str) -> Result<String> {
+    let prompt = prompt::build_proofread_prompt(input);
+    let content = request_completion(client, config, input, prompt).await?;
+    let corrected = prompt::extract_proofread(&content)
+        .ok_or_else(|| missing_markers(&content, "proofread"))?;
+    info!(
+        corrected_len = corrected.len(),
+        changed = corrected != input.trim(),
+        "OpenRouter proofread extracted"
+    );
+    Ok(corrected)
+}
+
+pub async fn rewrite(
+    client: &Client,
+    config: &Config,
+    input: &str,
+    tone: prompt::Tone,
+) -> Result<String> {
+    let prompt = prompt::build_rewrite_prompt(input, tone);
+    let content = request_completion(client, config, input, prompt).await?;
+    let rewritten =
+        prompt::extract_rewrite(&content).ok_or_else(|| missing_markers(&content, "rewrite"))?;
+    info!(
+        tone = tone.id(),
+        rewritten_len = rewritten.len(),
+        "OpenRouter rewrite extracted"
+    );
+    Ok(rewritten)
+}
+
+pub async fn run_custom(
+    client: &Client,
+    config: &Config,
+    input: &str,
+    action: &CustomAction,
+) -> Result<String> {
+    let prompt = prompt::build_custom_prompt(&action.prompt, input, &config.target_language);
+    let content = request_completion(client, config, input, prompt).await?;
+    let output =
+        prompt::extract_output(&content).ok_or_else(|| missing_markers(&content, "output"))?;
+    info!(
+        action = %action.name,
+        output_len = output.len(),
+        "OpenRouter custom action output extracted"
+    );
+    Ok(output)
+}
+
+pub async fn lookup(
+    client: &Client,
+    config: &Config,
+    input: &str,
+) -> Result<prompt::DictionaryEntry> {
+    let prompt = prompt::build_dictionary_prompt(input.trim(), &config.target_language);
+    let content = request_completion(client, config, input, prompt).await?;
+    match prompt::extract_dictionary_entry(&content) {
+        Some(entry) => {
+            info!(
+                definitions = entry.definitions.len(),
+                examples = entry.examples.len(),
+                "OpenRouter dictionary entry extracted"
+            );
+            Ok(entry)
+        }
+        None => {
+            error!(
+                response_preview = %preview(&content, 400),
+                "OpenRouter response missing dictionary entry"
+            );
+            Err(extraction_error("Missing dictionary entry in response"))
+        }
+    }
+}
+
+/// Transcribes the text in a PNG screenshot. `config.model` must accept images.
+pub async fn recognize_text(client: &Client, config: &Config, png: &[u8]) -> Result<String> {
+    let image = ContentPart::ImageUrl {
+        image_url: ImageUrl {
+            url: format!("data:image/png;base64,{}", BASE64.encode(png)),
+        },
+    };
+    let content = request_attachment(client, config, prompt::build_ocr_prompt(), image).await?;
+    match prompt::extract_ocr(&content) {
+        Some(text) => {
+            info!(
+                image_len = png.len(),
+                text_len = text.len(),
+                "OpenRouter text recognized"
+            );
+            Ok(text)
+        }
+        None if content.contains(prompt::OCR_START) => {
+            Err(extraction_error("No text found in the image"))
+        }
+        None => Err(missing_markers(&content, "text")),
+    }
+}
+
+/// Transcribes a WAV voice recording. `config.model` must accept audio.
+pub async fn transcribe(client: &Client, config: &Config, wav: &[u8]) -> Result<String> {
+    let audio = ContentPart::InputAudio {
+        input_audio: InputAudio {
+            data: BASE64.encode(wav),
+            format: "wav".to_string(),
+        },
+    };
+    let prompt = prompt::build_transcription_prompt();
+    let content = request_attachment(client, config, prompt, audio).await?;
+    match prompt::extract_transcript(&content) {
+        Some(transcript) => {
+            info!(
+                audio_len = wav.len(),
+                transcript_len = transcript.len(),
+                "OpenRouter speech transcribed"
+            );
+            Ok(transcript)
+        }
+        None if content.contains(prompt::TRANSCRIPT_START) => {
+            Err(extraction_error("No speech recognized"))
+        }
+        None => Err(missing_markers(&content, "transcript")),
+    }
+}
+
+/// Sends a prompt whose user message carries an image or audio part next to its text.
+async fn request_attachment(
+    client: &Client,
+    config: &Config,
+    prompt: Prompt,
+    attachment: ContentPart,
+) -> Result<String> {
+    let messages = vec![
+        Message {
+            role: "system".to_string(),
+            content: prompt.system.into(),
+        },
+        Message {
+            role: "user".to_string(),
+            content: Content::Parts(vec![
+                ContentPart::Text {
+                    text: prompt.user.clone(),
+                },
+                attachment,
+            ]),
+        },
+    ];
+    request_chat(client, config, &prompt.user, messages, None).await
+}
+
+async fn request_translation(
+    client: &Client,
+    config: &Config,
+    input: &str,
+    prompt: Prompt,
+) -> Result<String> {
+    let translated = if structured_output(config) {
+        let format = ResponseFormat::translation();
+        let content =
+            request_chat(client, config, input, chat_messages(prompt), Some(format)).await?;
+        extract_structured_logged(&content)?
+    } else {
+        let messages = chat_messages(prompt);
+        let content = request_chat(client, config, input, messages.clone(), None).await?;
+        if prompt::extract_translation(&content).is_none() {
+            recover_translation(client, config, input, messages, content).await?
+        } else {
+            extract_logged(&content)?
+        }
+    };
+    Ok(postformat::apply(&config.post_format, input, translated))
+}
+
+/// Handles a response without translation markers: asks the model once to re-emit the
+/// marked translation, then falls back to salvaging the original response.
+async fn recover_translation(
+    client: &Client,
+    config: &Config,
+    input: &str,
+    mut messages: Vec<Message>,
+    content: String,
+) -> Result<String> {
+    let recoveries = MARKER_RECOVERIES.fetch_add(1, Ordering::Relaxed) + 1;
+    warn!(
+        recoveries,
+        response_preview = %preview(&content, 400),
+        "Translation markers missing, asking the model to re-emit"
+    );
+
+    messages.push(Message {
+        role: "assistant".to_string(),
+        content: content.clone().into(),
+    });
+    messages.push(Message {
+        role: "user".to_string(),
+        content: prompt::marker_correction().into(),
+    });
+    match request_chat(client, config, input, messages, None).await {
+        Ok(retry) => {
+            if let Some(extracted) = prompt::extract_translation(&retry) {
+                info!(
+                    recoveries,
+                    method = "follow_up",
+                    translated_len = extracted.len(),
+                    "Translation recovered"
+                );
+                return Ok(extracted);
+            }
+        }
+        Err(e) => warn!(error = %e, "Marker follow-up request failed"),
+    }
+
+    match prompt::salvage_translation(&content) {
+        Some(extracted) => {
+            warn!(
+                recoveries,
+                method = "heuristic",
+                translated_len = extracted.len(),
+                translated_preview = %preview(&extracted, 200),
+                "Translation recovered"
+            );
+            Ok(extracted)
+        }
+        None => Err(missing_markers(&content, "translation")),
+    }
+}
+
+fn chat_messages(prompt: Prompt) -> Vec<Message> {
+    vec![
+        Message {
+            role: "system".to_string(),
+            content: prompt.system.into(),
+        },
+        Message {
+            role: "user".to_string(),
+            content: prompt.user.into(),
+        },
+    ]
+}
+
+/// Sends a system + user chat completion and returns the raw model output.
+async fn request_completion(
+    client: &Client,
+    config: &Config,
+    input: &str,
+    prompt: Prompt,
+) -> Result<String> {
+    request_chat(client, config, input, chat_messages(prompt), None).await
+}
+
+async fn request_chat(
+    client: &Client,
+    config: &Config,
+    input: &str,
+    mut messages: Vec<Message>,
+    response_format: Option<ResponseFormat>,
+) -> Result<String> {
+    // Every path that spends credits comes through here, not only execute_action
+    crate::usage::check(config)?;
+    // Requests that did not go through execute_action still carry "auto"
+    let resolved;
+    let config = if automodel::is_auto(&config.model) {
+        let mut auto = config.clone();
+        automodel::resolve(&mut auto, input);
+        resolved = auto;
+        &resolved
+    } else {
+        config
+    };
+    let mut redactions = Redactions::default();
+    let redacted_input;
+    let input = if config.redact_personal_data {
+        redact_messages(&mut messages, &mut redactions);
+        redacted_input = redactions.redact(input);
+        redacted_input.as_str()
+    } else {
+        input
+    };
+
+    #[cfg(feature = "mock")]
+    if crate::mock::enabled() {
+        // The first user message is the input; later ones are recovery follow-ups
+        let system = messages
+            .iter()
+            .find(|m| m.role == "system")
+            .map_or("", |m| m.content.text());
+        let user = messages
+            .iter()
+            .find(|m| m.role == "user")
+            .map_or("", |m| m.content.text());
+        return crate::mock::respond(config, system, user, response_format.is_some())
+            .await
+            .map(|content| redactions.restore(&content));
+    }
+
     if config.api_key.trim().is_empty() {
-        return Err(anyhow!("API key is empty"));
+        return Err(MissingApiKey.into());
+    }
+    // Models picked per request (app rules, fast mode, auto) get checked here too
+    if !policy::current().allows_model(&config.model) {
+        return Err(anyhow!(i18n::t(Msg::ModelNotAllowed(&config.model))));
     }
 
-    let prompt = prompt::build_prompt(input, &config.target_language);
     info!(
         model = %config.model,
         target_language = %config.target_language,
-        reasoning = config.reasoning_enabled,
+        reasoning = ?config.reasoning,
+        temperature = ?config.temperature,
+        structured = response_format.is_some(),
         input_len = input.len(),
-        prompt_len = prompt.len(),
+        messages = messages.len(),
+        prompt_len = messages.iter().map(|m| m.content.text().len()).sum::<usize>(),
         input_preview = %preview(input, 200),
         "OpenRouter request prepared"
     );
     let request = ChatRequest {
         model: config.model.clone(),
-        messages: vec![Message {
-            role: "user".to_string(),
-            content: prompt,
-        }],
-        reasoning: Reasoning {
-            enabled: config.reasoning_enabled,
-        },
+        messages,
+        reasoning: reasoning_for(config),
+        temperature: config.temperature,
+        top_p: config.top_p,
+        max_tokens: config.max_tokens,
+        response_format,
     };
 
-    let client = reqwest::Client::new();
     let start = Instant::now();
+    let body = send_chat(client, config, &request, start).await;
+    audit::record(
+        &api_url(CHAT_PATH),
+        &request,
+        body.as_ref().map(String::as_str),
+        start.elapsed(),
+    );
+    let body = body?;
+
+    let parsed: ChatResponse = match serde_json::from_str(&body).context("parse response json") {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            error!(
+                error = %e,
+                body_preview = %preview(&body, 400),
+                "OpenRouter response parse failed"
+            );
+            return Err(e);
+        }
+    };
+    if let Some(usage) = &parsed.usage {
+        crate::usage::record(usage.prompt_tokens, usage.completion_tokens, usage.cost);
+    }
+    let content = match parsed.choices.first() {
+        Some(choice) => choice.message.content.text(),
+        None => {
+            error!(
+                body_preview = %preview(&body, 400),
+                "OpenRouter response missing choices"
+            );
+            return Err(extraction_error("OpenRouter response missing choices"));
+        }
+    };
+
+    debug!(
+        response_len = content.len(),
+        response_preview = %preview(content, 400),
+        "OpenRouter response parsed"
+    );
+
+    Ok(redactions.restore(content))
+}
+
+/// Posts a chat request and returns the response body; transport failures and error
+/// statuses are logged and returned as errors.
+async fn send_chat(
+    client: &Client,
+    config: &Config,
+    request: &ChatRequest,
+    start: Instant,
+) -> Result<String> {
     let response = client
-        .post(OPENROUTER_URL)
+        .post(api_url(CHAT_PATH))
         .bearer_auth(&config.api_key)
-        .json(&request)
+        .json(request)
         .send()
         .await
         .context("send OpenRouter request");
@@ -108,48 +1020,53 @@ pub async fn translate(config: &Config, input: &str) -> Result<String> {
             body_preview = %preview(&body, 400),
             "OpenRouter request failed"
         );
-        return Err(anyhow!("OpenRouter error {}: {}", status, body));
+        return Err(status_error(status, preview(&body, 400)));
     }
 
     info!(status = %status, duration_ms, "OpenRouter response received");
+    Ok(body)
+}
 
-    let parsed: ChatResponse = match serde_json::from_str(&body).context("parse response json") {
-        Ok(parsed) => parsed,
-        Err(e) => {
-            error!(
-                error = %e,
-                body_preview = %preview(&body, 400),
-                "OpenRouter response parse failed"
-            );
-            return Err(e);
-        }
-    };
-    let content = match parsed.choices.first() {
-        Some(choice) => choice.message.content.as_str(),
-        None => {
-            error!(
-                body_preview = %preview(&body, 400),
-                "OpenRouter response missing choices"
-            );
-            return Err(anyhow!("OpenRouter response missing choices"));
-        }
-    };
+/// Swaps personal data in every message for tokens, and tells the model to keep them.
+fn redact_messages(messages: &mut [Message], redactions: &mut Redactions) {
+    for message in messages.iter_mut() {
+        message.content.redact(redactions);
+    }
+    if redactions.is_empty() {
+        return;
+    }
+    if let Some(Content::Text(system)) = messages
+        .iter_mut()
+        .find(|message| message.role == "system")
+        .map(|message| &mut message.content)
+    {
+        system.push_str(prompt::redaction_note());
+    }
+    info!(values = redactions.len(), "Personal data redacted");
+}
 
-    debug!(
-        response_len = content.len(),
-        response_preview = %preview(content, 400),
-        "OpenRouter response parsed"
-    );
+/// Restores masked spans, logging any placeholders the model lost along the way.
+fn unmask(masked: &Masked, output: &str) -> String {
+    if masked.is_empty() {
+        return output.to_string();
+    }
+    let missing = masked.missing(output);
+    if missing.is_empty() {
+        debug!(spans = masked.len(), "Restored masked spans");
+    } else {
+        warn!(
+            spans = masked.len(),
+            missing = ?missing,
+            "Model dropped masked spans"
+        );
+    }
+    masked.restore(output)
+}
 
+fn extract_logged(content: &str) -> Result<String> {
     let extracted = match prompt::extract_translation(content) {
         Some(extracted) => extracted,
-        None => {
-            error!(
-                response_preview = %preview(content, 400),
-                "OpenRouter response missing translation markers"
-            );
-            return Err(anyhow!("Missing translation markers in response"));
-        }
+        None => return Err(missing_markers(content, "translation")),
     };
 
     info!(
@@ -161,7 +1078,40 @@ pub async fn translate(config: &Config, input: &str) -> Result<String> {
     Ok(extracted)
 }
 
+fn extract_structured_logged(content: &str) -> Result<String> {
+    let Some(extracted) = prompt::extract_json_translation(content) else {
+        error!(
+            response_preview = %preview(content, 400),
+            "OpenRouter response is not a structured translation"
+        );
+        return Err(extraction_error(
+            "Missing translation in structured response",
+        ));
+    };
+
+    info!(
+        translated_len = extracted.len(),
+        translated_preview = %preview(&extracted, 200),
+        structured = true,
+        "OpenRouter translation extracted"
+    );
+
+    Ok(extracted)
+}
+
+fn missing_markers(content: &str, kind: &str) -> anyhow::Error {
+    error!(
+        kind,
+        response_preview = %preview(content, 400),
+        "OpenRouter response missing markers"
+    );
+    extraction_error(format!("Missing {} markers in response", kind))
+}
+
 fn preview(input: &str, limit: usize) -> String {
+    if !LOG_CONTENT.load(Ordering::Relaxed) {
+        return redacted(input);
+    }
     let cleaned = input.replace('\n', " ").replace('\r', " ");
     let mut out = String::new();
     let mut chars = cleaned.chars();
@@ -178,6 +1128,17 @@ fn preview(input: &str, limit: usize) -> String {
     out
 }
 
+/// Length and a stable hash, enough to tell texts apart without recording them.
+fn redacted(input: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    input.hash(&mut hasher);
+    format!(
+        "<redacted len={} hash={:016x}>",
+        input.len(),
+        hasher.finish()
+    )
+}
+
 #[derive(Debug, Deserialize)]
 struct ModelsResponse {
     data: Vec<ModelData>,
@@ -187,16 +1148,36 @@ struct ModelsResponse {
 struct ModelData {
     id: String,
     name: String,
+    #[serde(default)]
+    pricing: Option<PricingData>,
+    #[serde(default)]
+    context_length: Option<u64>,
+    #[serde(default)]
+    supported_parameters: Vec<String>,
+}
+
+/// USD per token, as decimal strings; routers report `-1`.
+#[derive(Debug, Deserialize)]
+struct PricingData {
+    prompt: String,
+    completion: String,
 }
 
-pub async fn fetch_models(api_key: &str) -> Result<Vec<ModelInfo>> {
-    let client = reqwest::Client::new();
+impl PricingData {
+    fn parse(&self) -> Option<ModelPricing> {
+        let prompt: f64 = self.prompt.parse().ok()?;
+        let completion: f64 = self.completion.parse().ok()?;
+        (prompt >= 0.0 && completion >= 0.0).then_some(ModelPricing { prompt, completion })
+    }
+}
+
+pub async fn fetch_models(client: &Client, api_key: &str) -> Result<Vec<ModelInfo>> {
     let start = Instant::now();
 
     debug!("Fetching models from OpenRouter");
 
     let response = client
-        .get(OPENROUTER_MODELS_URL)
+        .get(api_url(MODELS_PATH))
         .bearer_auth(api_key)
         .send()
         .await
@@ -217,7 +1198,7 @@ pub async fn fetch_models(api_key: &str) -> Result<Vec<ModelInfo>> {
             body_preview = %preview(&body, 400),
             "OpenRouter models request failed"
         );
-        return Err(anyhow!("OpenRouter error {}: {}", status, body));
+        return Err(status_error(status, preview(&body, 400)));
     }
 
     info!(status = %status, duration_ms, "OpenRouter models response received");
@@ -228,11 +1209,188 @@ pub async fn fetch_models(api_key: &str) -> Result<Vec<ModelInfo>> {
         .data
         .into_iter()
         .map(|m| ModelInfo {
+            pricing: m.pricing.as_ref().and_then(PricingData::parse),
+            context_length: m.context_length.filter(|&tokens| tokens > 0),
+            supports_reasoning: m
+                .supported_parameters
+                .iter()
+                .any(|parameter| parameter == "reasoning"),
+            supports_structured_output: m.supported_parameters.iter().any(|parameter| {
+                parameter == "structured_outputs" || parameter == "response_format"
+            }),
             id: m.id,
             name: m.name,
         })
         .collect();
 
+    *MODEL_SUPPORT.lock().unwrap() = models
+        .iter()
+        .map(|model| {
+            let support = ModelSupport {
+                reasoning: model.supports_reasoning,
+                structured_output: model.supports_structured_output,
+            };
+            (model.id.clone(), support)
+        })
+        .collect();
     info!(count = models.len(), "Models parsed successfully");
     Ok(models)
 }
+
+/// What OpenRouter reports about the API key making the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyInfo {
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub is_free_tier: bool,
+    /// Credits spent through this key, in USD.
+    #[serde(default)]
+    pub usage: f64,
+    /// Spending cap set on the key; `None` when unlimited.
+    #[serde(default)]
+    pub limit: Option<f64>,
+    #[serde(default)]
+    pub limit_remaining: Option<f64>,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub requests: u64,
+    pub interval: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreditsResponse {
+    data: Credits,
+}
+
+#[derive(Debug, Deserialize)]
+struct Credits {
+    total_credits: f64,
+    total_usage: f64,
+}
+
+/// Account balance and the limits of the configured key.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountStatus {
+    pub key: KeyInfo,
+    /// Purchased credits minus usage across the account, in USD, when OpenRouter reports it.
+    pub credits_remaining: Option<f64>,
+}
+
+impl AccountStatus {
+    /// The tighter of the account balance and the key's own spending cap.
+    pub fn remaining(&self) -> Option<f64> {
+        match (self.credits_remaining, self.key.limit_remaining) {
+            (Some(credits), Some(limit)) => Some(credits.min(limit)),
+            (credits, limit) => credits.or(limit),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyResponse {
+    data: KeyInfo,
+}
+
+/// Outcome of asking OpenRouter about a key. Network and server failures are errors
+/// instead, since they say nothing about the key itself.
+pub enum KeyCheck {
+    Valid(KeyInfo),
+    Rejected(String),
+}
+
+/// Sends an unauthenticated HEAD request, proving DNS, TCP and TLS all work. Any HTTP
+/// status counts as reachable.
+pub async fn ping(client: &Client) -> Result<u16> {
+    let response = client
+        .head(api_url(MODELS_PATH))
+        .send()
+        .await
+        .context("reach OpenRouter")?;
+    Ok(response.status().as_u16())
+}
+
+/// Checks a key with OpenRouter's key endpoint, which costs no credits.
+pub async fn check_key(client: &Client, api_key: &str) -> Result<KeyCheck> {
+    let start = Instant::now();
+    let response = client
+        .get(api_url(KEY_PATH))
+        .bearer_auth(api_key)
+        .send()
+        .await
+        .context("send OpenRouter key request")?;
+    let status = response.status();
+    let body = response.text().await.context("read key response body")?;
+    let duration_ms = start.elapsed().as_millis();
+
+    let reason = match status.as_u16() {
+        401 => Some(Msg::KeyNotRecognized),
+        403 => Some(Msg::KeyDisabled),
+        _ => None,
+    };
+    if let Some(reason) = reason {
+        info!(status = %status, duration_ms, "OpenRouter rejected API key");
+        return Ok(KeyCheck::Rejected(i18n::t(reason)));
+    }
+    if !status.is_success() {
+        error!(
+            status = %status,
+            duration_ms,
+            body_preview = %preview(&body, 400),
+            "OpenRouter key request failed"
+        );
+        return Err(status_error(status, preview(&body, 400)));
+    }
+
+    info!(status = %status, duration_ms, "OpenRouter key verified");
+    let parsed: KeyResponse = serde_json::from_str(&body).context("parse key response")?;
+    Ok(KeyCheck::Valid(parsed.data))
+}
+
+/// Fetches the key's limits and the account balance. The balance is optional: OpenRouter
+/// does not report it for every key.
+pub async fn account_status(client: &Client, api_key: &str) -> Result<AccountStatus> {
+    let key = match check_key(client, api_key).await? {
+        KeyCheck::Valid(key) => key,
+        KeyCheck::Rejected(reason) => return Err(anyhow!(reason)),
+    };
+    let credits_remaining = match fetch_credits(client, api_key).await {
+        Ok(credits) => Some(credits.total_credits - credits.total_usage),
+        Err(e) => {
+            warn!(error = %e, "Credits unavailable");
+            None
+        }
+    };
+    Ok(AccountStatus {
+        key,
+        credits_remaining,
+    })
+}
+
+async fn fetch_credits(client: &Client, api_key: &str) -> Result<Credits> {
+    let response = client
+        .get(api_url(CREDITS_PATH))
+        .bearer_auth(api_key)
+        .send()
+        .await
+        .context("send OpenRouter credits request")?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .context("read credits response body")?;
+    if !status.is_success() {
+        return Err(status_error(status, preview(&body, 200)));
+    }
+    let parsed: CreditsResponse = serde_json::from_str(&body).context("parse credits response")?;
+    debug!(
+        total_credits = parsed.data.total_credits,
+        total_usage = parsed.data.total_usage,
+        "OpenRouter credits received"
+    );
+    Ok(parsed.data)
+}
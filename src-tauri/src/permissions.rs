@@ -0,0 +1,48 @@
+/// macOS privacy permissions that global keyboard access depends on. Other platforms
+/// grant these implicitly.
+#[derive(Debug, Clone, Copy)]
+pub enum Permission {
+    /// Needed to send keystrokes (type-out mode).
+    Accessibility,
+    /// Needed to observe keys pressed in other apps (double tap).
+    InputMonitoring,
+}
+
+/// Whether `permission` is granted. When it is not, macOS shows its prompt pointing the
+/// user at System Settings, and the feature can be retried once it has been allowed.
+#[cfg(target_os = "macos")]
+pub fn ensure(permission: Permission) -> bool {
+    let granted = match permission {
+        Permission::Accessibility => {
+            macos_accessibility_client::accessibility::application_is_trusted_with_prompt()
+        }
+        // SAFETY: plain IOKit calls without pointers
+        Permission::InputMonitoring => unsafe {
+            hid::IOHIDCheckAccess(hid::REQUEST_LISTEN_EVENT) == hid::ACCESS_GRANTED
+                || hid::IOHIDRequestAccess(hid::REQUEST_LISTEN_EVENT)
+        },
+    };
+    if !granted {
+        tracing::warn!(?permission, "macOS permission not granted");
+    }
+    granted
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn ensure(_permission: Permission) -> bool {
+    true
+}
+
+#[cfg(target_os = "macos")]
+mod hid {
+    /// `kIOHIDRequestTypeListenEvent`
+    pub const REQUEST_LISTEN_EVENT: u32 = 1;
+    /// `kIOHIDAccessTypeGranted`
+    pub const ACCESS_GRANTED: u32 = 0;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        pub fn IOHIDCheckAccess(request: u32) -> u32;
+        pub fn IOHIDRequestAccess(request: u32) -> bool;
+    }
+}
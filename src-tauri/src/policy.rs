@@ -0,0 +1,242 @@
+use crate::automodel;
+use crate::config::Config;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tracing::{error, info, warn};
+
+const FILE_NAME: &str = "policy.json";
+
+/// Settings an administrator fixes for everyone on the machine, read from `policy.json`
+/// in a system location (see `path`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    /// Config fields and the values they are locked to, e.g. `{"log_content": false}`.
+    pub settings: Map<String, Value>,
+    /// Models that may be used; empty allows any.
+    pub allowed_models: Vec<String>,
+}
+
+/// A config value the policy does not permit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The field is locked to another value.
+    Locked(String),
+    /// The field names a model outside `allowed_models`.
+    ModelNotAllowed { field: String, model: String },
+}
+
+impl Violation {
+    pub fn field(&self) -> &str {
+        match self {
+            Violation::Locked(field) | Violation::ModelNotAllowed { field, .. } => field,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Locked(field) => write!(f, "{} is locked by policy", field),
+            Violation::ModelNotAllowed { field, model } => {
+                write!(f, "{} is not an allowed model ({})", model, field)
+            }
+        }
+    }
+}
+
+/// The policy read by the last `reload`.
+static CURRENT: Mutex<Option<Arc<Policy>>> = Mutex::new(None);
+
+/// `%ProgramData%\ThirdSpace\policy.json` on Windows,
+/// `/Library/Application Support/ThirdSpace/policy.json` on macOS and
+/// `/etc/thirdspace/policy.json` elsewhere: places only administrators can write.
+pub fn path() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let dir = PathBuf::from(std::env::var_os("ProgramData")?).join("ThirdSpace");
+    #[cfg(target_os = "macos")]
+    let dir = PathBuf::from("/Library/Application Support/ThirdSpace");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let dir = PathBuf::from("/etc/thirdspace");
+    Some(dir.join(FILE_NAME))
+}
+
+/// Reads the policy file again; `config::load` calls this so a changed policy applies
+/// with the next config load. A missing file is an empty policy, and so is one that
+/// cannot be read, so a broken policy never keeps ThirdSpace from starting.
+pub fn reload() -> Arc<Policy> {
+    let policy = match read() {
+        Ok(policy) => policy,
+        Err(e) => {
+            error!(error = %e, "Ignoring unreadable policy.json");
+            Policy::default()
+        }
+    };
+    let policy = Arc::new(policy);
+    let mut current = CURRENT.lock().unwrap();
+    if current.as_deref() != Some(&policy) && !policy.is_empty() {
+        let locked: Vec<&str> = policy.settings.keys().map(String::as_str).collect();
+        info!(
+            locked = ?locked,
+            allowed_models = policy.allowed_models.len(),
+            "Policy applied"
+        );
+    }
+    *current = Some(Arc::clone(&policy));
+    policy
+}
+
+pub fn current() -> Arc<Policy> {
+    CURRENT.lock().unwrap().clone().unwrap_or_default()
+}
+
+fn read() -> Result<Policy> {
+    let Some(path) = path().filter(|path| path.exists()) else {
+        return Ok(Policy::default());
+    };
+    let data = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+    let mut policy: Policy = serde_json::from_str(&data).context("parse policy.json")?;
+    policy.drop_invalid_settings();
+    Ok(policy)
+}
+
+impl Policy {
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty() && self.allowed_models.is_empty()
+    }
+
+    /// Removes locked values that are not config fields or that the field cannot take,
+    /// so `enforce` can apply the rest in one go.
+    fn drop_invalid_settings(&mut self) {
+        let defaults = serde_json::to_value(Config::default()).unwrap_or_default();
+        self.settings.retain(|field, value| {
+            let mut config = defaults.clone();
+            let valid = field != "version" && config.get(field).is_some() && {
+                config[field.as_str()] = value.clone();
+                serde_json::from_value::<Config>(config).is_ok()
+            };
+            if !valid {
+                warn!(field = %field, "Ignoring invalid policy setting");
+            }
+            valid
+        });
+    }
+
+    /// Whether `model` may be used. `"auto"` always may, since `enforce` keeps the
+    /// models it picks from within the list.
+    pub fn allows_model(&self, model: &str) -> bool {
+        let model = model.trim();
+        self.allowed_models.is_empty()
+            || automodel::is_auto(model)
+            || self
+                .allowed_models
+                .iter()
+                .any(|allowed| allowed.trim() == model)
+    }
+
+    /// Sets locked fields to their policy values and replaces models the policy does not
+    /// allow: the main and auto models with the first allowed one, while favorites and
+    /// per-purpose overrides are dropped.
+    pub fn enforce(&self, config: &mut Config) {
+        if !self.settings.is_empty() {
+            if let Ok(Value::Object(mut map)) = serde_json::to_value(&*config) {
+                map.extend(self.settings.clone());
+                if let Ok(locked) = serde_json::from_value(Value::Object(map)) {
+                    *config = locked;
+                }
+            }
+        }
+        let Some(fallback) = self.allowed_models.first().map(|model| model.trim()) else {
+            return;
+        };
+        if !self.allows_model(&config.model) {
+            config.model = fallback.to_string();
+        }
+        for model in [
+            &mut config.auto_model.fast_model,
+            &mut config.auto_model.strong_model,
+        ] {
+            if !model.trim().is_empty() && !self.allows_model(model) {
+                *model = fallback.to_string();
+            }
+        }
+        config
+            .favorite_models
+            .retain(|model| self.allows_model(model));
+        let overrides = [&mut config.ocr_model, &mut config.transcription_model]
+            .into_iter()
+            .chain(config.actions.iter_mut().map(|action| &mut action.model))
+            .chain(config.app_rules.iter_mut().map(|rule| &mut rule.model));
+        for model in overrides {
+            if !model.trim().is_empty() && !self.allows_model(model) {
+                model.clear();
+            }
+        }
+    }
+
+    /// Every value in `config` the policy does not permit.
+    pub fn violations(&self, config: &Config) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        if !self.settings.is_empty() {
+            let document = serde_json::to_value(config).unwrap_or_default();
+            for (field, value) in &self.settings {
+                if document.get(field) != Some(value) {
+                    violations.push(Violation::Locked(field.clone()));
+                }
+            }
+        }
+        if self.allowed_models.is_empty() {
+            return violations;
+        }
+        let mut models: Vec<(String, &str)> = vec![
+            ("model".to_string(), config.model.as_str()),
+            (
+                "auto_model".to_string(),
+                config.auto_model.fast_model.as_str(),
+            ),
+            (
+                "auto_model".to_string(),
+                config.auto_model.strong_model.as_str(),
+            ),
+            ("ocr_model".to_string(), config.ocr_model.as_str()),
+            (
+                "transcription_model".to_string(),
+                config.transcription_model.as_str(),
+            ),
+        ];
+        models.extend(
+            config
+                .favorite_models
+                .iter()
+                .map(|model| ("favorite_models".to_string(), model.as_str())),
+        );
+        models.extend(
+            config
+                .actions
+                .iter()
+                .enumerate()
+                .map(|(idx, action)| (format!("actions[{}].model", idx), action.model.as_str())),
+        );
+        models.extend(
+            config
+                .app_rules
+                .iter()
+                .enumerate()
+                .map(|(idx, rule)| (format!("app_rules[{}].model", idx), rule.model.as_str())),
+        );
+        for (field, model) in models {
+            if !model.trim().is_empty() && !self.allows_model(model) {
+                violations.push(Violation::ModelNotAllowed {
+                    field,
+                    model: model.trim().to_string(),
+                });
+            }
+        }
+        violations
+    }
+}
@@ -0,0 +1,170 @@
+use crate::config::PostFormat;
+use regex::Regex;
+use std::sync::LazyLock;
+use tracing::debug;
+
+static HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^[ \t]*#{1,6}[ \t]+").expect("valid heading regex"));
+static EMPHASIS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\*\*([^*\n]+)\*\*|__([^_\n]+)__|`([^`\n]+)`").expect("valid emphasis regex")
+});
+static LIST_ITEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([-*•]|\d+[.)])\s").expect("valid list item regex"));
+
+/// Lead-ins models put before the translation, lowercase.
+const LEAD_INS: [&str; 7] = [
+    "here is",
+    "here's",
+    "sure",
+    "below is",
+    "the translation",
+    "translation",
+    "translated text",
+];
+/// Openings of trailing translator's notes, lowercase.
+const NOTES: [&str; 4] = ["note:", "notes:", "translator's note", "translation note"];
+
+/// Cleans up an extracted translation as `options` asks. `source` is the text that was
+/// translated; commentary and markdown it contains itself are kept.
+pub fn apply(options: &PostFormat, source: &str, translation: String) -> String {
+    let mut text = translation;
+    let before = text.len();
+    if options.trim_commentary {
+        text = trim_commentary(source, &text);
+    }
+    if options.strip_markdown && !has_markdown(source) {
+        text = strip_markdown(&text);
+    }
+    if options.plain_punctuation {
+        text = plain_punctuation(&text);
+    }
+    if options.collapse_line_breaks {
+        text = collapse_line_breaks(&text);
+    }
+    if text.len() != before {
+        debug!(
+            before_len = before,
+            after_len = text.len(),
+            "Translation post-formatted"
+        );
+    }
+    text
+}
+
+/// Drops a "Here is the translation:" line at the start and a translator's note paragraph
+/// at the end. Never leaves the translation empty.
+fn trim_commentary(source: &str, text: &str) -> String {
+    let mut text = text.trim();
+    if let Some((first, rest)) = text.split_once('\n') {
+        let first = first.trim().to_lowercase();
+        if first.ends_with(':')
+            && LEAD_INS.iter().any(|lead| first.starts_with(lead))
+            && !rest.trim().is_empty()
+        {
+            text = rest.trim_start();
+        }
+    }
+    if !source.to_lowercase().contains("note") {
+        if let Some((body, last)) = text.rsplit_once("\n\n") {
+            let last = last
+                .trim()
+                .trim_start_matches(['(', '*', '_', '['])
+                .to_lowercase();
+            if NOTES.iter().any(|note| last.starts_with(note)) && !body.trim().is_empty() {
+                text = body.trim_end();
+            }
+        }
+    }
+    text.to_string()
+}
+
+fn has_markdown(source: &str) -> bool {
+    source.contains("**")
+        || source.contains("__")
+        || source.contains('`')
+        || HEADING.is_match(source)
+}
+
+/// Removes code fences, heading markers, bold and inline code, keeping their text.
+fn strip_markdown(text: &str) -> String {
+    let unfenced: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .collect();
+    let text = HEADING.replace_all(&unfenced.join("\n"), "").into_owned();
+    EMPHASIS
+        .replace_all(&text, |caps: &regex::Captures| {
+            caps.iter()
+                .skip(1)
+                .flatten()
+                .next()
+                .map_or(String::new(), |inner| inner.as_str().to_string())
+        })
+        .into_owned()
+}
+
+/// Curly quotes, dashes and ellipses become their ASCII forms.
+fn plain_punctuation(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{2033}' => plain.push('"'),
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{2032}' => plain.push('\''),
+            '\u{2013}' | '\u{2014}' => plain.push('-'),
+            '\u{2026}' => plain.push_str("..."),
+            _ => plain.push(ch),
+        }
+    }
+    plain
+}
+
+/// Joins the lines of each paragraph. Blank lines and `%%` separators still split
+/// paragraphs, and list items stay on their own lines.
+fn collapse_line_breaks(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut paragraph: Vec<&str> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line == "%%" {
+            flush_paragraph(&mut out, &mut paragraph);
+            if line == "%%" {
+                out.push_str("%%\n");
+            } else if !out.is_empty() && !out.ends_with("\n\n") {
+                out.push('\n');
+            }
+            continue;
+        }
+        if LIST_ITEM.is_match(line) {
+            flush_paragraph(&mut out, &mut paragraph);
+        }
+        paragraph.push(line);
+    }
+    flush_paragraph(&mut out, &mut paragraph);
+    out.trim_end().to_string()
+}
+
+fn flush_paragraph(out: &mut String, paragraph: &mut Vec<&str>) {
+    let mut previous: Option<char> = None;
+    for line in paragraph.drain(..) {
+        if let Some(last) = previous {
+            // Chinese and Japanese do not put spaces between words
+            if !(is_cjk(last) && line.chars().next().is_some_and(is_cjk)) {
+                out.push(' ');
+            }
+        }
+        out.push_str(line);
+        previous = line.chars().last();
+    }
+    if previous.is_some() {
+        out.push('\n');
+    }
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(ch,
+        '\u{3000}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF00}'..='\u{FFEF}')
+}
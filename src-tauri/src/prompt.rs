@@ -1,20 +1,296 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
 pub const MARKER_START: &str = "<<<TRANSLATION>>>";
 pub const MARKER_END: &str = "<<<END_TRANSLATION>>>";
+pub const ROMANIZATION_START: &str = "<<<ROMANIZATION>>>";
+pub const ROMANIZATION_END: &str = "<<<END_ROMANIZATION>>>";
+pub const SUMMARY_START: &str = "<<<SUMMARY>>>";
+pub const SUMMARY_END: &str = "<<<END_SUMMARY>>>";
+pub const PROOFREAD_START: &str = "<<<PROOFREAD>>>";
+pub const PROOFREAD_END: &str = "<<<END_PROOFREAD>>>";
+pub const REWRITE_START: &str = "<<<REWRITE>>>";
+pub const REWRITE_END: &str = "<<<END_REWRITE>>>";
+pub const OUTPUT_START: &str = "<<<OUTPUT>>>";
+pub const OUTPUT_END: &str = "<<<END_OUTPUT>>>";
+pub const DICTIONARY_START: &str = "<<<DICTIONARY>>>";
+pub const DICTIONARY_END: &str = "<<<END_DICTIONARY>>>";
+pub const OCR_START: &str = "<<<TEXT>>>";
+pub const OCR_END: &str = "<<<END_TEXT>>>";
+pub const TRANSCRIPT_START: &str = "<<<TRANSCRIPT>>>";
+pub const TRANSCRIPT_END: &str = "<<<END_TRANSCRIPT>>>";
+/// Longest input, in characters, still treated as a word or short phrase.
+const DICTIONARY_MAX_CHARS: usize = 40;
+
+/// A translation plus its pronunciation guide (pinyin, romaji, transliteration).
+#[derive(Debug, Clone)]
+pub struct Romanized {
+    pub translation: String,
+    pub romanization: Option<String>,
+}
+
+/// A dictionary entry for a single word or short phrase.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictionaryEntry {
+    pub translation: String,
+    #[serde(default)]
+    pub part_of_speech: String,
+    #[serde(default)]
+    pub definitions: Vec<String>,
+    #[serde(default)]
+    pub examples: Vec<String>,
+}
+
+/// A chat prompt: the rules and output contract go in the system message, the text to
+/// work on in the user message.
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    pub system: String,
+    pub user: String,
+}
+
+impl Prompt {
+    fn new(system: String, user: &str) -> Self {
+        Prompt {
+            system,
+            user: user.to_string(),
+        }
+    }
+}
+
+/// An earlier message of the conversation and its translation.
+#[derive(Debug, Clone)]
+pub struct ContextPair {
+    pub source: String,
+    pub translation: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tone {
+    #[default]
+    Formal,
+    Concise,
+    #[serde(alias = "casual")]
+    Friendly,
+}
+
+impl Tone {
+    pub const ALL: [Tone; 3] = [Tone::Formal, Tone::Concise, Tone::Friendly];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Tone::Formal => "formal",
+            Tone::Concise => "concise",
+            Tone::Friendly => "friendly",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Tone> {
+        Tone::ALL.into_iter().find(|tone| tone.id() == id)
+    }
+
+    fn instruction(self) -> &'static str {
+        match self {
+            Tone::Formal => "Make the text more formal and professional: polite phrasing, no slang or contractions, complete sentences",
+            Tone::Concise => "Make the text more concise: remove filler, redundancy and hedging while keeping every fact and request",
+            Tone::Friendly => "Make the text warmer and more friendly: approachable, conversational phrasing without becoming unprofessional",
+        }
+    }
+
+    /// The register a translation in this tone should use.
+    fn translation_instruction(self) -> &'static str {
+        match self {
+            Tone::Formal => "Use a formal, professional register: polite forms of address, no slang or contractions",
+            Tone::Concise => "Keep the translation concise: short, direct phrasing that still carries every fact and request",
+            Tone::Friendly => "Use a friendly, casual register: conversational phrasing and informal forms of address where the language has them",
+        }
+    }
+}
 
-pub fn build_prompt(input: &str, target_lang: &str) -> String {
-    let base = format!(
-        "You are a professional {to} native translator who needs to fluently translate text into {to}.\n\n## Translation Rules\n1. Output only the translated content, wrapped by the required markers and nothing else\n2. The returned translation must maintain exactly the same number of paragraphs and format as the original text\n3. If the text contains HTML tags, consider where the tags should be placed in the translation while maintaining fluency\n4. For content that should not be translated (such as proper nouns, code, etc.), keep the original text.\n5. If input contains %%, use %% in your output, if input has no %%, don't use %% in your output\n\n## OUTPUT FORMAT:\n- **Single paragraph input** -> Output translation directly (no separators, no extra text)\n- **Multi-paragraph input** -> Use %% as paragraph separator between translations\n\n## Marking Requirement\nWrap the final translation between {start} and {end} on a single output. Output nothing outside the markers.\n\n## Examples\n### Multi-paragraph Input:\nParagraph A\n%%\nParagraph B\n%%\nParagraph C\n%%\nParagraph D\n\n### Multi-paragraph Output:\nTranslation A\n%%\nTranslation B\n%%\nTranslation C\n%%\nTranslation D\n\n### Single paragraph Input:\nSingle paragraph content\n\n### Single paragraph Output:\nDirect translation without separators\n",
+/// With `structured` the model is asked for a `{"translation": ...}` JSON object instead
+/// of marker-wrapped text; see `translation_schema`.
+/// `tone`, when set, asks for that register instead of mirroring the source.
+pub fn build_prompt(
+    input: &str,
+    target_lang: &str,
+    tone: Option<Tone>,
+    structured: bool,
+) -> Prompt {
+    Prompt::new(
+        translation_instructions(target_lang, tone, &translation_delivery(structured)),
+        input,
+    )
+}
+
+/// Adds earlier messages of the conversation to the system prompt, so names, pronouns and
+/// terminology stay consistent across pieces translated one at a time.
+pub fn with_context(mut prompt: Prompt, context: &[ContextPair]) -> Prompt {
+    if context.is_empty() {
+        return prompt;
+    }
+    prompt.system.push_str("\n## Conversation So Far\nEarlier messages of the same conversation and their translations, oldest first. Use them only to keep names, pronouns and terminology consistent; do not translate or repeat them.\n");
+    for pair in context {
+        prompt.system.push_str(&format!(
+            "\nOriginal: {}\nTranslation: {}\n",
+            pair.source.trim(),
+            pair.translation.trim()
+        ));
+    }
+    prompt
+}
+
+fn translation_instructions(target_lang: &str, tone: Option<Tone>, delivery: &str) -> String {
+    format!(
+        "You are a professional {to} native translator who needs to fluently translate text into {to}.\n\n## Translation Rules\n1. Output only what the output requirements below ask for, with no explanations or commentary\n2. The returned translation must maintain exactly the same number of paragraphs and format as the original text\n3. If the text contains HTML tags, consider where the tags should be placed in the translation while maintaining fluency\n4. For content that should not be translated (such as proper nouns, code, etc.), keep the original text. Placeholders such as ⟦0⟧ stand for code, links, emails, template variables or markup: copy each one unchanged, exactly once, to the matching place in the translation.\n5. If input contains %%, use %% in your output, if input has no %%, don't use %% in your output\n\n{tone}## OUTPUT FORMAT:\n- **Single paragraph input** -> Output translation directly (no separators, no extra text)\n- **Multi-paragraph input** -> Use %% as paragraph separator between translations\n\n{delivery}\n\n## Examples\n### Multi-paragraph Input:\nParagraph A\n%%\nParagraph B\n%%\nParagraph C\n%%\nParagraph D\n\n### Multi-paragraph Output:\nTranslation A\n%%\nTranslation B\n%%\nTranslation C\n%%\nTranslation D\n\n### Single paragraph Input:\nSingle paragraph content\n\n### Single paragraph Output:\nDirect translation without separators\n",
         to = target_lang,
+        tone = tone
+            .map(|tone| format!("## Tone\n{}\n\n", tone.translation_instruction()))
+            .unwrap_or_default(),
+        delivery = delivery,
+    )
+}
+
+/// The output section of a translation prompt: marker-wrapped text, or the JSON object
+/// that `translation_schema` enforces.
+fn translation_delivery(structured: bool) -> String {
+    if structured {
+        "## Output Requirement\nReturn a single JSON object {\"translation\": \"...\"} whose translation field holds the complete translation, keeping line breaks and %% separators inside the string. Output nothing else.".to_string()
+    } else {
+        format!(
+            "## Marking Requirement\nWrap the final translation between {start} and {end} on a single output. Output nothing outside the markers.",
+            start = MARKER_START,
+            end = MARKER_END,
+        )
+    }
+}
+
+/// JSON schema sent as `response_format` for structured translations.
+pub fn translation_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "translation": {
+                "type": "string",
+                "description": "The complete translation"
+            }
+        },
+        "required": ["translation"],
+        "additionalProperties": false
+    })
+}
+
+/// Reads the translation out of a structured response.
+pub fn extract_json_translation(content: &str) -> Option<String> {
+    #[derive(Deserialize)]
+    struct Structured {
+        translation: String,
+    }
+
+    let structured: Structured = serde_json::from_str(strip_code_fence(content)).ok()?;
+    let translation = structured.translation.trim();
+    if translation.is_empty() {
+        None
+    } else {
+        Some(translation.to_string())
+    }
+}
+
+pub fn build_romanization_prompt(input: &str, target_lang: &str, tone: Option<Tone>) -> Prompt {
+    // The romanization gets its own marker pair, so the delivery rule names both
+    let delivery = format!(
+        "## Marking Requirement\nWrap the final translation between {start} and {end}, then the romanization described below between {roman_start} and {roman_end}. Output nothing outside these two marker pairs.",
         start = MARKER_START,
         end = MARKER_END,
+        roman_start = ROMANIZATION_START,
+        roman_end = ROMANIZATION_END,
+    );
+    let base = translation_instructions(target_lang, tone, &delivery);
+    let system = format!(
+        "{base}\n## Romanization Requirement\nAfter the translation, write a romanization of the translation between {start} and {end}: pinyin with tone marks for Chinese, Hepburn romaji for Japanese, Revised Romanization for Korean, and a standard transliteration for other non-Latin scripts. If the translation is already in Latin script, romanize the original text instead. Keep the same paragraphs and %% separators as the translation.\n",
+        start = ROMANIZATION_START,
+        end = ROMANIZATION_END,
+    );
+    Prompt::new(system, input)
+}
+
+/// Whether `input` is short enough to be looked up instead of translated.
+pub fn is_dictionary_candidate(input: &str, max_words: usize) -> bool {
+    let input = input.trim();
+    !input.is_empty()
+        && !input.contains('\n')
+        && input.chars().count() <= DICTIONARY_MAX_CHARS
+        && input.split_whitespace().count() <= max_words
+}
+
+pub fn build_dictionary_prompt(input: &str, target_lang: &str) -> Prompt {
+    let system = format!(
+        "You are a bilingual lexicographer writing dictionary entries for {to} speakers.\n\n## Task\nLook up the word or short phrase from the input and describe it in {to}.\n\n## Output Format\nReturn a single JSON object between {start} and {end}, with these fields:\n- \"translation\": the most common {to} equivalent (just the word or phrase)\n- \"part_of_speech\": part of speech, in {to}\n- \"definitions\": up to 3 short definitions, in {to}\n- \"examples\": up to 2 example sentences in the original language, each followed by \" — \" and its {to} translation\n\nIf the input is already in {to}, translate it into English instead and keep the explanations in {to}. Output nothing outside the markers.",
+        to = target_lang,
+        start = DICTIONARY_START,
+        end = DICTIONARY_END,
     );
+    Prompt::new(system, input)
+}
+
+pub fn extract_dictionary_entry(content: &str) -> Option<DictionaryEntry> {
+    let section = extract_section(content, DICTIONARY_START, DICTIONARY_END)?;
+    let entry: DictionaryEntry = serde_json::from_str(strip_code_fence(&section)).ok()?;
+    if entry.translation.trim().is_empty() {
+        None
+    } else {
+        Some(entry)
+    }
+}
+
+/// Added to the system prompt when personal data was swapped for tokens.
+pub fn redaction_note() -> &'static str {
+    "\n\n## Redacted Data\nTokens such as ⟪0⟫ stand for personal data that was removed before sending. Copy each one unchanged to the matching place in the output."
+}
 
-    format!("{base}\n\n### Input\n{input}")
+/// Follow-up message for a response that came back without translation markers.
+pub fn marker_correction() -> String {
+    format!(
+        "Your reply did not contain the required markers. Re-emit only the translation between {MARKER_START} and {MARKER_END}, with nothing outside the markers."
+    )
+}
+
+/// Best-effort translation from a response with missing or unbalanced markers: the text
+/// after a lone start marker or before a lone end marker, otherwise the whole reply.
+pub fn salvage_translation(content: &str) -> Option<String> {
+    let text = match (content.find(MARKER_START), content.find(MARKER_END)) {
+        (Some(start), None) => &content[start + MARKER_START.len()..],
+        (None, Some(end)) => &content[..end],
+        _ => content,
+    };
+    let text = strip_code_fence(text)
+        .replace(MARKER_START, "")
+        .replace(MARKER_END, "");
+    let text = text.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+/// Models sometimes fence JSON even when told not to.
+fn strip_code_fence(content: &str) -> &str {
+    content
+        .trim()
+        .trim_start_matches("```json")
+        .trim_start_matches("```")
+        .trim_end_matches("```")
+        .trim()
 }
 
 pub fn extract_translation(content: &str) -> Option<String> {
-    let start = content.find(MARKER_START)? + MARKER_START.len();
-    let end = content[start..].find(MARKER_END)? + start;
+    extract_section(content, MARKER_START, MARKER_END)
+}
+
+fn extract_section(content: &str, marker_start: &str, marker_end: &str) -> Option<String> {
+    let start = content.find(marker_start)? + marker_start.len();
+    let end = content[start..].find(marker_end)? + start;
     let extracted = content[start..end].trim();
     if extracted.is_empty() {
         None
@@ -22,3 +298,198 @@ pub fn extract_translation(content: &str) -> Option<String> {
         Some(extracted.to_string())
     }
 }
+
+pub fn extract_romanization(content: &str) -> Option<String> {
+    extract_section(content, ROMANIZATION_START, ROMANIZATION_END)
+}
+
+pub fn build_summary_prompt(input: &str, target_lang: &str) -> Prompt {
+    let system = format!(
+        "You are an expert editor who writes clear, faithful summaries in {to}.\n\n## Summary Rules\n1. Write the summary in {to}, whatever the language of the input\n2. Capture the key points, decisions, numbers and action items; do not add information that is not in the input\n3. Keep it short: one paragraph for short inputs, or a few bullet points for long or structured inputs\n4. Keep proper nouns, code and URLs unchanged\n\n## Marking Requirement\nWrap the summary between {start} and {end}. Output nothing outside the markers.",
+        to = target_lang,
+        start = SUMMARY_START,
+        end = SUMMARY_END,
+    );
+    Prompt::new(system, input)
+}
+
+pub fn extract_summary(content: &str) -> Option<String> {
+    extract_section(content, SUMMARY_START, SUMMARY_END)
+}
+
+pub fn build_proofread_prompt(input: &str) -> Prompt {
+    let system = format!(
+        "You are a meticulous copy editor.\n\n## Proofreading Rules\n1. Correct spelling, grammar, punctuation and obvious typos in the input\n2. Keep the original language; never translate\n3. Keep the author's wording, tone and meaning; do not rephrase sentences that are already correct\n4. Keep paragraphs, line breaks, lists, %% separators, code, URLs and HTML tags exactly as they are\n5. If nothing needs fixing, return the input unchanged\n\n## Marking Requirement\nWrap the corrected text between {start} and {end}. Output nothing outside the markers.",
+        start = PROOFREAD_START,
+        end = PROOFREAD_END,
+    );
+    Prompt::new(system, input)
+}
+
+pub fn extract_proofread(content: &str) -> Option<String> {
+    extract_section(content, PROOFREAD_START, PROOFREAD_END)
+}
+
+pub fn build_rewrite_prompt(input: &str, tone: Tone) -> Prompt {
+    let system = format!(
+        "You are a skilled writing assistant.\n\n## Rewrite Goal\n{goal}.\n\n## Rewrite Rules\n1. Keep the original language; never translate\n2. Keep the meaning, facts, names, numbers, code and URLs unchanged\n3. Keep the paragraph structure and %% separators; keep lists as lists\n4. Return only the rewritten text, without explanations or alternatives\n\n## Marking Requirement\nWrap the rewritten text between {start} and {end}. Output nothing outside the markers.",
+        goal = tone.instruction(),
+        start = REWRITE_START,
+        end = REWRITE_END,
+    );
+    Prompt::new(system, input)
+}
+
+pub fn extract_rewrite(content: &str) -> Option<String> {
+    extract_section(content, REWRITE_START, REWRITE_END)
+}
+
+/// Asks a vision model to transcribe the text in an attached screenshot. The image
+/// travels next to the user message.
+pub fn build_ocr_prompt() -> Prompt {
+    let system = format!(
+        "You are a precise OCR engine.\n\n## Transcription Rules\n1. Transcribe all readable text in the image exactly as written, in its original language; never translate\n2. Keep the reading order, paragraphs and line breaks; join lines that were only wrapped by the layout\n3. Ignore icons, decorations and UI chrome that carry no text\n4. Do not describe the image or add commentary\n\n## Marking Requirement\nWrap the transcribed text between {start} and {end}. Output nothing outside the markers. If the image contains no text, output the markers with nothing between them.",
+        start = OCR_START,
+        end = OCR_END,
+    );
+    Prompt::new(system, "Transcribe the text in this screenshot.")
+}
+
+pub fn extract_ocr(content: &str) -> Option<String> {
+    extract_section(content, OCR_START, OCR_END)
+}
+
+/// Asks an audio-capable model to transcribe an attached voice recording.
+pub fn build_transcription_prompt() -> Prompt {
+    let system = format!(
+        "You are a precise speech-to-text engine.\n\n## Transcription Rules\n1. Transcribe the speech in the recording word for word, in the language spoken; never translate\n2. Add punctuation and sentence breaks; leave out filler sounds, false starts and background noise\n3. Do not answer, summarize or comment on what is said\n\n## Marking Requirement\nWrap the transcript between {start} and {end}. Output nothing outside the markers. If there is no intelligible speech, output the markers with nothing between them.",
+        start = TRANSCRIPT_START,
+        end = TRANSCRIPT_END,
+    );
+    Prompt::new(system, "Transcribe this recording.")
+}
+
+pub fn extract_transcript(content: &str) -> Option<String> {
+    extract_section(content, TRANSCRIPT_START, TRANSCRIPT_END)
+}
+
+/// Fills a user-defined prompt template and adds the output marker contract to the
+/// system message. A template with an `{input}` placeholder becomes the user message;
+/// otherwise it is the instruction and the input is sent on its own.
+pub fn build_custom_prompt(template: &str, input: &str, target_lang: &str) -> Prompt {
+    let body = template.replace("{target_language}", target_lang);
+    let marking = format!(
+        "## Marking Requirement\nWrap your final output between {start} and {end}. Output nothing outside the markers.",
+        start = OUTPUT_START,
+        end = OUTPUT_END,
+    );
+    if body.contains("{input}") {
+        Prompt::new(marking, &body.replace("{input}", input))
+    } else {
+        Prompt::new(format!("{body}\n\n{marking}"), input)
+    }
+}
+
+pub fn extract_output(content: &str) -> Option<String> {
+    extract_section(content, OUTPUT_START, OUTPUT_END)
+}
+
+pub fn build_commit_prompt(input: &str, target_lang: &str, structured: bool) -> Prompt {
+    let system = format!(
+        "You are a senior software engineer who writes commit messages and pull request descriptions in fluent {to}.\n\n## Translation Rules\n1. Translate the commit message or pull request text into {to}\n2. Keep conventional-commit prefixes such as `feat:`, `fix(parser):` or `refactor!:` exactly as written, including type, scope and punctuation\n3. Keep issue and pull request references (#123, GH-42, owner/repo#7), ticket IDs (PROJ-123), commit hashes and URLs unchanged\n4. Keep everything inside backticks and fenced code blocks unchanged. Placeholders such as ⟦0⟧ stand for code, links or template variables: copy each one unchanged, exactly once, to the matching place in the translation\n5. Keep git trailers such as `Signed-off-by:`, `Co-authored-by:`, `Fixes:` and `Refs:` unchanged, including their keys\n6. Preserve the subject line, blank lines, bullet lists and paragraph structure; keep the subject short and in the imperative mood\n\n{delivery}\n",
+        to = target_lang,
+        delivery = translation_delivery(structured),
+    );
+    Prompt::new(system, input)
+}
+
+/// Tokens of a commit message that must survive translation verbatim:
+/// the conventional-commit prefix, issue/PR references, ticket IDs and code spans.
+pub fn commit_references(text: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+
+    if let Some(prefix) = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .and_then(conventional_prefix)
+    {
+        refs.push(prefix.to_string());
+    }
+
+    for word in text.split_whitespace() {
+        let word = word.trim_matches(|c: char| "()[]{}<>,.;:!?'\"".contains(c));
+        if (is_issue_reference(word) || is_ticket_id(word)) && !refs.iter().any(|r| r == word) {
+            refs.push(word.to_string());
+        }
+    }
+
+    // Odd segments between backticks are code spans (or fenced block bodies).
+    for (idx, segment) in text.split('`').enumerate() {
+        let segment = segment.trim();
+        if idx % 2 == 1 && !segment.is_empty() && !refs.iter().any(|r| r == segment) {
+            refs.push(segment.to_string());
+        }
+    }
+
+    refs
+}
+
+/// References from `source` that no longer appear in `translated`.
+pub fn missing_commit_references(source: &str, translated: &str) -> Vec<String> {
+    commit_references(source)
+        .into_iter()
+        .filter(|reference| !translated.contains(reference.as_str()))
+        .collect()
+}
+
+/// Conventional-commit types, as in the spec and commitlint's conventional config.
+const COMMIT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// `type(scope)!: ` at the start of a subject line, for a known type with an optional
+/// scope and breaking-change marker.
+fn conventional_prefix(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    let colon = line.find(": ")?;
+    let head = &line[..colon];
+    let head = head.strip_suffix('!').unwrap_or(head);
+    let kind = match head.split_once('(') {
+        Some((kind, scope)) => {
+            let scope = scope.strip_suffix(')')?;
+            if scope.is_empty() || scope.contains(['(', ')']) {
+                return None;
+            }
+            kind
+        }
+        None => head,
+    };
+    if !COMMIT_TYPES.contains(&kind) {
+        return None;
+    }
+    Some(&line[..=colon])
+}
+
+fn is_issue_reference(word: &str) -> bool {
+    // #123, owner/repo#123, GH-123
+    let number = match word.rfind('#') {
+        Some(hash) => &word[hash + 1..],
+        None => match word.strip_prefix("GH-") {
+            Some(number) => number,
+            None => return false,
+        },
+    };
+    !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+}
+
+fn is_ticket_id(word: &str) -> bool {
+    // PROJ-123 style keys: uppercase project key, dash, number
+    let Some((key, number)) = word.split_once('-') else {
+        return false;
+    };
+    key.len() >= 2
+        && key.starts_with(|c: char| c.is_ascii_uppercase())
+        && key.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        && !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+}
@@ -0,0 +1,144 @@
+use crate::clipboard::Input;
+use crate::i18n::{self, Msg};
+use crate::toast::show_toast;
+use crate::{openrouter, Action, AppState, Delivery};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{debug, info, warn};
+
+/// Inputs kept while offline; further ones are refused so the backlog stays reviewable.
+const QUEUE_LIMIT: usize = 20;
+/// How often a disabled queue re-reads the config to see if it was enabled.
+const IDLE_POLL: Duration = Duration::from_secs(60);
+
+struct Queued {
+    action: Action,
+    input: Input,
+    target_language: Option<String>,
+}
+
+/// Clipboard snapshots whose requests could not reach OpenRouter, oldest first.
+#[derive(Default)]
+pub struct OfflineQueue {
+    items: Mutex<VecDeque<Queued>>,
+    /// Set while queued items are being replayed, so a failed replay is not queued twice.
+    draining: AtomicBool,
+}
+
+impl OfflineQueue {
+    /// Queues an input for later. Returns false when the queue is full or is being
+    /// replayed.
+    pub fn push(&self, action: Action, input: Input, target_language: Option<String>) -> bool {
+        if self.draining.load(Ordering::Relaxed) {
+            return false;
+        }
+        let mut items = self.items.lock().unwrap();
+        if items.len() >= QUEUE_LIMIT {
+            return false;
+        }
+        items.push_back(Queued {
+            action,
+            input,
+            target_language,
+        });
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.lock().unwrap().is_empty()
+    }
+}
+
+/// Probes OpenRouter every `offline_probe_secs` while items are queued and replays
+/// them in order once it answers. Each result opens in the result window.
+pub fn watch(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let state = app.state::<AppState>();
+            let (enabled, probe_secs) = {
+                let config = state.config();
+                (config.offline_queue, config.offline_probe_secs)
+            };
+            if !enabled || probe_secs == 0 {
+                tokio::time::sleep(IDLE_POLL).await;
+                continue;
+            }
+            tokio::time::sleep(Duration::from_secs(probe_secs)).await;
+            if state.offline_queue.is_empty() {
+                continue;
+            }
+
+            match openrouter::ping(&state.http()).await {
+                Ok(_) => drain(&app).await,
+                Err(e) => debug!(
+                    error = %e,
+                    queued = state.offline_queue.len(),
+                    "OpenRouter still unreachable"
+                ),
+            }
+        }
+    });
+}
+
+async fn drain(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let queue = &state.offline_queue;
+    queue.draining.store(true, Ordering::Relaxed);
+    info!(
+        queued = queue.len(),
+        "OpenRouter reachable, replaying queue"
+    );
+
+    loop {
+        if !state.requests.is_empty() {
+            break;
+        }
+        let Some(item) = queue.items.lock().unwrap().pop_front() else {
+            break;
+        };
+        let remaining = queue.len();
+        let result = crate::execute_action(
+            app.clone(),
+            app.state::<AppState>(),
+            item.action,
+            false,
+            item.input.clone(),
+            item.target_language.clone(),
+            // The user has copied other things since, so the clipboard is left alone
+            Delivery::Window,
+        )
+        .await;
+        match result {
+            Ok(()) => {
+                info!(
+                    action = item.action.name(),
+                    remaining, "Queued input translated"
+                );
+                let title = if remaining == 0 {
+                    i18n::t(Msg::QueueDone)
+                } else {
+                    i18n::t(Msg::QueueLeft(remaining))
+                };
+                show_toast(app, "success", &title);
+            }
+            Err(e) => {
+                // Only an outage keeps the item; other failures were already reported
+                // by `execute_action` and would fail again
+                if openrouter::ping(&state.http()).await.is_err() {
+                    warn!(error = %e, remaining, "Connection lost again, keeping queued input");
+                    queue.items.lock().unwrap().push_front(item);
+                    break;
+                }
+                warn!(error = %e, remaining, "Queued input failed, dropping it");
+            }
+        }
+    }
+    queue.draining.store(false, Ordering::Relaxed);
+}
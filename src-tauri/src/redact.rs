@@ -0,0 +1,96 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+const TOKEN_OPEN: char = '⟪';
+const TOKEN_CLOSE: char = '⟫';
+
+/// Emails, and digit runs that may be phone or card numbers; `is_personal_number` decides.
+static PERSONAL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)+|\+?\(?\d[\d \t().-]{5,}\d")
+        .expect("valid personal data regex")
+});
+static DATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\d{4}[-./]\d{1,2}[-./]\d{1,2}$|^\d{1,2}[-./]\d{1,2}[-./]\d{2,4}$")
+        .expect("valid date regex")
+});
+
+/// Personal data swapped out for `⟪n⟫` tokens across every message of one request. The
+/// same value always gets the same token, so the model sees consistent references.
+#[derive(Debug, Default)]
+pub struct Redactions {
+    values: Vec<String>,
+}
+
+impl Redactions {
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// `text` with emails, phone numbers and card numbers replaced by tokens. Text that
+    /// already contains the token brackets is left alone.
+    pub fn redact(&mut self, text: &str) -> String {
+        if text.contains(TOKEN_OPEN) || text.contains(TOKEN_CLOSE) {
+            return text.to_string();
+        }
+        PERSONAL
+            .replace_all(text, |caps: &regex::Captures| {
+                let found = &caps[0];
+                if !found.contains('@') && !is_personal_number(found) {
+                    return found.to_string();
+                }
+                let idx = match self.values.iter().position(|value| value == found) {
+                    Some(idx) => idx,
+                    None => {
+                        self.values.push(found.to_string());
+                        self.values.len() - 1
+                    }
+                };
+                token(idx)
+            })
+            .into_owned()
+    }
+
+    /// Puts the redacted values back into the model output.
+    pub fn restore(&self, output: &str) -> String {
+        self.values
+            .iter()
+            .enumerate()
+            .fold(output.to_string(), |restored, (idx, value)| {
+                restored.replace(&token(idx), value)
+            })
+    }
+}
+
+fn token(idx: usize) -> String {
+    format!("{TOKEN_OPEN}{idx}{TOKEN_CLOSE}")
+}
+
+/// Card numbers (13 to 19 digits passing the Luhn check) and phone numbers (7 to 15
+/// digits); dates and decimals are not redacted.
+fn is_personal_number(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|ch| ch.to_digit(10)).collect();
+    if (13..=19).contains(&digits.len()) && luhn(&digits) {
+        return true;
+    }
+    let separators = candidate.chars().filter(|ch| !ch.is_ascii_digit()).count();
+    let decimal = candidate.matches('.').count() == 1 && separators == 1;
+    (7..=15).contains(&digits.len()) && !decimal && !DATE.is_match(candidate)
+}
+
+fn luhn(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| match (i % 2, digit * 2) {
+            (1, doubled) if doubled > 9 => doubled - 9,
+            (1, doubled) => doubled,
+            _ => digit,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
@@ -0,0 +1,89 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Upper bound for `max_concurrent_requests`.
+pub const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// A request `execute_action` is waiting on, as reported by `get_active_requests`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveRequest {
+    pub request_id: u64,
+    pub action: &'static str,
+    pub model: String,
+    pub target_language: String,
+    pub input_len: usize,
+    pub elapsed_ms: u64,
+}
+
+struct Entry {
+    request: ActiveRequest,
+    started: Instant,
+}
+
+/// Requests in flight, keyed by request id, so several can run side by side up to a
+/// limit.
+#[derive(Default)]
+pub struct RequestRegistry {
+    active: Mutex<BTreeMap<u64, Entry>>,
+}
+
+/// Removes its request from the registry when dropped, however the request ends.
+pub struct RequestGuard<'a> {
+    registry: &'a RequestRegistry,
+    request_id: u64,
+}
+
+impl RequestRegistry {
+    pub fn len(&self) -> usize {
+        self.active.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.lock().unwrap().is_empty()
+    }
+
+    /// Registers `request` unless `limit` requests are already running.
+    pub fn begin(&self, limit: usize, request: ActiveRequest) -> Option<RequestGuard<'_>> {
+        let mut active = self.active.lock().unwrap();
+        if active.len() >= limit.max(1) {
+            return None;
+        }
+        let request_id = request.request_id;
+        active.insert(
+            request_id,
+            Entry {
+                request,
+                started: Instant::now(),
+            },
+        );
+        Some(RequestGuard {
+            registry: self,
+            request_id,
+        })
+    }
+
+    /// Running requests, oldest first.
+    pub fn list(&self) -> Vec<ActiveRequest> {
+        self.active
+            .lock()
+            .unwrap()
+            .values()
+            .map(|entry| ActiveRequest {
+                elapsed_ms: entry.started.elapsed().as_millis() as u64,
+                ..entry.request.clone()
+            })
+            .collect()
+    }
+}
+
+impl Drop for RequestGuard<'_> {
+    fn drop(&mut self) {
+        self.registry
+            .active
+            .lock()
+            .unwrap()
+            .remove(&self.request_id);
+    }
+}
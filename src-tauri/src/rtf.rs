@@ -0,0 +1,394 @@
+use crate::mask::{self, Masked};
+
+/// Groups whose content is never visible text (font tables, metadata, pictures, ...).
+const HIDDEN_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "listtable",
+    "listoverridetable",
+    "rsidtbl",
+    "generator",
+    "xmlnstbl",
+    "latentstyles",
+    "themedata",
+    "colorschememapping",
+    "datastore",
+    "filetbl",
+    "revtbl",
+    "header",
+    "headerl",
+    "headerr",
+    "headerf",
+    "footer",
+    "footerl",
+    "footerr",
+    "footerf",
+    "nonshppict",
+    "fldinst",
+];
+
+/// Splits an RTF document into translatable text and placeholder-protected control
+/// sequences. Unicode and code page escapes are decoded so the model sees real text;
+/// paragraph breaks are mirrored as newlines.
+pub fn mask(rtf: &str) -> Masked {
+    let mut masked = Masked::default();
+    if !mask::can_mask(rtf) {
+        masked.push_text(rtf);
+        return masked;
+    }
+
+    let bytes = rtf.as_bytes();
+    let mut pos = 0;
+    // Fallback characters to skip after each \uN, per \ucN
+    let mut unicode_skip = 1;
+    let mut decode_hex = true;
+    let mut high_surrogate = None;
+    let mut text = String::new();
+
+    while pos < rtf.len() {
+        if high_surrogate.is_some()
+            && !matches!(bytes[pos], b'\r' | b'\n')
+            && !starts_unicode_escape(bytes, pos)
+        {
+            // Only the low half may follow; anything else leaves the high half unpaired
+            high_surrogate = None;
+            text.push(char::REPLACEMENT_CHARACTER);
+        }
+        match bytes[pos] {
+            b'{' => {
+                flush(&mut masked, &mut text);
+                let end = hidden_group_end(rtf, pos).unwrap_or(pos + 1);
+                masked.push_span(&rtf[pos..end]);
+                pos = end;
+            }
+            b'}' => {
+                flush(&mut masked, &mut text);
+                masked.push_span("}");
+                pos += 1;
+            }
+            b'\r' | b'\n' => pos += 1,
+            b'\\' => {
+                let start = pos;
+                let (token, end) = control(rtf, pos);
+                pos = end;
+                match token {
+                    Control::Word { word, param, raw } => match word {
+                        "u" => {
+                            // Parameters are signed 16-bit; code points above U+7FFF come negative
+                            let unit = param.unwrap_or(0) as u16;
+                            push_utf16(&mut text, &mut high_surrogate, unit);
+                            pos = skip_fallback(rtf, pos, unicode_skip);
+                        }
+                        "uc" => {
+                            unicode_skip = param.unwrap_or(1).max(0) as usize;
+                            flush(&mut masked, &mut text);
+                            masked.push_span(&raw);
+                        }
+                        "ansicpg" => {
+                            decode_hex = param == Some(1252);
+                            flush(&mut masked, &mut text);
+                            masked.push_span(&raw);
+                        }
+                        "par" | "line" | "sect" | "page" | "row" => {
+                            flush(&mut masked, &mut text);
+                            masked.push_span(&raw);
+                            masked.push_text("\n");
+                        }
+                        "tab" => text.push('\t'),
+                        "emdash" => text.push('—'),
+                        "endash" => text.push('–'),
+                        "lquote" => text.push('‘'),
+                        "rquote" => text.push('’'),
+                        "ldblquote" => text.push('“'),
+                        "rdblquote" => text.push('”'),
+                        "bullet" => text.push('•'),
+                        _ => {
+                            flush(&mut masked, &mut text);
+                            masked.push_span(&raw);
+                        }
+                    },
+                    Control::Hex(value) if decode_hex => text.push(windows_1252(value)),
+                    Control::Literal(ch) => text.push(ch),
+                    Control::Skip => {}
+                    Control::Hex(_) | Control::Symbol => {
+                        flush(&mut masked, &mut text);
+                        masked.push_span(&rtf[start..pos]);
+                    }
+                }
+            }
+            _ => {
+                let end = rtf[pos..]
+                    .find(['\\', '{', '}', '\r', '\n'])
+                    .map_or(rtf.len(), |offset| pos + offset);
+                text.push_str(&rtf[pos..end]);
+                pos = end;
+            }
+        }
+    }
+    if high_surrogate.is_some() {
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+    flush(&mut masked, &mut text);
+    masked
+}
+
+/// Rebuilds the RTF document around the translated text.
+pub fn restore(masked: &Masked, output: &str) -> String {
+    masked.restore_with(output, escape)
+}
+
+/// Encodes plain text for an RTF body: braces and backslashes escaped, non-ASCII as `\uN?`.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            '\t' => escaped.push_str("\\tab "),
+            '\n' => escaped.push('\n'),
+            ch if ch.is_ascii() => escaped.push(ch),
+            ch => {
+                let mut units = [0u16; 2];
+                for unit in ch.encode_utf16(&mut units) {
+                    escaped.push_str(&format!("\\u{}?", *unit as i16));
+                }
+            }
+        }
+    }
+    escaped
+}
+
+fn flush(masked: &mut Masked, text: &mut String) {
+    if !text.is_empty() {
+        masked.push_text(text);
+        text.clear();
+    }
+}
+
+enum Control<'a> {
+    /// A control word, with its trailing delimiter normalized to a single space so
+    /// translated text can never run into it.
+    Word {
+        word: &'a str,
+        param: Option<i32>,
+        raw: String,
+    },
+    Hex(u8),
+    Literal(char),
+    Symbol,
+    Skip,
+}
+
+/// Parses the control sequence starting at the backslash at `pos`.
+fn control(rtf: &str, pos: usize) -> (Control<'_>, usize) {
+    let bytes = rtf.as_bytes();
+    let start = pos + 1;
+    let Some(&next) = bytes.get(start) else {
+        return (Control::Symbol, rtf.len());
+    };
+    if next.is_ascii_alphabetic() {
+        let word_end = scan(bytes, start, |b| b.is_ascii_alphabetic());
+        let param_start = if bytes.get(word_end) == Some(&b'-') {
+            word_end + 1
+        } else {
+            word_end
+        };
+        let param_end = scan(bytes, param_start, |b| b.is_ascii_digit());
+        let (param, mut end) = if param_end > param_start {
+            (rtf[word_end..param_end].parse().ok(), param_end)
+        } else {
+            (None, word_end)
+        };
+        if bytes.get(end) == Some(&b' ') {
+            end += 1;
+        }
+        let raw = format!("{} ", &rtf[pos..end].trim_end());
+        let word = &rtf[start..word_end];
+        return (Control::Word { word, param, raw }, end);
+    }
+    match next {
+        b'\'' => {
+            let value = rtf
+                .get(start + 1..start + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            match value {
+                Some(value) => (Control::Hex(value), start + 3),
+                None => (Control::Symbol, start + 1),
+            }
+        }
+        b'\\' | b'{' | b'}' => (Control::Literal(next as char), start + 1),
+        // A backslash before a line break is an old spelling of \par
+        b'\n' | b'\r' => {
+            let raw = "\\par ".to_string();
+            let word = "par";
+            (
+                Control::Word {
+                    word,
+                    param: None,
+                    raw,
+                },
+                start + 1,
+            )
+        }
+        b'~' => (Control::Literal('\u{a0}'), start + 1),
+        b'_' => (Control::Literal('\u{2011}'), start + 1),
+        // Optional hyphens only matter for line breaking
+        b'-' => (Control::Skip, start + 1),
+        _ => {
+            let len = rtf[start..].chars().next().map_or(1, char::len_utf8);
+            (Control::Symbol, start + len)
+        }
+    }
+}
+
+fn scan(bytes: &[u8], from: usize, accept: impl Fn(u8) -> bool) -> usize {
+    bytes[from..]
+        .iter()
+        .position(|&b| !accept(b))
+        .map_or(bytes.len(), |offset| from + offset)
+}
+
+/// End of the group opening at `pos` when it is a hidden destination (`{\*...}` or one of
+/// `HIDDEN_DESTINATIONS`), so the whole group becomes a single span.
+fn hidden_group_end(rtf: &str, pos: usize) -> Option<usize> {
+    let bytes = rtf.as_bytes();
+    let inner = rtf[pos + 1..].trim_start();
+    let hidden = inner.starts_with("\\*")
+        || inner.strip_prefix('\\').is_some_and(|rest| {
+            let word_len = rest
+                .bytes()
+                .position(|b| !b.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            HIDDEN_DESTINATIONS.contains(&&rest[..word_len])
+        });
+    if !hidden {
+        return None;
+    }
+
+    let mut depth = 0usize;
+    let mut idx = pos;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' => idx += 1,
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx + 1);
+                }
+            }
+            _ => {}
+        }
+        idx += 1;
+    }
+    Some(rtf.len())
+}
+
+/// Skips the `count` fallback characters that follow a `\uN` escape.
+fn skip_fallback(rtf: &str, mut pos: usize, count: usize) -> usize {
+    let bytes = rtf.as_bytes();
+    for _ in 0..count {
+        match bytes.get(pos) {
+            Some(b'\\') if bytes.get(pos + 1) == Some(&b'\'') => pos += 4,
+            Some(b'\\') | Some(b'{') | Some(b'}') | None => break,
+            Some(_) => pos += rtf[pos..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    pos.min(rtf.len())
+}
+
+/// Whether a `\uN` escape starts at `pos`; `\uc` and words like `\ul` do not count.
+fn starts_unicode_escape(bytes: &[u8], pos: usize) -> bool {
+    bytes.get(pos) == Some(&b'\\')
+        && bytes.get(pos + 1) == Some(&b'u')
+        && bytes
+            .get(pos + 2)
+            .is_some_and(|&b| b.is_ascii_digit() || b == b'-')
+}
+
+/// Appends a UTF-16 code unit; surrogate pairs arrive as two consecutive `\u` escapes.
+fn push_utf16(text: &mut String, high_surrogate: &mut Option<u16>, unit: u16) {
+    if (0xD800..0xDC00).contains(&unit) {
+        if high_surrogate.replace(unit).is_some() {
+            text.push(char::REPLACEMENT_CHARACTER);
+        }
+        return;
+    }
+    let units = match high_surrogate.take() {
+        Some(high) => vec![high, unit],
+        None => vec![unit],
+    };
+    text.extend(char::decode_utf16(units).map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER)));
+}
+
+/// Decodes a `\'hh` escape from the Windows-1252 code page.
+fn windows_1252(byte: u8) -> char {
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž',
+        '\u{8f}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}',
+        'ž', 'Ÿ',
+    ];
+    match byte {
+        0x80..=0x9f => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The text the model would see, without placeholders.
+    fn visible(rtf: &str) -> String {
+        let masked = mask(rtf);
+        masked.strip(&masked.text)
+    }
+
+    #[test]
+    fn unicode_escape_skips_its_fallback() {
+        assert_eq!(visible(r"{\rtf1 caf\u233?s}"), "cafés");
+        assert_eq!(visible(r"{\rtf1 \uc2 caf\u233 e's}"), "cafés");
+        assert_eq!(visible(r"{\rtf1 \uc2 caf\u233\'e9\'27s}"), "cafés");
+    }
+
+    #[test]
+    fn surrogate_pair_becomes_one_character() {
+        assert_eq!(visible(r"{\rtf1 smile \u-10179?\u-8704?}"), "smile 😀");
+    }
+
+    #[test]
+    fn unpaired_high_surrogate_is_replaced_where_it_stood() {
+        assert_eq!(visible(r"{\rtf1 \u-10179?abc}"), "\u{FFFD}abc");
+        assert_eq!(visible(r"{\rtf1 \u-10179?\b x\u233?}"), "\u{FFFD}xé");
+        assert_eq!(visible(r"{\rtf1 x\u-10179?}"), "x\u{FFFD}");
+    }
+
+    #[test]
+    fn code_page_escapes_decode_as_windows_1252() {
+        assert_eq!(
+            visible(r"{\rtf1 \ansi \ansicpg1252 na\'efve \'93quoted\'94}"),
+            "naïve “quoted”"
+        );
+    }
+
+    #[test]
+    fn code_page_escapes_of_other_code_pages_are_kept() {
+        let rtf = r"{\rtf1 \ansi \ansicpg932 \'82\'a0}";
+        let masked = mask(rtf);
+        assert_eq!(masked.strip(&masked.text).trim(), "");
+        assert_eq!(restore(&masked, &masked.text), rtf);
+    }
+
+    #[test]
+    fn hidden_groups_are_not_translated() {
+        let rtf = r"{\rtf1 {\fonttbl {\f0 Arial;}}{\colortbl ;\red255\green0\blue0;}{\*\generator Word;}Hello}";
+        let masked = mask(rtf);
+        assert_eq!(masked.strip(&masked.text), "Hello");
+        assert_eq!(restore(&masked, &masked.text), rtf);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::config::{self, Config, Schedule};
+use crate::i18n::{self, Msg};
+use crate::toast::show_toast;
+use crate::{openrouter, updater, AppState, CachedModels};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, error, info, warn};
+
+/// How often each job re-reads its interval, so edits apply within this time.
+const TICK: Duration = Duration::from_secs(30);
+/// How often the models cache is checked against its TTL.
+const CACHE_SWEEP: Duration = Duration::from_secs(10 * 60);
+/// How often the weekly version check looks at when it last ran.
+const VERSION_CHECK_POLL: Duration = Duration::from_secs(24 * 60 * 60);
+const CONFIG_BACKUPS_KEPT: usize = 7;
+
+/// Set once the low-credits toast was shown, until credits are topped up again.
+static LOW_CREDITS_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// Background maintenance jobs. Each runs on its own timer whose interval is
+/// re-read from the config every `TICK`, so edits apply without a restart.
+#[derive(Debug, Clone, Copy)]
+enum Job {
+    LogCleanup,
+    ModelsCacheExpiry,
+    ModelsRefresh,
+    ConfigBackup,
+    CreditsCheck,
+    VersionCheck,
+}
+
+const JOBS: [Job; 6] = [
+    Job::LogCleanup,
+    Job::ModelsCacheExpiry,
+    Job::ModelsRefresh,
+    Job::ConfigBackup,
+    Job::CreditsCheck,
+    Job::VersionCheck,
+];
+
+impl Job {
+    fn name(self) -> &'static str {
+        match self {
+            Job::LogCleanup => "log_cleanup",
+            Job::ModelsCacheExpiry => "models_cache_expiry",
+            Job::ModelsRefresh => "models_refresh",
+            Job::ConfigBackup => "config_backup",
+            Job::CreditsCheck => "credits_check",
+            Job::VersionCheck => "version_check",
+        }
+    }
+
+    fn interval(self, config: &Config) -> Option<Duration> {
+        let schedule = &config.schedule;
+        let minutes = match self {
+            Job::LogCleanup => schedule.log_cleanup_minutes,
+            Job::ModelsCacheExpiry => {
+                return (schedule.models_cache_ttl_minutes > 0).then_some(CACHE_SWEEP);
+            }
+            Job::ModelsRefresh => schedule.models_refresh_minutes,
+            Job::ConfigBackup => schedule.config_backup_minutes,
+            Job::CreditsCheck => schedule.credits_check_minutes,
+            Job::VersionCheck => {
+                return config.weekly_version_check.then_some(VERSION_CHECK_POLL);
+            }
+        };
+        (minutes > 0).then(|| Duration::from_secs(minutes * 60))
+    }
+
+    /// Housekeeping jobs also run once at startup; the rest wait a full interval.
+    fn runs_at_startup(self) -> bool {
+        matches!(
+            self,
+            Job::LogCleanup | Job::ConfigBackup | Job::CreditsCheck | Job::VersionCheck
+        )
+    }
+
+    async fn run(self, app: &AppHandle) {
+        match self {
+            Job::LogCleanup => {
+                if let Ok(log_dir) = config::logs_dir() {
+                    crate::logs::cleanup_old_logs(&log_dir);
+                }
+                crate::audit::cleanup();
+            }
+            Job::ModelsCacheExpiry => {
+                let ttl = schedule(app).models_cache_ttl_minutes;
+                let state = app.state::<AppState>();
+                let mut cache = state.models_cache.lock().unwrap();
+                let expired = cache
+                    .as_ref()
+                    .is_some_and(|cached| cached.fetched_at.elapsed().as_secs() >= ttl * 60);
+                if expired {
+                    *cache = None;
+                    debug!("Models cache expired");
+                }
+            }
+            Job::ModelsRefresh => {
+                let api_key = app.state::<AppState>().config().api_key.clone();
+                if api_key.trim().is_empty() {
+                    return;
+                }
+                match openrouter::fetch_models(&app.state::<AppState>().http(), &api_key).await {
+                    Ok(models) => {
+                        info!(count = models.len(), "Models cache refreshed");
+                        *app.state::<AppState>().models_cache.lock().unwrap() =
+                            Some(CachedModels {
+                                models,
+                                fetched_at: Instant::now(),
+                            });
+                    }
+                    Err(e) => error!(error = %e, "Scheduled models refresh failed"),
+                }
+            }
+            Job::ConfigBackup => match config::backup(CONFIG_BACKUPS_KEPT) {
+                Ok(Some(path)) => debug!(path = %path.display(), "Config backed up"),
+                Ok(None) => {}
+                Err(e) => error!(error = %e, "Config backup failed"),
+            },
+            Job::CreditsCheck => {
+                let (api_key, threshold) = {
+                    let state = app.state::<AppState>();
+                    let config = state.config();
+                    (config.api_key.clone(), config.low_credits_warning)
+                };
+                if api_key.trim().is_empty() || threshold <= 0.0 {
+                    return;
+                }
+                match openrouter::account_status(&app.state::<AppState>().http(), &api_key).await {
+                    Ok(status) => {
+                        let Some(remaining) = status.remaining() else {
+                            return;
+                        };
+                        debug!(remaining, threshold, "Credits checked");
+                        if remaining >= threshold {
+                            LOW_CREDITS_WARNED.store(false, Ordering::Relaxed);
+                        } else if !LOW_CREDITS_WARNED.swap(true, Ordering::Relaxed) {
+                            warn!(remaining, threshold, "OpenRouter credits low");
+                            show_toast(app, "error", &i18n::t(Msg::CreditsLow));
+                        }
+                    }
+                    Err(e) => error!(error = %e, "Scheduled credits check failed"),
+                }
+            }
+            Job::VersionCheck => updater::check_latest_if_due(app).await,
+        }
+    }
+}
+
+fn schedule(app: &AppHandle) -> Schedule {
+    app.state::<AppState>().config().schedule.clone()
+}
+
+fn current_config(app: &AppHandle) -> Arc<Config> {
+    app.state::<AppState>().config()
+}
+
+/// Spawns one timer task per job on the Tauri async runtime.
+pub fn start(app: AppHandle) {
+    for job in JOBS {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if job.runs_at_startup() && job.interval(&current_config(&app)).is_some() {
+                job.run(&app).await;
+            }
+            let mut last_run = Instant::now();
+            loop {
+                tokio::time::sleep(TICK).await;
+                let Some(interval) = job.interval(&current_config(&app)) else {
+                    // A job that gets enabled waits a full interval, as at startup
+                    last_run = Instant::now();
+                    continue;
+                };
+                if last_run.elapsed() < interval {
+                    continue;
+                }
+                debug!(job = job.name(), "Scheduled job running");
+                job.run(&app).await;
+                last_run = Instant::now();
+            }
+        });
+    }
+    info!(jobs = JOBS.len(), "Scheduler started");
+}
@@ -0,0 +1,288 @@
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+/// Marks a config value encrypted by `seal`, followed by the scheme and the base64
+/// ciphertext, e.g. `enc:dpapi:AQAAANCM...`.
+const PREFIX: &str = "enc:";
+
+#[cfg(windows)]
+const SCHEME: &str = "dpapi";
+#[cfg(not(windows))]
+const SCHEME: &str = "local";
+
+/// Whether `value` was written by `seal` rather than being plaintext.
+pub fn is_sealed(value: &str) -> bool {
+    value.starts_with(PREFIX)
+}
+
+/// Encrypts `plaintext` so that only this user on this machine can read it back.
+pub fn seal(plaintext: &str) -> Result<String> {
+    let ciphertext = platform::protect(plaintext.as_bytes())?;
+    Ok(format!(
+        "{}{}:{}",
+        PREFIX,
+        SCHEME,
+        BASE64.encode(ciphertext)
+    ))
+}
+
+/// Decrypts a value written by `seal`.
+pub fn open(value: &str) -> Result<String> {
+    let (scheme, encoded) = value
+        .strip_prefix(PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+        .ok_or_else(|| anyhow!("Not an encrypted value"))?;
+    if scheme != SCHEME {
+        return Err(anyhow!("Encrypted with {} on another system", scheme));
+    }
+    let ciphertext = BASE64.decode(encoded.trim()).context("decode ciphertext")?;
+    let plaintext = platform::unprotect(&ciphertext)?;
+    String::from_utf8(plaintext).context("decrypted value is not UTF-8")
+}
+
+/// DPAPI in the current user's scope: the Windows account's credentials hold the key.
+#[cfg(windows)]
+mod platform {
+    use anyhow::{anyhow, Result};
+    use std::ptr;
+    use windows_sys::Win32::Foundation::LocalFree;
+    use windows_sys::Win32::Security::Cryptography::{
+        CryptProtectData, CryptUnprotectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB,
+    };
+
+    pub fn protect(plaintext: &[u8]) -> Result<Vec<u8>> {
+        let input = blob(plaintext)?;
+        let mut output = CRYPT_INTEGER_BLOB::default();
+        // SAFETY: `input` borrows `plaintext` for the call; `output` is allocated by
+        // DPAPI and released by `take`
+        let ok = unsafe {
+            CryptProtectData(
+                &input,
+                ptr::null(),
+                ptr::null(),
+                ptr::null(),
+                ptr::null(),
+                CRYPTPROTECT_UI_FORBIDDEN,
+                &mut output,
+            )
+        };
+        if ok == 0 {
+            return Err(anyhow!(
+                "CryptProtectData failed: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(take(output))
+    }
+
+    pub fn unprotect(ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let input = blob(ciphertext)?;
+        let mut output = CRYPT_INTEGER_BLOB::default();
+        // SAFETY: as in `protect`
+        let ok = unsafe {
+            CryptUnprotectData(
+                &input,
+                ptr::null_mut(),
+                ptr::null(),
+                ptr::null(),
+                ptr::null(),
+                CRYPTPROTECT_UI_FORBIDDEN,
+                &mut output,
+            )
+        };
+        if ok == 0 {
+            return Err(anyhow!(
+                "CryptUnprotectData failed: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(take(output))
+    }
+
+    fn blob(data: &[u8]) -> Result<CRYPT_INTEGER_BLOB> {
+        Ok(CRYPT_INTEGER_BLOB {
+            cbData: u32::try_from(data.len()).map_err(|_| anyhow!("Value too large"))?,
+            pbData: data.as_ptr() as *mut u8,
+        })
+    }
+
+    fn take(output: CRYPT_INTEGER_BLOB) -> Vec<u8> {
+        // SAFETY: DPAPI returned `cbData` bytes at `pbData`, allocated with LocalAlloc
+        unsafe {
+            let data = std::slice::from_raw_parts(output.pbData, output.cbData as usize).to_vec();
+            LocalFree(output.pbData.cast());
+            data
+        }
+    }
+}
+
+/// ChaCha20-Poly1305 under a random key held by the OS keystore: the login Keychain on
+/// macOS, the Secret Service (GNOME Keyring, KWallet) on Linux. Without a keystore,
+/// such as on a headless Linux session, the key falls back to `~/.thirdspace/secret.key`,
+/// readable by the current user only but sitting next to the config.json it protects.
+#[cfg(not(windows))]
+mod platform {
+    use anyhow::{anyhow, Result};
+    use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+    use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+    use std::sync::{Mutex, PoisonError};
+
+    const KEY_LEN: usize = 32;
+    const NONCE_LEN: usize = 12;
+
+    /// Held while the key is looked up or created, so two first-time `seal` calls do
+    /// not each create one.
+    static KEY_LOCK: Mutex<()> = Mutex::new(());
+
+    pub fn protect(plaintext: &[u8]) -> Result<Vec<u8>> {
+        let cipher = ChaCha20Poly1305::new(&key(true)?);
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| anyhow!("Failed to encrypt"))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    pub fn unprotect(sealed: &[u8]) -> Result<Vec<u8>> {
+        if sealed.len() < NONCE_LEN {
+            return Err(anyhow!("Encrypted value is damaged"));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let cipher = ChaCha20Poly1305::new(&key(false)?);
+        cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("Encrypted with a different key"))
+    }
+
+    /// The encryption key, created first when `create` is set and there is none.
+    fn key(create: bool) -> Result<Key> {
+        let _lock = KEY_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        if let Some(key) = keystore::key(create)? {
+            return Ok(key);
+        }
+        file::key(create)
+    }
+
+    fn generate() -> Key {
+        ChaCha20Poly1305::generate_key(&mut OsRng)
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    mod keystore {
+        use super::{file, generate, KEY_LEN};
+        use anyhow::{anyhow, Result};
+        use chacha20poly1305::Key;
+        use keyring::{Entry, Error};
+        use tracing::{info, warn};
+
+        const SERVICE: &str = "ThirdSpace";
+        const ACCOUNT: &str = "config-encryption-key";
+
+        /// The key from the keystore, moving a secret.key left by an earlier version
+        /// into it. `None` when no keystore can be reached.
+        pub fn key(create: bool) -> Result<Option<Key>> {
+            let entry = match Entry::new(SERVICE, ACCOUNT) {
+                Ok(entry) => entry,
+                Err(e) => return Ok(unavailable(e)),
+            };
+            match entry.get_secret() {
+                Ok(bytes) if bytes.len() == KEY_LEN => return Ok(Some(*Key::from_slice(&bytes))),
+                Ok(_) => return Err(anyhow!("The keystore's ThirdSpace key is damaged")),
+                Err(Error::NoEntry) => {}
+                Err(e) => return Ok(unavailable(e)),
+            }
+
+            let (key, migrated) = match file::read()? {
+                Some(key) => (key, true),
+                None if create => (generate(), false),
+                None => return Err(anyhow!("No ThirdSpace key in the keystore")),
+            };
+            if let Err(e) = entry.set_secret(&key) {
+                return Ok(unavailable(e));
+            }
+            if migrated {
+                file::remove();
+            }
+            info!(migrated, "Stored the encryption key in the keystore");
+            Ok(Some(key))
+        }
+
+        fn unavailable(e: Error) -> Option<Key> {
+            warn!(error = %e, "OS keystore unavailable, using secret.key");
+            None
+        }
+    }
+
+    /// The fallback key file, created with mode 0600.
+    mod file {
+        use super::{generate, KEY_LEN};
+        use crate::config;
+        use anyhow::{anyhow, Context, Result};
+        use chacha20poly1305::Key;
+        use std::fs;
+        use std::io::{ErrorKind, Write};
+        use std::path::PathBuf;
+        use tracing::info;
+
+        fn path() -> Result<PathBuf> {
+            Ok(config::app_dir()?.join("secret.key"))
+        }
+
+        /// The key in secret.key; `None` when there is no such file.
+        pub fn read() -> Result<Option<Key>> {
+            match fs::read(path()?) {
+                Ok(bytes) if bytes.len() == KEY_LEN => Ok(Some(*Key::from_slice(&bytes))),
+                Ok(_) => Err(anyhow!("secret.key is damaged")),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e).context("read secret.key"),
+            }
+        }
+
+        /// Reads secret.key, creating it first when `create` is set and it is missing.
+        pub fn key(create: bool) -> Result<Key> {
+            if let Some(key) = read()? {
+                return Ok(key);
+            }
+            if !create {
+                return Err(anyhow!("secret.key is missing"));
+            }
+
+            let path = path()?;
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).context("create app directory")?;
+            }
+            let mut options = fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            let mut file = match options.open(&path) {
+                Ok(file) => file,
+                // Another process created it since the read above
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    return read()?.ok_or_else(|| anyhow!("secret.key disappeared"));
+                }
+                Err(e) => return Err(e).context("create secret.key"),
+            };
+            let key = generate();
+            file.write_all(&key).context("write secret.key")?;
+            info!(path = %path.display(), "Created secret.key");
+            Ok(key)
+        }
+
+        /// Deletes secret.key once the keystore holds its key.
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        pub fn remove() {
+            let removed = path().and_then(|path| fs::remove_file(path).map_err(Into::into));
+            if let Err(e) = removed {
+                tracing::warn!(error = %e, "Failed to delete secret.key");
+            }
+        }
+    }
+}
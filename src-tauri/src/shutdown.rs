@@ -0,0 +1,106 @@
+use crate::i18n::{self, Msg};
+use crate::AppState;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogResult};
+use tauri_plugin_global_shortcut::GlobalShortcutExt;
+use tracing::{debug, info, warn};
+
+/// How long quitting waits for running requests to write their results.
+const GRACE_PERIOD: Duration = Duration::from_secs(5);
+/// How long "Quit When Done" waits, in case a request never returns.
+const WAIT_LIMIT: Duration = Duration::from_secs(10 * 60);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Set once quitting has begun; `execute_action` refuses new requests from then on.
+pub fn in_progress() -> bool {
+    SHUTTING_DOWN.load(Ordering::Relaxed)
+}
+
+/// Tray "Quit". With requests running or inputs in the offline queue, asks first whether
+/// to wait for the requests, quit anyway, or keep running; otherwise quits right away.
+pub fn request_quit(app: &AppHandle) {
+    if in_progress() {
+        return;
+    }
+    let state = app.state::<AppState>();
+    let running = state.requests.len();
+    let queued = state.offline_queue.len();
+    if running == 0 && queued == 0 {
+        quit(app, GRACE_PERIOD);
+        return;
+    }
+
+    info!(running, queued, "Quit needs confirmation");
+    let wait = i18n::t(Msg::QuitWhenDone);
+    let now = i18n::t(Msg::QuitNow);
+    let keep = i18n::t(Msg::KeepRunning);
+    // Waiting only helps running requests; queued inputs cannot be sent while quitting
+    let buttons = if running > 0 {
+        MessageDialogButtons::YesNoCancelCustom(wait.clone(), now.clone(), keep)
+    } else {
+        MessageDialogButtons::OkCancelCustom(now.clone(), keep)
+    };
+    let handle = app.clone();
+    app.dialog()
+        .message(i18n::t(Msg::QuitPending(running, queued)))
+        .title(i18n::t(Msg::DialogQuit))
+        .buttons(buttons)
+        .show_with_result(move |result| match result {
+            MessageDialogResult::Custom(label) if label == wait => quit(&handle, WAIT_LIMIT),
+            MessageDialogResult::Custom(label) if label == now => quit(&handle, GRACE_PERIOD),
+            _ => debug!("Quit cancelled"),
+        });
+}
+
+/// Stops taking input, lets running requests finish for up to `wait`, then exits.
+/// Calling it again while waiting does nothing.
+fn quit(app: &AppHandle, wait: Duration) {
+    if SHUTTING_DOWN.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    let state = app.state::<AppState>();
+    info!(
+        active_requests = state.requests.len(),
+        queued = state.offline_queue.len(),
+        wait_secs = wait.as_secs(),
+        "Shutdown started"
+    );
+    if let Err(e) = app.global_shortcut().unregister_all() {
+        warn!(error = %e, "Failed to unregister hotkeys");
+    }
+    if let Some(task) = state.bridge.lock().unwrap().take() {
+        task.abort();
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let started = Instant::now();
+        while !state.requests.is_empty() && started.elapsed() < wait {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        let abandoned = state.requests.list();
+        if abandoned.is_empty() {
+            info!(
+                waited_ms = started.elapsed().as_millis() as u64,
+                "Requests settled"
+            );
+        } else {
+            warn!(
+                request_ids = ?abandoned.iter().map(|request| request.request_id).collect::<Vec<_>>(),
+                "Abandoning requests still running"
+            );
+        }
+        if !state.offline_queue.is_empty() {
+            warn!(
+                queued = state.offline_queue.len(),
+                "Offline queue dropped on exit"
+            );
+        }
+        app.exit(0);
+    });
+}
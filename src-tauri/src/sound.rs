@@ -0,0 +1,114 @@
+use anyhow::{anyhow, Context, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SizedSample, Stream, StreamConfig};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Peak amplitude, well below full scale so a cue does not startle.
+const VOLUME: f32 = 0.2;
+/// Fade at both ends of each note, which keeps it from clicking.
+const FADE_SECS: f32 = 0.005;
+/// The stream stays open this much longer than the notes so the device can drain.
+const TAIL: Duration = Duration::from_millis(100);
+
+/// The sound played when a translation finishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    /// Two short rising notes.
+    Success,
+    /// Two longer, lower falling notes.
+    Failure,
+}
+
+impl Cue {
+    /// Notes as (frequency in Hz, length in ms).
+    fn notes(self) -> &'static [(f32, u64)] {
+        match self {
+            Cue::Success => &[(880.0, 90), (1320.0, 120)],
+            Cue::Failure => &[(440.0, 150), (294.0, 220)],
+        }
+    }
+}
+
+/// Plays `cue` on the default output device without waiting for it. A missing or busy
+/// device is logged and otherwise ignored.
+pub fn play(cue: Cue) {
+    // The output stream is not `Send`, so it is opened on the thread that keeps it alive
+    let spawned = std::thread::Builder::new()
+        .name("sound".into())
+        .spawn(move || {
+            if let Err(e) = render(cue) {
+                warn!(cue = ?cue, error = %e, "Completion sound failed");
+            }
+        });
+    if let Err(e) = spawned {
+        warn!(error = %e, "Failed to start sound thread");
+    }
+}
+
+fn render(cue: Cue) -> Result<()> {
+    let device = cpal::default_host()
+        .default_output_device()
+        .context("no audio output device found")?;
+    let supported = device
+        .default_output_config()
+        .context("read audio output config")?;
+    let sample_rate = supported.sample_rate().0;
+    let format = supported.sample_format();
+    let config: StreamConfig = supported.into();
+    let samples = synthesize(cue, sample_rate);
+    let duration = Duration::from_secs_f64(samples.len() as f64 / sample_rate.max(1) as f64);
+
+    let stream = match format {
+        cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config, samples),
+        cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config, samples),
+        cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config, samples),
+        other => Err(anyhow!("unsupported sample format {:?}", other)),
+    }?;
+    stream.play().context("start audio output")?;
+    std::thread::sleep(duration + TAIL);
+    debug!(cue = ?cue, "Completion sound played");
+    Ok(())
+}
+
+/// Mono sine notes at `sample_rate`, one after the other.
+fn synthesize(cue: Cue, sample_rate: u32) -> Vec<f32> {
+    let rate = sample_rate as f32;
+    let fade = ((FADE_SECS * rate) as usize).max(1);
+    let mut samples = Vec::new();
+    for &(frequency, ms) in cue.notes() {
+        let len = (rate * ms as f32 / 1000.0) as usize;
+        for i in 0..len {
+            let envelope = (i.min(len - 1 - i) as f32 / fade as f32).min(1.0);
+            let phase = std::f32::consts::TAU * frequency * i as f32 / rate;
+            samples.push(phase.sin() * envelope * VOLUME);
+        }
+    }
+    samples
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    samples: Vec<f32>,
+) -> Result<Stream>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    let channels = config.channels.max(1) as usize;
+    let mut position = 0;
+    device
+        .build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                for frame in data.chunks_mut(channels) {
+                    let value = samples.get(position).copied().unwrap_or(0.0);
+                    position += 1;
+                    frame.fill(T::from_sample(value));
+                }
+            },
+            |e| warn!(error = %e, "Audio output stream error"),
+            None,
+        )
+        .context("open audio output")
+}
@@ -0,0 +1,174 @@
+use crate::config::Config;
+use crate::openrouter;
+use anyhow::Result;
+use reqwest::Client;
+use std::collections::VecDeque;
+use tracing::{debug, info};
+
+/// Upper bound on cues sent in one request; the character budget is `chunk_max_chars`.
+const BATCH_MAX_CUES: usize = 40;
+const CUE_SEPARATOR: &str = "\n%%\n";
+
+/// A blank-line separated block of an SRT/VTT file.
+enum Block {
+    /// Index and timing lines, followed by the cue text.
+    Cue { header: String, text: String },
+    /// WEBVTT header, NOTE, STYLE and anything else without a timing line.
+    Verbatim(String),
+}
+
+/// Translates the cue text of an SRT or VTT document, leaving indices, timestamps and
+/// cue settings untouched.
+pub async fn translate(
+    client: &Client,
+    config: &Config,
+    input: &str,
+    on_progress: impl Fn(usize, usize),
+) -> Result<String> {
+    let (bom, body) = match input.strip_prefix('\u{feff}') {
+        Some(body) => ("\u{feff}", body),
+        None => ("", input),
+    };
+    let line_ending = if body.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut blocks = parse(&body.replace("\r\n", "\n"));
+
+    let texts: Vec<String> = blocks
+        .iter()
+        .filter_map(|block| match block {
+            Block::Cue { text, .. } if !text.trim().is_empty() => Some(text.clone()),
+            _ => None,
+        })
+        .collect();
+    info!(cues = texts.len(), "Translating subtitle cues");
+    let mut translated = translate_cues(client, config, &texts, on_progress)
+        .await?
+        .into_iter();
+
+    for block in &mut blocks {
+        if let Block::Cue { text, .. } = block {
+            if text.trim().is_empty() {
+                continue;
+            }
+            if let Some(next) = translated.next() {
+                *text = next;
+            }
+        }
+    }
+
+    let output = blocks
+        .iter()
+        .map(|block| match block {
+            Block::Cue { header, text } if text.is_empty() => header.clone(),
+            Block::Cue { header, text } => format!("{}\n{}", header, text),
+            Block::Verbatim(raw) => raw.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Ok(format!("{}{}\n", bom, output).replace('\n', line_ending))
+}
+
+fn parse(body: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut lines: Vec<&str> = Vec::new();
+    for line in body.lines().chain(std::iter::once("")) {
+        if !line.trim().is_empty() {
+            lines.push(line);
+            continue;
+        }
+        if lines.is_empty() {
+            continue;
+        }
+        let block = match lines.iter().position(|line| line.contains("-->")) {
+            Some(timing) => Block::Cue {
+                header: lines[..=timing].join("\n"),
+                text: lines[timing + 1..].join("\n"),
+            },
+            None => Block::Verbatim(lines.join("\n")),
+        };
+        blocks.push(block);
+        lines.clear();
+    }
+    blocks
+}
+
+/// Translates non-empty cue texts in `%%`-separated batches. A batch whose output does
+/// not split back into the same number of cues is halved and retried, down to single cues.
+async fn translate_cues(
+    client: &Client,
+    config: &Config,
+    texts: &[String],
+    on_progress: impl Fn(usize, usize),
+) -> Result<Vec<String>> {
+    let mut translated = texts.to_vec();
+    let mut queue: VecDeque<(usize, usize)> = batches(texts, config.chunk_max_chars).into();
+    let mut done = 0;
+
+    while let Some((start, end)) = queue.pop_front() {
+        let batch: Vec<&str> = texts[start..end].iter().map(String::as_str).collect();
+        let output = openrouter::translate(client, config, &batch.join(CUE_SEPARATOR)).await?;
+        let parts = split_cues(&output);
+        if parts.len() == batch.len() {
+            translated.splice(start..end, parts);
+            done += end - start;
+            on_progress(done, texts.len());
+        } else if end - start > 1 {
+            debug!(
+                expected = batch.len(),
+                received = parts.len(),
+                "Cue count mismatch, splitting batch"
+            );
+            let mid = start + (end - start) / 2;
+            queue.push_front((mid, end));
+            queue.push_front((start, mid));
+        } else {
+            translated[start] = output.trim().to_string();
+            done += 1;
+            on_progress(done, texts.len());
+        }
+    }
+    Ok(translated)
+}
+
+/// Groups consecutive cues into `(start, end)` ranges within the request budget.
+fn batches(texts: &[String], max_chars: usize) -> Vec<(usize, usize)> {
+    let max_chars = if max_chars == 0 {
+        usize::MAX
+    } else {
+        max_chars
+    };
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut chars = 0;
+    for (idx, text) in texts.iter().enumerate() {
+        let len = text.chars().count() + CUE_SEPARATOR.len();
+        if idx > start && (chars + len > max_chars || idx - start >= BATCH_MAX_CUES) {
+            ranges.push((start, idx));
+            start = idx;
+            chars = 0;
+        }
+        chars += len;
+    }
+    if start < texts.len() {
+        ranges.push((start, texts.len()));
+    }
+    ranges
+}
+
+fn split_cues(output: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    for line in output.lines() {
+        if line.trim() == "%%" {
+            parts.push(String::new());
+            continue;
+        }
+        let part = parts.last_mut().expect("parts is never empty");
+        if !part.is_empty() {
+            part.push('\n');
+        }
+        part.push_str(line);
+    }
+    parts
+        .into_iter()
+        .map(|part| part.trim().to_string())
+        .collect()
+}
@@ -0,0 +1,53 @@
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// `1` exports spans to the endpoint in the standard `OTEL_EXPORTER_OTLP_ENDPOINT` /
+/// `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT` variables (default `http://localhost:4318`);
+/// a URL exports to that collector instead. Unset or empty leaves export off.
+const OTLP_ENV: &str = "THIRDSPACE_OTLP";
+/// How long the exit flush may wait for the collector.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Batches and sends spans; kept so they can be flushed on exit.
+static PROVIDER: Mutex<Option<SdkTracerProvider>> = Mutex::new(None);
+
+/// An OTLP/HTTP export layer when `THIRDSPACE_OTLP` asks for one. Built before the
+/// subscriber exists, so errors are returned for the caller to log.
+pub fn layer<S>() -> Result<Option<OpenTelemetryLayer<S, SdkTracer>>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let setting = std::env::var(OTLP_ENV).unwrap_or_default();
+    let setting = setting.trim();
+    if setting.is_empty() || setting == "0" {
+        return Ok(None);
+    }
+
+    let mut builder = SpanExporter::builder().with_http();
+    if setting != "1" {
+        builder = builder.with_endpoint(format!("{}/v1/traces", setting.trim_end_matches('/')));
+    }
+    let exporter = builder.build().context("build OTLP exporter")?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name("thirdspace").build())
+        .build();
+    let tracer = provider.tracer("thirdspace");
+    *PROVIDER.lock().unwrap() = Some(provider);
+    Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+}
+
+/// Sends the spans still queued; called on exit.
+pub fn shutdown() {
+    if let Some(provider) = PROVIDER.lock().unwrap().take() {
+        let _ = provider.shutdown_with_timeout(SHUTDOWN_TIMEOUT);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::config::{Config, NotificationMode, ToastCorner};
+use crate::AppState;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{
+    AppHandle, Emitter, Manager, Monitor, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+};
+use tauri_plugin_notification::NotificationExt;
+use tracing::error;
+
+const TOAST_WIDTH: f64 = 200.0;
+const TOAST_HEIGHT: f64 = 56.0;
+
+/// Tracks which toast is currently on screen so that hide timers belonging
+/// to a replaced toast never hide the newer one.
+#[derive(Default)]
+pub struct ToastManager {
+    generation: AtomicU64,
+}
+
+impl ToastManager {
+    /// Claims the toast window for a new message and returns its token.
+    fn begin(&self) -> u64 {
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn is_current(&self, token: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == token
+    }
+}
+
+pub fn show_toast(app: &AppHandle, kind: &str, title: &str) {
+    let state = app.state::<AppState>();
+    let config = state.config();
+    if config.silent_mode && kind != "error" && kind != "budget" {
+        return;
+    }
+    if config.notification_mode == NotificationMode::Native {
+        show_native(app, kind, title);
+        return;
+    }
+    let token = state.toasts.begin();
+
+    // Create or get toast window
+    let (toast, is_new) = match app.get_webview_window("toast") {
+        Some(w) => (w, false),
+        None => {
+            // Pass initial state via URL query params
+            let url = format!("toast.html?kind={}&title={}", kind, urlencoding(title));
+            match WebviewWindowBuilder::new(app, "toast", WebviewUrl::App(url.into()))
+                .title("Toast")
+                .decorations(false)
+                .transparent(true)
+                .shadow(false)
+                .always_on_top(true)
+                .skip_taskbar(true)
+                .resizable(false)
+                // Never take focus from the field a translation may be typed into
+                .focusable(false)
+                .inner_size(TOAST_WIDTH, TOAST_HEIGHT)
+                .visible(false)
+                .build()
+            {
+                Ok(w) => (w, true),
+                Err(e) => {
+                    error!(error = %e, "Failed to create toast window");
+                    return;
+                }
+            }
+        }
+    };
+
+    position_toast(&toast, &config);
+
+    // Update toast content via event (only for existing windows)
+    if !is_new {
+        let _ = toast.emit(
+            "update-toast",
+            serde_json::json!({
+                "kind": kind,
+                "title": title
+            }),
+        );
+    }
+
+    // Show toast
+    let _ = toast.show();
+
+    // Schedule hide after duration; a newer toast invalidates this timer
+    let duration = Duration::from_millis(duration_ms(&config, kind));
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(duration).await;
+        if !app_handle.state::<AppState>().toasts.is_current(token) {
+            return;
+        }
+        if let Some(toast) = app_handle.get_webview_window("toast") {
+            let _ = toast.hide();
+        }
+    });
+}
+
+fn duration_ms(config: &Config, kind: &str) -> u64 {
+    config
+        .toast_duration_overrides
+        .get(kind)
+        .copied()
+        .unwrap_or(config.toast_duration_ms)
+}
+
+fn show_native(app: &AppHandle, kind: &str, title: &str) {
+    // Notification centers queue every message, so skip transient progress updates
+    if kind == "processing" {
+        return;
+    }
+    let body = if title.is_empty() {
+        default_label(kind)
+    } else {
+        title
+    };
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("ThirdSpace")
+        .body(body)
+        .show()
+    {
+        error!(error = %e, "Failed to show native notification");
+    }
+}
+
+/// Mirrors the fallback labels in toast.js.
+fn default_label(kind: &str) -> &str {
+    match kind {
+        "success" => "Done",
+        "error" => "Error",
+        "cached" => "Done (cached)",
+        "processing" => "Translating...",
+        "budget" => "Budget reached",
+        other => other,
+    }
+}
+
+fn position_toast(toast: &WebviewWindow, config: &Config) {
+    let Some(monitor) = target_monitor(toast, config) else {
+        return;
+    };
+
+    // The work area excludes the taskbar/dock wherever the OS puts it
+    let area = monitor.work_area();
+    let scale = monitor.scale_factor();
+    let width = TOAST_WIDTH * scale;
+    let height = TOAST_HEIGHT * scale;
+    let margin = config.toast_margin.max(0.0) * scale;
+
+    let left = area.position.x as f64 + margin;
+    let right = area.position.x as f64 + area.size.width as f64 - width - margin;
+    let top = area.position.y as f64 + margin;
+    let bottom = area.position.y as f64 + area.size.height as f64 - height - margin;
+
+    let (x, y) = match config.toast_corner {
+        ToastCorner::TopLeft => (left, top),
+        ToastCorner::TopRight => (right, top),
+        ToastCorner::BottomLeft => (left, bottom),
+        ToastCorner::BottomRight => (right, bottom),
+    };
+    let _ = toast.set_position(tauri::PhysicalPosition::new(x as i32, y as i32));
+}
+
+fn target_monitor(toast: &WebviewWindow, config: &Config) -> Option<Monitor> {
+    if config.toast_follow_cursor {
+        let under_cursor = toast
+            .cursor_position()
+            .ok()
+            .and_then(|pos| toast.monitor_from_point(pos.x, pos.y).ok().flatten());
+        if under_cursor.is_some() {
+            return under_cursor;
+        }
+    }
+    toast.primary_monitor().ok().flatten()
+}
+
+fn urlencoding(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
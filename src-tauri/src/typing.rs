@@ -0,0 +1,92 @@
+use crate::i18n::{self, Msg};
+use crate::permissions::{self, Permission};
+use crate::toast::show_toast;
+use anyhow::{anyhow, Result};
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tracing::{error, info};
+
+/// Types the translation into the focused field on a background thread, so a slow
+/// typing rate does not hold up the next request.
+pub fn spawn(app: &AppHandle, text: String, chars_per_sec: u32) {
+    if !permissions::ensure(Permission::Accessibility) {
+        show_toast(app, "error", &i18n::t(Msg::AllowAccessibility));
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let started = Instant::now();
+        match type_text(&text, chars_per_sec) {
+            Ok(()) => info!(
+                chars = text.chars().count(),
+                chars_per_sec,
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "Translation typed"
+            ),
+            Err(e) => {
+                error!(error = %e, "Typing failed");
+                show_toast(&app, "error", &i18n::t(Msg::TypingFailed));
+            }
+        }
+    });
+}
+
+/// Presses the platform's copy shortcut so the focused application puts its selection on
+/// the clipboard. Modifiers still held from the hotkey are released first, so the
+/// application sees a plain Ctrl+C (Cmd+C on macOS).
+pub fn press_copy() -> Result<()> {
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|e| anyhow!("open keyboard: {}", e))?;
+    #[cfg(target_os = "macos")]
+    let (held, command) = ([Key::Shift, Key::Alt, Key::Control], Key::Meta);
+    #[cfg(not(target_os = "macos"))]
+    let (held, command) = ([Key::Shift, Key::Alt, Key::Meta], Key::Control);
+
+    for key in held {
+        enigo
+            .key(key, Direction::Release)
+            .map_err(|e| anyhow!("release modifier: {}", e))?;
+    }
+    enigo
+        .key(command, Direction::Press)
+        .map_err(|e| anyhow!("press copy: {}", e))?;
+    let copied = enigo.key(Key::Unicode('c'), Direction::Click);
+    // Released even when the click failed, so the modifier does not stay down
+    enigo
+        .key(command, Direction::Release)
+        .map_err(|e| anyhow!("release copy: {}", e))?;
+    copied.map_err(|e| anyhow!("press copy: {}", e))
+}
+
+/// Sends `text` as keystrokes, `chars_per_sec` at a time; 0 types it all at once.
+/// Line breaks are sent as Return so they work in fields that ignore typed newlines.
+fn type_text(text: &str, chars_per_sec: u32) -> Result<()> {
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|e| anyhow!("open keyboard: {}", e))?;
+    let delay = (chars_per_sec > 0).then(|| Duration::from_secs_f64(1.0 / chars_per_sec as f64));
+
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            enigo
+                .key(Key::Return, Direction::Click)
+                .map_err(|e| anyhow!("type line break: {}", e))?;
+            if let Some(delay) = delay {
+                std::thread::sleep(delay);
+            }
+        }
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let Some(delay) = delay else {
+            enigo.text(line).map_err(|e| anyhow!("type text: {}", e))?;
+            continue;
+        };
+        let mut buf = [0; 4];
+        for ch in line.chars() {
+            enigo
+                .text(ch.encode_utf8(&mut buf))
+                .map_err(|e| anyhow!("type text: {}", e))?;
+            std::thread::sleep(delay);
+        }
+    }
+    Ok(())
+}
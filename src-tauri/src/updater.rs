@@ -0,0 +1,253 @@
+use crate::config::{self, UpdateChannel};
+use crate::i18n::{self, Msg};
+use crate::toast::show_toast;
+use crate::AppState;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+use tauri_plugin_updater::UpdaterExt;
+use tracing::{debug, error, info, warn};
+
+/// Where the release workflow keeps one manifest per channel (`stable.json`, `beta.json`),
+/// on a rolling `updater` release.
+const MANIFEST_BASE: &str = "https://github.com/wenming-ma/ThirdSpace/releases/download/updater";
+/// The newest published release; drafts and pre-releases are never returned.
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/wenming-ma/ThirdSpace/releases/latest";
+/// How long the weekly version check waits after the last one, across restarts.
+const VERSION_CHECK_INTERVAL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// A newer release on the configured channel.
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub notes: Option<String>,
+    pub channel: UpdateChannel,
+}
+
+/// Asks `channel` for a newer release. A found update is kept for `install`.
+pub async fn check(app: &AppHandle, channel: UpdateChannel) -> Result<Option<UpdateInfo>> {
+    if !signing_key_configured(app) {
+        return Err(anyhow!("This build was made without an update signing key"));
+    }
+    let endpoint = Url::parse(&format!("{}/{}.json", MANIFEST_BASE, channel.name()))?;
+    let update = app
+        .updater_builder()
+        .endpoints(vec![endpoint])?
+        .build()?
+        .check()
+        .await?;
+
+    let info = update.as_ref().map(|update| UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        notes: update.body.clone(),
+        channel,
+    });
+    match &info {
+        Some(info) => info!(version = %info.version, channel = channel.name(), "Update available"),
+        None => debug!(channel = channel.name(), "No update available"),
+    }
+    *app.state::<AppState>().pending_update.lock().unwrap() = update;
+    Ok(info)
+}
+
+/// Downloads and installs the update found by the last `check`, then restarts into it.
+pub async fn install(app: &AppHandle) -> Result<()> {
+    let update = app
+        .state::<AppState>()
+        .pending_update
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| anyhow!("No update to install; check for updates first"))?;
+    show_toast(app, "processing", &i18n::t(Msg::Updating));
+    update.download_and_install(|_, _| {}, || {}).await?;
+    info!(version = %update.version, "Update installed, restarting");
+    app.restart();
+}
+
+/// Tray "Check for Updates…": checks, then asks before downloading anything.
+pub fn check_from_tray(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let channel = app.state::<AppState>().config().update_channel;
+        match check(&app, channel).await {
+            Ok(Some(info)) => confirm_install(&app, &info),
+            Ok(None) => show_toast(&app, "success", &i18n::t(Msg::UpToDate)),
+            Err(e) => {
+                warn!(error = %e, "Update check failed");
+                show_toast(&app, "error", &i18n::t(Msg::UpdateCheckFailed));
+            }
+        }
+    });
+}
+
+/// The latest GitHub release compared with the running version.
+#[derive(Debug, Clone, Serialize)]
+pub struct LatestVersion {
+    pub version: String,
+    pub current_version: String,
+    pub newer: bool,
+    /// The release page.
+    pub url: String,
+    pub published_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: String,
+    published_at: Option<String>,
+}
+
+/// Asks the GitHub releases API for the latest release. A newer one is announced with an
+/// `update-available` event and a toast. Unlike `check`, this needs no signing key and
+/// installs nothing.
+pub async fn check_latest(app: &AppHandle) -> Result<LatestVersion> {
+    let release: GithubRelease = app
+        .state::<AppState>()
+        .http()
+        .get(LATEST_RELEASE_URL)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await
+        .context("send GitHub releases request")?
+        .error_for_status()
+        .context("GitHub releases request failed")?
+        .json()
+        .await
+        .context("parse GitHub release")?;
+
+    let version = release.tag_name.trim_start_matches('v').to_string();
+    let current_version = app.package_info().version.to_string();
+    let latest = LatestVersion {
+        newer: is_newer(&version, &current_version),
+        version,
+        current_version,
+        url: release.html_url,
+        published_at: release.published_at,
+    };
+    if let Err(e) = write_version_stamp() {
+        warn!(error = %e, "Failed to record version check");
+    }
+    if latest.newer {
+        info!(
+            version = %latest.version,
+            current = %latest.current_version,
+            "Newer release on GitHub"
+        );
+        let _ = app.emit("update-available", &latest);
+        show_toast(
+            app,
+            "success",
+            &i18n::t(Msg::UpdateAvailable(&latest.version)),
+        );
+    } else {
+        debug!(version = %latest.version, "Running the latest release");
+    }
+    Ok(latest)
+}
+
+/// Scheduler entry for `weekly_version_check`: checks unless the last check, from this
+/// or an earlier session, is less than a week old.
+pub async fn check_latest_if_due(app: &AppHandle) {
+    let due = read_version_stamp()
+        .and_then(|checked| SystemTime::now().duration_since(checked).ok())
+        .is_none_or(|elapsed| elapsed >= VERSION_CHECK_INTERVAL);
+    if !due {
+        return;
+    }
+    if let Err(e) = check_latest(app).await {
+        warn!(error = %e, "Scheduled version check failed");
+    }
+}
+
+/// Whether dotted version `candidate` is above `current`, numerically per component.
+/// Pre-release and build suffixes are ignored.
+fn is_newer(candidate: &str, current: &str) -> bool {
+    fn parts(version: &str) -> Vec<u64> {
+        let mut parts: Vec<u64> = version
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.trim().parse().unwrap_or(0))
+            .collect();
+        // "1.2" and "1.2.0" are the same version
+        while parts.last() == Some(&0) {
+            parts.pop();
+        }
+        parts
+    }
+    parts(candidate) > parts(current)
+}
+
+fn version_stamp_path() -> Result<std::path::PathBuf> {
+    Ok(config::app_dir()?.join("version_check"))
+}
+
+/// When the last version check ran, from its stamp file.
+fn read_version_stamp() -> Option<SystemTime> {
+    let secs = fs::read_to_string(version_stamp_path().ok()?).ok()?;
+    Some(UNIX_EPOCH + Duration::from_secs(secs.trim().parse().ok()?))
+}
+
+fn write_version_stamp() -> Result<()> {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    fs::write(version_stamp_path()?, secs.to_string()).context("write version check stamp")
+}
+
+fn confirm_install(app: &AppHandle, info: &UpdateInfo) {
+    let mut message = format!(
+        "ThirdSpace {} is available. You have {}.",
+        info.version, info.current_version
+    );
+    if let Some(notes) = info
+        .notes
+        .as_deref()
+        .filter(|notes| !notes.trim().is_empty())
+    {
+        message.push_str("\n\n");
+        message.push_str(notes.trim());
+    }
+    let handle = app.clone();
+    app.dialog()
+        .message(message)
+        .title("Update Available")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Install and Restart".to_string(),
+            "Later".to_string(),
+        ))
+        .show(move |accepted| {
+            if !accepted {
+                debug!("Update postponed");
+                return;
+            }
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = install(&handle).await {
+                    error!(error = %e, "Update failed");
+                    show_toast(&handle, "error", &i18n::t(Msg::UpdateFailed));
+                }
+            });
+        });
+}
+
+/// Release builds get the updater public key from the release workflow; without it no
+/// download could be verified, so checking is pointless.
+fn signing_key_configured(app: &AppHandle) -> bool {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|pubkey| pubkey.as_str())
+        .is_some_and(|pubkey| !pubkey.trim().is_empty())
+}
@@ -0,0 +1,185 @@
+use crate::config::{self, BudgetAction, Config};
+use crate::estimate;
+use crate::i18n::{self, Msg};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+/// This month's totals, read from `usage.json` on first use.
+static CURRENT: Mutex<Option<MonthlyUsage>> = Mutex::new(None);
+
+tokio::task_local! {
+    /// Set while a request runs that the user repeated to go over the budget.
+    static CONFIRMED: ();
+}
+
+/// Tokens and spend of every OpenRouter chat request in one calendar month (UTC).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MonthlyUsage {
+    /// `YYYY-MM`.
+    pub month: String,
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// USD as reported by OpenRouter; requests it did not price add nothing.
+    pub cost: f64,
+}
+
+impl MonthlyUsage {
+    pub fn tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// A request refused by `check` because this month's usage reached `monthly_budget` or
+/// `monthly_token_limit`.
+#[derive(Debug)]
+pub struct BudgetExceeded {
+    pub usage: MonthlyUsage,
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&i18n::t(Msg::BudgetExceeded(
+            &estimate::format_cost(self.usage.cost),
+            self.usage.tokens(),
+            &self.usage.month,
+        )))
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+/// The budget gate every chat request passes (`openrouter::request_chat`), whatever sent
+/// it. With `BudgetAction::Confirm`, requests running inside `confirmed` still go out.
+pub fn check(config: &Config) -> Result<(), BudgetExceeded> {
+    if config.monthly_budget <= 0.0 && config.monthly_token_limit == 0 {
+        return Ok(());
+    }
+    let usage = current();
+    let over_cost = config.monthly_budget > 0.0 && usage.cost >= config.monthly_budget;
+    let over_tokens =
+        config.monthly_token_limit > 0 && usage.tokens() >= config.monthly_token_limit;
+    if !over_cost && !over_tokens {
+        return Ok(());
+    }
+    if config.budget_action == BudgetAction::Confirm && is_confirmed() {
+        return Ok(());
+    }
+    Err(BudgetExceeded { usage })
+}
+
+/// Runs `future` past the budget when `confirmed` is set; `execute_action` uses it for a
+/// request the user repeated, and chunked translations hand it on to their tasks.
+pub async fn confirmed<F: Future>(confirmed: bool, future: F) -> F::Output {
+    if confirmed {
+        CONFIRMED.scope((), future).await
+    } else {
+        future.await
+    }
+}
+
+/// Whether the running task is inside `confirmed`.
+pub fn is_confirmed() -> bool {
+    CONFIRMED.try_with(|_| ()).is_ok()
+}
+
+pub fn path() -> Result<PathBuf> {
+    Ok(config::app_dir()?.join("usage.json"))
+}
+
+/// Totals of the current month; the first request of a month starts again from zero.
+pub fn current() -> MonthlyUsage {
+    let mut current = CURRENT.lock().unwrap();
+    this_month(&mut current).clone()
+}
+
+/// Adds one request to this month's totals and saves them.
+pub fn record(prompt_tokens: u64, completion_tokens: u64, cost: Option<f64>) {
+    let mut current = CURRENT.lock().unwrap();
+    let usage = this_month(&mut current);
+    usage.requests += 1;
+    usage.prompt_tokens += prompt_tokens;
+    usage.completion_tokens += completion_tokens;
+    usage.cost += cost.unwrap_or(0.0);
+    debug!(
+        prompt_tokens,
+        completion_tokens,
+        cost = ?cost,
+        month_tokens = usage.tokens(),
+        month_cost = usage.cost,
+        "Usage recorded"
+    );
+    if let Err(e) = save(usage) {
+        warn!(error = %e, "Failed to save usage");
+    }
+}
+
+fn this_month(current: &mut Option<MonthlyUsage>) -> &mut MonthlyUsage {
+    let month = month_key(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0),
+    );
+    if current.as_ref().is_some_and(|usage| usage.month != month) {
+        info!(month = %month, "Monthly usage reset");
+        *current = None;
+    }
+    current.get_or_insert_with(|| {
+        load()
+            .ok()
+            .filter(|usage| usage.month == month)
+            .unwrap_or_else(|| MonthlyUsage {
+                month,
+                ..MonthlyUsage::default()
+            })
+    })
+}
+
+fn load() -> Result<MonthlyUsage> {
+    let data = fs::read_to_string(path()?).context("read usage.json")?;
+    serde_json::from_str(&data).context("parse usage.json")
+}
+
+fn save(usage: &MonthlyUsage) -> Result<()> {
+    let path = path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).context("create app directory")?;
+    }
+    let data = serde_json::to_string_pretty(usage).context("serialize usage")?;
+    fs::write(&path, data).context("write usage.json")
+}
+
+/// `YYYY-MM` of a Unix timestamp, in UTC.
+fn month_key(secs: u64) -> String {
+    let (year, month, _) = civil_date(secs);
+    format!("{:04}-{:02}", year, month)
+}
+
+/// Year, month and day of a Unix timestamp, in UTC.
+pub fn civil_date(secs: u64) -> (i64, i64, i64) {
+    // Days to civil date, after Howard Hinnant's `civil_from_days`
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,100 @@
+use crate::{bridge, config, AppState};
+use notify::{Event, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{debug, error, info, warn};
+
+/// Editors write a file in several steps; events closer together than this are one change.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Reloads config.json when it is edited outside the app. The parent directory is
+/// watched rather than the file, so atomic saves (write temp file, rename) are seen too.
+pub fn watch_config(app: AppHandle) {
+    let (path, dir) = match config::config_path().and_then(|path| {
+        let dir = config::app_dir()?;
+        std::fs::create_dir_all(&dir)?;
+        Ok((path, dir))
+    }) {
+        Ok(paths) => paths,
+        Err(e) => {
+            error!(error = %e, "Config watcher not started");
+            return;
+        }
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let watched = path.clone();
+    let mut watcher =
+        match notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+            Ok(event) if event.paths.iter().any(|changed| changed == &watched) => {
+                let _ = tx.send(());
+            }
+            Ok(_) => {}
+            Err(e) => warn!(error = %e, "Config watcher error"),
+        }) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                error!(error = %e, "Config watcher not started");
+                return;
+            }
+        };
+    if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+        error!(error = %e, dir = %dir.display(), "Config watcher not started");
+        return;
+    }
+
+    debug!(path = %path.display(), "Config watcher started");
+    std::thread::spawn(move || {
+        // The thread owns the watcher; dropping it would stop the events
+        let _watcher = watcher;
+        while rx.recv().is_ok() {
+            while rx.recv_timeout(DEBOUNCE).is_ok() {}
+            reload(&app, &path);
+        }
+    });
+}
+
+fn reload(app: &AppHandle, path: &Path) {
+    if !path.exists() {
+        return;
+    }
+    let mut new_config = match config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            // Usually a half-finished edit; the next save triggers another attempt
+            warn!(error = %e, "Ignoring unreadable config.json");
+            return;
+        }
+    };
+
+    // Writing the token back lands here again, with nothing left to change
+    if bridge::ensure_token(&mut new_config) {
+        if let Err(e) = config::save(&new_config) {
+            warn!(error = %e, "Failed to save the browser bridge token");
+        }
+    }
+
+    let state = app.state::<AppState>();
+    let unchanged = {
+        let current = state.config();
+        serde_json::to_value(&*current).ok() == serde_json::to_value(&new_config).ok()
+    };
+    // Our own saves land here too
+    if unchanged {
+        return;
+    }
+
+    if let Err(e) = crate::apply_config(app, &state, &new_config) {
+        error!(error = %e, "Reloaded config could not be applied");
+        return;
+    }
+    info!(
+        model = %new_config.model,
+        target_language = %new_config.target_language,
+        hotkey = %new_config.hotkey,
+        "Config reloaded from disk"
+    );
+    let _ = app.emit("config-changed", &new_config);
+}
@@ -0,0 +1,52 @@
+const { invoke } = window.__TAURI__.core;
+
+let candidates = [];
+
+function render(list) {
+    candidates = list || [];
+    const container = document.getElementById('candidates');
+    container.replaceChildren(...candidates.map((text, i) => {
+        const button = document.createElement('button');
+        button.className = 'candidate';
+        button.onclick = () => choose(i);
+
+        const number = document.createElement('span');
+        number.className = 'number';
+        number.textContent = i + 1;
+        const body = document.createElement('span');
+        body.className = 'text';
+        body.textContent = text;
+
+        button.append(number, body);
+        return button;
+    }));
+    container.firstChild?.focus();
+}
+
+async function choose(index) {
+    try {
+        await invoke('choose_candidate', { index });
+        window.__TAURI__.window.getCurrentWindow().hide();
+    } catch (e) {
+        console.error('Failed to choose candidate:', e);
+    }
+}
+
+document.addEventListener('keydown', (event) => {
+    const index = Number(event.key) - 1;
+    if (index >= 0 && index < candidates.length) {
+        event.preventDefault();
+        choose(index);
+    } else if (event.key === 'Escape') {
+        window.__TAURI__.window.getCurrentWindow().hide();
+    }
+});
+
+// Initial candidates come from the backend; later ones arrive as events
+invoke('get_candidates')
+    .then(render)
+    .catch(e => console.error('Failed to load candidates:', e));
+
+window.__TAURI__.event.listen('candidates-updated', (event) => {
+    render(event.payload);
+});
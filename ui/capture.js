@@ -0,0 +1,77 @@
+const { invoke } = window.__TAURI__.core;
+
+const screen = document.getElementById('screen');
+const shade = document.getElementById('shade');
+const selection = document.getElementById('selection');
+let capture = null;
+let origin = null;
+
+async function loadCapture() {
+    try {
+        capture = await invoke('get_capture');
+        screen.onload = async () => {
+            const win = window.__TAURI__.window.getCurrentWindow();
+            await win.show();
+            await win.setFocus();
+        };
+        screen.src = capture.image;
+    } catch (e) {
+        console.error('Failed to load capture:', e);
+        invoke('cancel_capture');
+    }
+}
+
+function currentRect(event) {
+    return {
+        left: Math.min(origin.x, event.clientX),
+        top: Math.min(origin.y, event.clientY),
+        width: Math.abs(event.clientX - origin.x),
+        height: Math.abs(event.clientY - origin.y)
+    };
+}
+
+document.addEventListener('mousedown', (event) => {
+    if (event.button !== 0 || !capture) return;
+    origin = { x: event.clientX, y: event.clientY };
+    shade.style.display = 'none';
+    selection.style.display = 'block';
+    selection.style.left = `${origin.x}px`;
+    selection.style.top = `${origin.y}px`;
+    selection.style.width = '0px';
+    selection.style.height = '0px';
+});
+
+document.addEventListener('mousemove', (event) => {
+    if (!origin) return;
+    const rect = currentRect(event);
+    selection.style.left = `${rect.left}px`;
+    selection.style.top = `${rect.top}px`;
+    selection.style.width = `${rect.width}px`;
+    selection.style.height = `${rect.height}px`;
+});
+
+document.addEventListener('mouseup', (event) => {
+    if (!origin) return;
+    const rect = currentRect(event);
+    origin = null;
+
+    // The screenshot is in physical pixels and stretched over the window
+    const scaleX = capture.width / window.innerWidth;
+    const scaleY = capture.height / window.innerHeight;
+    invoke('capture_region', {
+        region: {
+            x: Math.round(rect.left * scaleX),
+            y: Math.round(rect.top * scaleY),
+            width: Math.round(rect.width * scaleX),
+            height: Math.round(rect.height * scaleY)
+        }
+    }).catch((e) => console.error('Capture failed:', e));
+});
+
+document.addEventListener('keydown', (event) => {
+    if (event.key === 'Escape') {
+        invoke('cancel_capture');
+    }
+});
+
+loadCapture();
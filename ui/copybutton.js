@@ -0,0 +1,11 @@
+const { invoke } = window.__TAURI__.core;
+
+// Hides right away; the toast takes over from here
+document.getElementById('button').addEventListener('click', async () => {
+    window.__TAURI__.window.getCurrentWindow().hide();
+    try {
+        await invoke('translate_clipboard');
+    } catch (e) {
+        console.warn('Translation failed:', e);
+    }
+});
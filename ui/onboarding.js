@@ -0,0 +1,124 @@
+const { invoke } = window.__TAURI__.core;
+const IS_MAC = navigator.userAgent.includes('Mac');
+
+// Step the Next button completes
+let current = 'api_key';
+let isRecording = false;
+
+function show(step) {
+    current = step;
+    document.querySelectorAll('.step').forEach(el => {
+        el.classList.toggle('active', el.id === `step-${step}`);
+    });
+    document.querySelectorAll('.steps span').forEach(el => {
+        el.classList.toggle('active', el.dataset.step === step);
+    });
+    document.getElementById('nextBtn').textContent = step === 'finish' ? 'Start' : 'Next';
+    document.getElementById('message').textContent = '';
+    const input = document.querySelector(`#step-${step} input:not([readonly])`);
+    if (input) input.focus();
+}
+
+function prefill(progress) {
+    document.getElementById('model').value = progress.model;
+    document.getElementById('hotkey').value = progress.hotkey;
+}
+
+// The request body for the step being completed
+function answer() {
+    switch (current) {
+        case 'api_key':
+            return { step: 'api_key', key: document.getElementById('apiKey').value };
+        case 'model':
+            return { step: 'model', model: document.getElementById('model').value };
+        case 'hotkey':
+            return { step: 'hotkey', hotkey: document.getElementById('hotkey').value };
+        default:
+            return { step: 'finish' };
+    }
+}
+
+async function next() {
+    const button = document.getElementById('nextBtn');
+    if (button.disabled) return;
+    button.disabled = true;
+    document.getElementById('message').textContent = current === 'api_key' ? 'Checking...' : '';
+    try {
+        const progress = await invoke('complete_onboarding', { step: answer() });
+        prefill(progress);
+        if (progress.next !== 'done') {
+            show(progress.next);
+        }
+    } catch (e) {
+        document.getElementById('message').textContent = e.message ?? String(e);
+    } finally {
+        button.disabled = false;
+    }
+}
+
+document.querySelectorAll('.step a').forEach(link => {
+    link.addEventListener('click', async (e) => {
+        e.preventDefault();
+        if (window.__TAURI__?.shell) {
+            await window.__TAURI__.shell.open(link.getAttribute('href'));
+        }
+    });
+});
+
+// Hotkey recording; the current hotkey is paused so pressing it is recorded, not run
+const hotkeyInput = document.getElementById('hotkey');
+
+hotkeyInput.addEventListener('focus', async () => {
+    isRecording = true;
+    hotkeyInput.placeholder = 'Press keys...';
+    try {
+        await invoke('pause_hotkey');
+    } catch (e) {
+        console.error('Failed to pause hotkey:', e);
+    }
+});
+
+hotkeyInput.addEventListener('blur', async () => {
+    isRecording = false;
+    try {
+        await invoke('resume_hotkey');
+    } catch (e) {
+        console.error('Failed to resume hotkey:', e);
+    }
+});
+
+document.addEventListener('keydown', (e) => {
+    if (!isRecording) {
+        if (e.key === 'Enter') next();
+        return;
+    }
+    e.preventDefault();
+    e.stopPropagation();
+    if (['Control', 'Alt', 'Shift', 'Meta'].includes(e.key)) {
+        return;
+    }
+
+    const parts = [];
+    if (e.ctrlKey) parts.push('Ctrl');
+    if (e.altKey) parts.push(IS_MAC ? 'Option' : 'Alt');
+    if (e.shiftKey) parts.push('Shift');
+    if (e.metaKey) parts.push(IS_MAC ? 'Cmd' : 'Win');
+
+    let key = e.key;
+    if (key === ' ') key = 'Space';
+    else if (e.code.startsWith('Numpad')) key = e.code;
+    else if (key.length === 1 && !/[a-z0-9]/i.test(key)) key = e.code.replace(/^(Digit|Key)/, '');
+    else if (key.length === 1) key = key.toUpperCase();
+    else if (key.startsWith('Arrow')) key = key.replace('Arrow', '');
+
+    parts.push(key);
+    hotkeyInput.value = parts.join('+');
+    hotkeyInput.blur();
+}, { capture: true });
+
+invoke('get_onboarding')
+    .then(progress => {
+        prefill(progress);
+        show(progress.next);
+    })
+    .catch(e => console.error('Failed to load onboarding:', e));
@@ -0,0 +1,79 @@
+const { invoke } = window.__TAURI__.core;
+
+async function loadLanguages() {
+    const select = document.getElementById('language');
+    try {
+        const config = await invoke('get_config');
+        const languages = [...config.languages];
+        if (config.target_language && !languages.includes(config.target_language)) {
+            languages.unshift(config.target_language);
+        }
+        select.innerHTML = '';
+        for (const language of languages) {
+            const option = document.createElement('option');
+            option.value = language;
+            option.textContent = language;
+            option.selected = language === config.target_language;
+            select.appendChild(option);
+        }
+    } catch (e) {
+        console.error('Failed to load languages:', e);
+    }
+}
+
+async function translateInput() {
+    const text = document.getElementById('input').value;
+    if (!text.trim()) return;
+
+    const output = document.getElementById('output');
+    const status = document.getElementById('status');
+    const button = document.getElementById('translateBtn');
+    const started = Date.now();
+    button.disabled = true;
+    output.className = 'output pending';
+    output.textContent = 'Translating...';
+    status.textContent = '';
+    try {
+        output.textContent = await invoke('translate_text', {
+            text,
+            targetLanguage: document.getElementById('language').value || null
+        });
+        output.className = 'output';
+        status.textContent = `${((Date.now() - started) / 1000).toFixed(1)}s`;
+    } catch (e) {
+        output.className = 'output error';
+        output.textContent = e.message ?? String(e);
+    } finally {
+        button.disabled = false;
+    }
+}
+
+async function copyOutput() {
+    const output = document.getElementById('output');
+    if (output.className !== 'output' || !output.textContent) return;
+    try {
+        await window.__TAURI__.clipboardManager.writeText(output.textContent);
+        document.getElementById('status').textContent = 'Copied';
+    } catch (e) {
+        console.error('Failed to copy:', e);
+    }
+}
+
+document.addEventListener('keydown', (event) => {
+    if (event.key === 'Enter' && (event.ctrlKey || event.metaKey)) {
+        event.preventDefault();
+        translateInput();
+    } else if (event.key === 'Escape') {
+        window.__TAURI__.window.getCurrentWindow().hide();
+    }
+});
+
+// Reopening the popup focuses the text box and picks up language changes
+window.__TAURI__.event.listen('quick-translate-opened', () => {
+    loadLanguages();
+    const input = document.getElementById('input');
+    input.focus();
+    input.select();
+});
+
+loadLanguages();
@@ -0,0 +1,92 @@
+const { invoke } = window.__TAURI__.core;
+
+function render(result) {
+    if (!result) return;
+    document.getElementById('translation').textContent = result.translation || '';
+    document.getElementById('source').textContent = result.source || '';
+
+    const romanization = result.romanization || '';
+    document.getElementById('romanization').textContent = romanization;
+    document.getElementById('romanizationSection').classList.toggle('hidden', !romanization);
+
+    renderDictionary(result.dictionary);
+    renderTargets(result.translations || []);
+}
+
+// A multi-target result shows one section per language instead of the combined text
+function renderTargets(translations) {
+    document.getElementById('translationSection').classList.toggle('hidden', translations.length > 0);
+    document.getElementById('targets').replaceChildren(...translations.map((target, i) => {
+        const section = document.createElement('div');
+        section.className = 'section';
+
+        const header = document.createElement('div');
+        header.className = 'section-header';
+        const title = document.createElement('span');
+        title.className = 'section-title';
+        title.textContent = target.language;
+        const copy = document.createElement('button');
+        copy.className = 'copy-btn';
+        copy.textContent = 'Copy';
+        copy.onclick = () => copyField(`target${i}`);
+        header.append(title, copy);
+
+        const body = document.createElement('div');
+        body.className = 'section-body';
+        body.id = `target${i}`;
+        body.textContent = target.text;
+
+        section.append(header, body);
+        return section;
+    }));
+}
+
+function renderDictionary(entry) {
+    const section = document.getElementById('dictionarySection');
+    const body = document.getElementById('dictionary');
+    body.innerHTML = '';
+    section.classList.toggle('hidden', !entry);
+    if (!entry) return;
+
+    if (entry.part_of_speech) {
+        const pos = document.createElement('div');
+        pos.className = 'part-of-speech';
+        pos.textContent = entry.part_of_speech;
+        body.appendChild(pos);
+    }
+
+    if (entry.definitions && entry.definitions.length > 0) {
+        const list = document.createElement('ol');
+        entry.definitions.forEach(def => {
+            const item = document.createElement('li');
+            item.textContent = def;
+            list.appendChild(item);
+        });
+        body.appendChild(list);
+    }
+
+    (entry.examples || []).forEach(example => {
+        const line = document.createElement('div');
+        line.className = 'example';
+        line.textContent = example;
+        body.appendChild(line);
+    });
+}
+
+async function copyField(id) {
+    const text = document.getElementById(id).textContent;
+    try {
+        await window.__TAURI__.clipboardManager.writeText(text);
+    } catch (e) {
+        console.error('Failed to copy:', e);
+    }
+}
+
+// Initial state comes from the backend; later results arrive as events
+invoke('get_result')
+    .then(render)
+    .catch(e => console.error('Failed to load result:', e));
+
+window.__TAURI__.event.listen('result-updated', (event) => {
+    render(event.payload);
+});
@@ -0,0 +1,63 @@
+const { invoke } = window.__TAURI__.core;
+
+function render(review) {
+    const translation = document.getElementById('translation');
+    const hint = document.getElementById('hint');
+    if (!review) {
+        translation.value = '';
+        hint.textContent = 'Nothing to review';
+        document.getElementById('acceptBtn').disabled = true;
+        return;
+    }
+    document.getElementById('source').textContent = review.source;
+    translation.value = review.translation;
+    hint.textContent = `${review.target_language} · Ctrl+Enter to accept, Esc to reject`;
+    document.getElementById('acceptBtn').disabled = false;
+    translation.focus();
+}
+
+function close() {
+    window.__TAURI__.window.getCurrentWindow().hide();
+}
+
+async function accept() {
+    const button = document.getElementById('acceptBtn');
+    if (button.disabled) return;
+    button.disabled = true;
+    try {
+        await invoke('accept_translation', {
+            translation: document.getElementById('translation').value
+        });
+        close();
+    } catch (e) {
+        document.getElementById('hint').textContent = e.message ?? String(e);
+        button.disabled = false;
+    }
+}
+
+async function reject() {
+    try {
+        await invoke('reject_translation');
+    } catch (e) {
+        console.error('Failed to reject translation:', e);
+    }
+    close();
+}
+
+document.addEventListener('keydown', (event) => {
+    if (event.key === 'Enter' && (event.ctrlKey || event.metaKey)) {
+        event.preventDefault();
+        accept();
+    } else if (event.key === 'Escape') {
+        reject();
+    }
+});
+
+// Initial review comes from the backend; later ones arrive as events
+invoke('get_review')
+    .then(render)
+    .catch(e => console.error('Failed to load review:', e));
+
+window.__TAURI__.event.listen('review-updated', (event) => {
+    render(event.payload);
+});
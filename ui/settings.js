@@ -1,39 +1,534 @@
 const { invoke } = window.__TAURI__.core;
 
+// macOS names the modifiers differently; both spellings parse the same
+const IS_MAC = navigator.userAgent.includes('Mac');
+
+// Last loaded config, so fields without a control survive a save
+let loadedConfig = {};
+
 // Model search state
-let modelsCache = null;
-let modelsFetching = false;
+let modelsLoaded = false;
+let modelSearchTimer = null;
+// Bumped per search so a slow answer cannot replace a newer one
+let modelSearchSeq = 0;
+// Model list order: 'name' keeps OpenRouter's order, 'price' puts the cheapest first
+let modelSort = 'name';
+
+function parseList(value) {
+    return value.split(',').map(s => s.trim()).filter(s => s.length > 0);
+}
+
+// Empty number inputs mean "use the model default"
+function parseOptionalNumber(value) {
+    return value.trim() === '' ? null : Number(value);
+}
+
+// Effort name, or `{ budget: tokens }` when a token budget is chosen.
+function readReasoning() {
+    const effort = document.getElementById('reasoning').value;
+    if (effort !== 'budget') {
+        return effort;
+    }
+    const budget = Math.floor(Number(document.getElementById('reasoningBudget').value) || 0);
+    return { budget: Math.max(0, budget) };
+}
 
 async function loadConfig() {
     try {
         const config = await invoke('get_config');
+        loadedConfig = config;
         document.getElementById('apiKey').value = config.api_key || '';
         document.getElementById('model').value = config.model || '';
         document.getElementById('targetLanguage').value = config.target_language || 'English';
-        document.getElementById('hotkey').value = config.hotkey || 'Ctrl+Alt+T';
-        document.getElementById('reasoning').checked = config.reasoning_enabled !== false;
+        document.getElementById('hotkey').value = config.hotkey || (IS_MAC ? 'Cmd+Option+T' : 'Ctrl+Alt+T');
+        const reasoning = config.reasoning ?? 'medium';
+        document.getElementById('reasoning').value =
+            typeof reasoning === 'object' ? 'budget' : reasoning;
+        document.getElementById('reasoningBudget').value = reasoning.budget ?? 2048;
         document.getElementById('autostart').checked = config.autostart === true;
+        document.getElementById('summarizeHotkey').value = config.summarize_hotkey || '';
+        document.getElementById('proofreadHotkey').value = config.proofread_hotkey || '';
+        document.getElementById('rewriteHotkey').value = config.rewrite_hotkey || '';
+        document.getElementById('rewriteTone').value = config.rewrite_tone || 'formal';
+        document.getElementById('translationTone').value = config.translation_tone || '';
+        document.getElementById('romanization').checked = config.romanization === true;
+        document.getElementById('dictionaryLookup').checked = config.dictionary_lookup === true;
+        document.getElementById('preserveFormatting').checked = config.preserve_formatting === true;
+        document.getElementById('primarySelection').checked = config.primary_selection === true;
+        document.getElementById('reviewTranslations').checked = config.review_translations === true;
+        document.getElementById('redactPersonalData').checked = config.redact_personal_data === true;
+        document.getElementById('structuredOutput').checked = config.structured_output === true;
+        document.getElementById('logContent').checked = config.log_content === true;
+        document.getElementById('logRetentionDays').value = config.log_retention_days ?? 14;
+        document.getElementById('logMaxFileMb').value = config.log_max_file_mb ?? 10;
+        document.getElementById('logMaxTotalMb').value = config.log_max_total_mb ?? 100;
+        document.getElementById('auditLog').checked = config.audit_log === true;
+        document.getElementById('auditRetentionDays').value = config.audit_retention_days ?? 90;
+        document.getElementById('encryptApiKey').checked = config.encrypt_api_key !== false;
+        document.getElementById('offlineQueue').checked = config.offline_queue === true;
+        document.getElementById('bridgeEnabled').checked = config.bridge_enabled === true;
+        document.getElementById('bridgeToken').value = config.bridge_token || '';
+        document.getElementById('deepLinks').checked = config.deep_links === true;
+        document.getElementById('connectTimeout').value = config.connect_timeout_secs ?? 10;
+        document.getElementById('requestTimeout').value = config.request_timeout_secs ?? 120;
+        document.getElementById('caBundle').value = config.ca_bundle || '';
+        document.getElementById('useSystemCertificates').checked = config.use_system_certificates !== false;
+        document.getElementById('bypassProxy').checked = config.bypass_proxy === true;
+        document.getElementById('proxy').value = config.proxy || '';
+        document.getElementById('apiBaseUrl').value = config.api_base_url || '';
+        document.getElementById('maxConcurrentRequests').value = config.max_concurrent_requests ?? 2;
+        document.getElementById('maxInputChars').value = config.max_input_chars ?? 20000;
+        document.getElementById('lowCreditsWarning').value = config.low_credits_warning ?? 1;
+        document.getElementById('showCostEstimate').checked = config.show_cost_estimate === true;
+        document.getElementById('confirmCostAbove').value = config.confirm_cost_above ?? 0;
+        document.getElementById('monthlyBudget').value = config.monthly_budget ?? 0;
+        document.getElementById('monthlyTokenLimit').value = config.monthly_token_limit ?? 0;
+        document.getElementById('budgetAction').value = config.budget_action || 'block';
+        document.getElementById('temperature').value = config.temperature ?? '';
+        document.getElementById('topP').value = config.top_p ?? '';
+        document.getElementById('maxTokens').value = config.max_tokens ?? '';
+        document.getElementById('languages').value = (config.languages || []).join(', ');
+        document.getElementById('multiTargetLanguages').value = (config.multi_target_languages || []).join(', ');
+        document.getElementById('translationCandidates').value = config.translation_candidates ?? 0;
+        document.getElementById('conversationContext').value = config.conversation_context ?? 0;
+        document.getElementById('translateSelectionHotkey').value = config.translate_selection_hotkey || '';
+        document.getElementById('cycleLanguageHotkey').value = config.cycle_language_hotkey || '';
+        document.getElementById('undoHotkey').value = config.undo_hotkey || '';
+        document.getElementById('quickTranslateHotkey').value = config.quick_translate_hotkey || '';
+        document.getElementById('captureHotkey').value = config.capture_hotkey || '';
+        document.getElementById('dictationHotkey').value = config.dictation_hotkey || '';
+        document.getElementById('togglePauseHotkey').value = config.toggle_pause_hotkey || '';
+        document.getElementById('trayClick').value = config.tray_click || 'menu';
+        document.getElementById('copyButton').checked = config.copy_button === true;
+        document.getElementById('favoriteModels').value = (config.favorite_models || []).join(', ');
+        document.getElementById('autoFastModel').value = config.auto_model?.fast_model || '';
+        document.getElementById('autoStrongModel').value = config.auto_model?.strong_model || '';
+        document.getElementById('fastMode').checked = config.fast_mode === true;
+        document.getElementById('fastModeModifier').value = config.fast_mode_modifier ?? 'Shift';
+        document.getElementById('doubleTapModifier').value = config.double_tap_modifier || '';
+        document.getElementById('uiLanguage').value = config.ui_language || '';
+        document.getElementById('notificationMode').value = config.notification_mode || 'toast';
+        document.getElementById('outputMode').value = config.output_mode || 'clipboard';
+        document.getElementById('outputLayout').value = config.output_layout || 'replace';
+        const postFormat = config.post_format || {};
+        document.getElementById('stripMarkdown').checked = postFormat.strip_markdown === true;
+        document.getElementById('plainPunctuation').checked = postFormat.plain_punctuation === true;
+        document.getElementById('collapseLineBreaks').checked = postFormat.collapse_line_breaks === true;
+        document.getElementById('trimCommentary').checked = postFormat.trim_commentary === true;
+        document.getElementById('updateChannel').value = config.update_channel || 'stable';
+        document.getElementById('weeklyVersionCheck').checked = config.weekly_version_check === true;
+        document.getElementById('typingRate').value = config.typing_chars_per_sec ?? 60;
+        document.getElementById('toastDuration').value = config.toast_duration_ms ?? 2200;
+        document.getElementById('toastErrorDuration').value =
+            config.toast_duration_overrides?.error ?? config.toast_duration_ms ?? 2200;
+        document.getElementById('silentMode').checked = config.silent_mode === true;
+        document.getElementById('completionSound').checked = config.completion_sound === true;
+        document.getElementById('toastCorner').value = config.toast_corner || 'bottom_right';
+        document.getElementById('toastMargin').value = config.toast_margin ?? 16;
+        document.getElementById('toastFollowCursor').checked = config.toast_follow_cursor === true;
     } catch (e) {
         console.error('Failed to load config:', e);
     }
 }
 
+function formatAccountStatus(status) {
+    const parts = [];
+    const remaining = [status.credits_remaining, status.key.limit_remaining]
+        .filter(value => value !== null && value !== undefined);
+    if (remaining.length > 0) {
+        parts.push(`$${Math.min(...remaining).toFixed(2)} left`);
+    } else {
+        parts.push(`$${status.key.usage.toFixed(2)} used`);
+    }
+    if (status.key.rate_limit) {
+        parts.push(`${status.key.rate_limit.requests} req/${status.key.rate_limit.interval}`);
+    }
+    if (status.key.is_free_tier) {
+        parts.push('free tier');
+    }
+    return parts.join(' · ');
+}
+
+// "This month: $1.24 · 52,300 tokens" under the budget inputs
+async function loadUsage() {
+    try {
+        const usage = await invoke('get_usage');
+        const tokens = usage.prompt_tokens + usage.completion_tokens;
+        document.getElementById('monthlyUsage').textContent =
+            `This month: $${usage.cost.toFixed(2)} · ${tokens.toLocaleString()} tokens`;
+    } catch (e) {
+        console.warn('Failed to load usage:', e);
+    }
+}
+
+// Suggestions for the target language field, from the backend's catalog
+async function loadLanguages() {
+    try {
+        const languages = await invoke('get_languages');
+        document.getElementById('languageOptions').replaceChildren(...languages.map(language => {
+            const option = document.createElement('option');
+            option.value = language.name;
+            option.label = `${language.native_name} (${language.code})`;
+            return option;
+        }));
+    } catch (e) {
+        console.error('Failed to load languages:', e);
+    }
+}
+
+async function loadAccountStatus() {
+    const label = document.getElementById('accountStatus');
+    if (!loadedConfig.api_key) {
+        label.textContent = 'Balance unknown';
+        return;
+    }
+    try {
+        label.textContent = formatAccountStatus(await invoke('get_account_status'));
+    } catch (e) {
+        label.textContent = e.code === 'rate_limited' ? 'Rate limited' : 'Balance unavailable';
+        console.warn('Failed to load account status:', e);
+    }
+}
+
+// Config keys reported by validate_config, mapped to their inputs
+const FIELD_INPUTS = {
+    api_key: 'apiKey',
+    model: 'model',
+    target_language: 'targetLanguage',
+    multi_target_languages: 'multiTargetLanguages',
+    translation_candidates: 'translationCandidates',
+    max_concurrent_requests: 'maxConcurrentRequests',
+    ca_bundle: 'caBundle',
+    proxy: 'proxy',
+    api_base_url: 'apiBaseUrl',
+    conversation_context: 'conversationContext',
+    hotkey: 'hotkey',
+    fast_mode_modifier: 'fastModeModifier',
+    summarize_hotkey: 'summarizeHotkey',
+    proofread_hotkey: 'proofreadHotkey',
+    rewrite_hotkey: 'rewriteHotkey',
+    translate_selection_hotkey: 'translateSelectionHotkey',
+    cycle_language_hotkey: 'cycleLanguageHotkey',
+    undo_hotkey: 'undoHotkey',
+    quick_translate_hotkey: 'quickTranslateHotkey',
+    capture_hotkey: 'captureHotkey',
+    dictation_hotkey: 'dictationHotkey',
+    toggle_pause_hotkey: 'togglePauseHotkey',
+    double_tap_modifier: 'doubleTapModifier',
+    favorite_models: 'favoriteModels',
+    auto_model: 'autoFastModel',
+    temperature: 'temperature',
+    top_p: 'topP',
+    max_tokens: 'maxTokens',
+    reasoning: 'reasoningBudget'
+};
+
+// Every issue, hotkey checks included, carries a severity; only errors block saving
+function isWarning(issue) {
+    return issue.severity === 'warning';
+}
+
+function showIssues(issues) {
+    document.querySelectorAll('.invalid, .questionable').forEach(el => {
+        el.classList.remove('invalid', 'questionable');
+        el.removeAttribute('title');
+    });
+    const list = document.getElementById('validationErrors');
+    list.replaceChildren(...issues.map(issue => {
+        const line = document.createElement('div');
+        line.textContent = issue.message;
+        line.classList.toggle('warning', isWarning(issue));
+        return line;
+    }));
+    for (const issue of issues) {
+        const input = document.getElementById(FIELD_INPUTS[issue.field]);
+        if (input) {
+            input.classList.add(isWarning(issue) ? 'questionable' : 'invalid');
+            input.title = issue.message;
+        }
+    }
+}
+
+// Warns as soon as a hotkey is entered that another application already owns
+async function checkHotkey(field, hotkey) {
+    try {
+        const status = await invoke('validate_hotkey', { hotkey });
+        showIssues(status.available ? [] : [{ field, message: status.message }]);
+    } catch (e) {
+        console.warn('Could not check hotkey:', e);
+    }
+}
+
+for (const [field, id] of Object.entries(FIELD_INPUTS)) {
+    if (field.endsWith('_hotkey')) {
+        document.getElementById(id).addEventListener('change', (e) => {
+            const hotkey = e.target.value.trim();
+            if (hotkey) {
+                checkHotkey(field, hotkey);
+            }
+        });
+    }
+}
+
+// Only a key OpenRouter actually rejects blocks saving; being offline does not
+async function apiKeyAccepted(key) {
+    try {
+        const status = await invoke('validate_api_key', { key });
+        if (!status.valid) {
+            showIssues([{ field: 'api_key', message: status.message }]);
+        }
+        return status.valid;
+    } catch (e) {
+        console.warn('Could not verify API key:', e);
+        return true;
+    }
+}
+
 async function save() {
     try {
+        const toastDuration = Number(document.getElementById('toastDuration').value) || 2200;
+        const toastErrorDuration = Number(document.getElementById('toastErrorDuration').value) || toastDuration;
+        const maxTokens = parseOptionalNumber(document.getElementById('maxTokens').value);
         const config = {
+            ...loadedConfig,
             api_key: document.getElementById('apiKey').value,
             model: document.getElementById('model').value,
             target_language: document.getElementById('targetLanguage').value,
             hotkey: document.getElementById('hotkey').value,
-            reasoning_enabled: document.getElementById('reasoning').checked,
-            autostart: document.getElementById('autostart').checked
+            reasoning: readReasoning(),
+            autostart: document.getElementById('autostart').checked,
+            summarize_hotkey: document.getElementById('summarizeHotkey').value.trim(),
+            proofread_hotkey: document.getElementById('proofreadHotkey').value.trim(),
+            rewrite_hotkey: document.getElementById('rewriteHotkey').value.trim(),
+            rewrite_tone: document.getElementById('rewriteTone').value,
+            translation_tone: document.getElementById('translationTone').value || null,
+            romanization: document.getElementById('romanization').checked,
+            dictionary_lookup: document.getElementById('dictionaryLookup').checked,
+            preserve_formatting: document.getElementById('preserveFormatting').checked,
+            primary_selection: document.getElementById('primarySelection').checked,
+            review_translations: document.getElementById('reviewTranslations').checked,
+            redact_personal_data: document.getElementById('redactPersonalData').checked,
+            structured_output: document.getElementById('structuredOutput').checked,
+            log_content: document.getElementById('logContent').checked,
+            log_retention_days: Math.max(0, Math.floor(Number(document.getElementById('logRetentionDays').value) || 0)),
+            log_max_file_mb: Math.max(0, Math.floor(Number(document.getElementById('logMaxFileMb').value) || 0)),
+            log_max_total_mb: Math.max(0, Math.floor(Number(document.getElementById('logMaxTotalMb').value) || 0)),
+            audit_log: document.getElementById('auditLog').checked,
+            audit_retention_days: Math.max(0, Math.floor(Number(document.getElementById('auditRetentionDays').value) || 0)),
+            encrypt_api_key: document.getElementById('encryptApiKey').checked,
+            offline_queue: document.getElementById('offlineQueue').checked,
+            bridge_enabled: document.getElementById('bridgeEnabled').checked,
+            deep_links: document.getElementById('deepLinks').checked,
+            connect_timeout_secs: Math.max(0, Math.floor(Number(document.getElementById('connectTimeout').value) || 0)),
+            request_timeout_secs: Math.max(0, Math.floor(Number(document.getElementById('requestTimeout').value) || 0)),
+            ca_bundle: document.getElementById('caBundle').value.trim(),
+            use_system_certificates: document.getElementById('useSystemCertificates').checked,
+            bypass_proxy: document.getElementById('bypassProxy').checked,
+            proxy: document.getElementById('proxy').value.trim(),
+            api_base_url: document.getElementById('apiBaseUrl').value.trim(),
+            max_concurrent_requests: Math.floor(Number(document.getElementById('maxConcurrentRequests').value) || 0),
+            max_input_chars: Math.max(0, Math.floor(Number(document.getElementById('maxInputChars').value) || 0)),
+            low_credits_warning: Math.max(0, Number(document.getElementById('lowCreditsWarning').value) || 0),
+            show_cost_estimate: document.getElementById('showCostEstimate').checked,
+            confirm_cost_above: Math.max(0, Number(document.getElementById('confirmCostAbove').value) || 0),
+            monthly_budget: Math.max(0, Number(document.getElementById('monthlyBudget').value) || 0),
+            monthly_token_limit: Math.max(0, Math.round(Number(document.getElementById('monthlyTokenLimit').value) || 0)),
+            budget_action: document.getElementById('budgetAction').value,
+            languages: parseList(document.getElementById('languages').value),
+            multi_target_languages: parseList(document.getElementById('multiTargetLanguages').value),
+            translation_candidates: Math.max(0, Math.floor(Number(document.getElementById('translationCandidates').value) || 0)),
+            conversation_context: Math.max(0, Math.floor(Number(document.getElementById('conversationContext').value) || 0)),
+            translate_selection_hotkey: document.getElementById('translateSelectionHotkey').value.trim(),
+            cycle_language_hotkey: document.getElementById('cycleLanguageHotkey').value.trim(),
+            undo_hotkey: document.getElementById('undoHotkey').value.trim(),
+            quick_translate_hotkey: document.getElementById('quickTranslateHotkey').value.trim(),
+            capture_hotkey: document.getElementById('captureHotkey').value.trim(),
+            dictation_hotkey: document.getElementById('dictationHotkey').value.trim(),
+            toggle_pause_hotkey: document.getElementById('togglePauseHotkey').value.trim(),
+            tray_click: document.getElementById('trayClick').value,
+            copy_button: document.getElementById('copyButton').checked,
+            favorite_models: parseList(document.getElementById('favoriteModels').value),
+            auto_model: {
+                ...(loadedConfig.auto_model || {}),
+                fast_model: document.getElementById('autoFastModel').value.trim(),
+                strong_model: document.getElementById('autoStrongModel').value.trim()
+            },
+            temperature: parseOptionalNumber(document.getElementById('temperature').value),
+            top_p: parseOptionalNumber(document.getElementById('topP').value),
+            max_tokens: maxTokens === null ? null : Math.max(0, Math.floor(maxTokens)),
+            fast_mode: document.getElementById('fastMode').checked,
+            fast_mode_modifier: document.getElementById('fastModeModifier').value,
+            double_tap_modifier: document.getElementById('doubleTapModifier').value,
+            ui_language: document.getElementById('uiLanguage').value,
+            notification_mode: document.getElementById('notificationMode').value,
+            output_mode: document.getElementById('outputMode').value,
+            output_layout: document.getElementById('outputLayout').value,
+            post_format: {
+                strip_markdown: document.getElementById('stripMarkdown').checked,
+                plain_punctuation: document.getElementById('plainPunctuation').checked,
+                collapse_line_breaks: document.getElementById('collapseLineBreaks').checked,
+                trim_commentary: document.getElementById('trimCommentary').checked
+            },
+            update_channel: document.getElementById('updateChannel').value,
+            weekly_version_check: document.getElementById('weeklyVersionCheck').checked,
+            typing_chars_per_sec: Math.max(0, Math.floor(Number(document.getElementById('typingRate').value) || 0)),
+            toast_duration_ms: toastDuration,
+            toast_duration_overrides: {
+                ...(loadedConfig.toast_duration_overrides || {}),
+                error: toastErrorDuration
+            },
+            silent_mode: document.getElementById('silentMode').checked,
+            completion_sound: document.getElementById('completionSound').checked,
+            toast_corner: document.getElementById('toastCorner').value,
+            toast_margin: Number(document.getElementById('toastMargin').value) || 0,
+            toast_follow_cursor: document.getElementById('toastFollowCursor').checked
         };
+        const issues = await invoke('validate_config', { config });
+        showIssues(issues);
+        if (issues.some(issue => !isWarning(issue))) {
+            return;
+        }
+        const keyChanged = config.api_key !== loadedConfig.api_key;
+        if (keyChanged && config.api_key.trim() && !(await apiKeyAccepted(config.api_key))) {
+            return;
+        }
         await invoke('save_config', { newConfig: config });
+        // Saving may have created the bridge token
+        loadedConfig = await invoke('get_config');
+        document.getElementById('bridgeToken').value = loadedConfig.bridge_token || '';
+        if (keyChanged) {
+            loadAccountStatus();
+        }
     } catch (e) {
         console.error('Failed to save config:', e);
     }
 }
 
+const BUNDLE_FILTERS = [{ name: 'ThirdSpace settings', extensions: ['json'] }];
+
+async function exportSettings() {
+    try {
+        const path = await window.__TAURI__.dialog.save({
+            defaultPath: 'thirdspace-settings.json',
+            filters: BUNDLE_FILTERS
+        });
+        if (!path) return;
+        const passphrase = document.getElementById('bundlePassphrase').value;
+        await invoke('export_settings', { path, passphrase });
+        showIssues([]);
+    } catch (e) {
+        showIssues([{ field: '', message: e.message ?? String(e) }]);
+    }
+}
+
+async function exportHistory(format) {
+    try {
+        const path = await window.__TAURI__.dialog.save({
+            defaultPath: `thirdspace-history.${format}`,
+            filters: [{ name: format.toUpperCase(), extensions: [format] }]
+        });
+        if (!path) return;
+        await invoke('export_history', { path, format });
+        showIssues([]);
+    } catch (e) {
+        showIssues([{ field: '', message: e.message ?? String(e) }]);
+    }
+}
+
+async function setLogLevel() {
+    try {
+        await invoke('set_log_level', { filter: document.getElementById('logLevel').value });
+        showIssues([]);
+    } catch (e) {
+        showIssues([{ field: '', message: e.message ?? String(e) }]);
+    }
+}
+
+const DIAGNOSTIC_LABELS = {
+    dns: 'DNS',
+    tls: 'Connection',
+    api_key: 'API key',
+    clipboard: 'Clipboard',
+    hotkey: 'Hotkey'
+};
+const DIAGNOSTIC_MARKS = { ok: '✓', warn: '!', fail: '✗' };
+
+async function runDiagnostics() {
+    const button = document.getElementById('diagnosticsBtn');
+    const report = document.getElementById('diagnosticsReport');
+    button.disabled = true;
+    report.textContent = '';
+    try {
+        const result = await invoke('run_diagnostics');
+        for (const check of result.checks) {
+            const line = document.createElement('span');
+            line.className = 'advanced-item-desc';
+            const label = DIAGNOSTIC_LABELS[check.name] || check.name;
+            line.textContent = `${DIAGNOSTIC_MARKS[check.status]} ${label}: ${check.message}`;
+            report.appendChild(line);
+        }
+    } catch (e) {
+        console.error('Diagnostics failed:', e);
+    } finally {
+        button.disabled = false;
+    }
+}
+
+async function exportDiagnostics() {
+    try {
+        const path = await window.__TAURI__.dialog.save({
+            defaultPath: 'thirdspace-diagnostics.zip',
+            filters: [{ name: 'Zip', extensions: ['zip'] }]
+        });
+        if (!path) return;
+        await invoke('export_diagnostics', { path });
+        showIssues([]);
+    } catch (e) {
+        showIssues([{ field: '', message: e.message ?? String(e) }]);
+    }
+}
+
+async function checkForUpdates() {
+    const button = document.getElementById('updateBtn');
+    const status = document.getElementById('updateStatus');
+    button.disabled = true;
+    try {
+        const channel = document.getElementById('updateChannel').value;
+        const update = await invoke('check_for_updates', { channel });
+        if (!update) {
+            status.textContent = 'ThirdSpace is up to date';
+            return;
+        }
+        status.textContent = `Version ${update.version} is available`;
+        const install = await window.__TAURI__.dialog.ask(
+            `ThirdSpace ${update.version} is available. You have ${update.current_version}.` +
+                (update.notes ? `\n\n${update.notes}` : ''),
+            { title: 'Update Available', okLabel: 'Install and Restart', cancelLabel: 'Later' }
+        );
+        if (install) {
+            status.textContent = `Installing ${update.version}...`;
+            await invoke('install_update');
+        }
+    } catch (e) {
+        status.textContent = e.message ?? String(e);
+    } finally {
+        button.disabled = false;
+    }
+}
+
+async function importSettings() {
+    try {
+        const path = await window.__TAURI__.dialog.open({ multiple: false, filters: BUNDLE_FILTERS });
+        if (!path) return;
+        const passphrase = document.getElementById('bundlePassphrase').value;
+        await invoke('import_settings', { path, passphrase });
+        showIssues([]);
+        // config-changed reloads the form
+    } catch (e) {
+        showIssues([{ field: '', message: e.message ?? String(e) }]);
+    }
+}
+
 function toggleModelDropdown() {
     const dropdown = document.getElementById('modelDropdown');
     const isShowing = dropdown.classList.toggle('show');
@@ -56,36 +551,51 @@ function selectModel(model) {
     document.getElementById('modelDropdown').classList.remove('show');
 }
 
-// Fetch models from OpenRouter API
-async function fetchModels() {
-    if (modelsCache) return modelsCache;
-    if (modelsFetching) return null;
-
-    modelsFetching = true;
-    try {
-        const models = await invoke('fetch_models');
-        modelsCache = models;
-        return models;
-    } catch (e) {
-        console.error('Failed to fetch models:', e);
-        return null;
-    } finally {
-        modelsFetching = false;
+// "$0.15/$0.60 · 1M ctx" for the dropdown
+function modelMeta(m) {
+    const parts = [];
+    if (m.pricing) {
+        const perMillion = v => '$' + Number((v * 1e6).toFixed(2));
+        parts.push(`${perMillion(m.pricing.prompt)}/${perMillion(m.pricing.completion)}`);
+    }
+    if (m.context_length) {
+        const ctx = m.context_length >= 1e6
+            ? Number((m.context_length / 1e6).toFixed(1)) + 'M'
+            : Math.round(m.context_length / 1000) + 'K';
+        parts.push(`${ctx} ctx`);
     }
+    return parts.join(' · ');
 }
 
-// Filter models by query
-function filterModels(query, models) {
-    if (!models) return [];
-    const q = query.toLowerCase().trim();
-    if (!q) return models.slice(0, 10);
-    return models
-        .filter(m => m.id.toLowerCase().includes(q) || m.name.toLowerCase().includes(q))
-        .slice(0, 10);
+function modelFilters() {
+    return {
+        provider: document.getElementById('modelProvider').value || null,
+        reasoning: document.getElementById('modelReasoning').checked,
+        long_context: document.getElementById('modelLongContext').checked,
+        sort: modelSort,
+    };
 }
 
-// Update dropdown with filtered results
-function updateDropdown(models, isLoading = false, error = null) {
+function toggleModelSort() {
+    modelSort = modelSort === 'name' ? 'price' : 'name';
+    document.getElementById('modelSort').textContent = `Sort: ${modelSort}`;
+    searchModels(document.getElementById('model').value);
+}
+
+// Providers come with the first page; the list does not change while settings are open
+function fillProviders(providers) {
+    const select = document.getElementById('modelProvider');
+    if (select.options.length > 1) return;
+    providers.forEach(provider => {
+        const option = document.createElement('option');
+        option.value = provider;
+        option.textContent = provider;
+        select.appendChild(option);
+    });
+}
+
+// Loading, error and empty states of the dropdown
+function updateDropdown(isLoading = false, error = null) {
     const dropdown = document.getElementById('modelDropdown');
     dropdown.innerHTML = '';
 
@@ -99,74 +609,112 @@ function updateDropdown(models, isLoading = false, error = null) {
         return;
     }
 
-    if (!models || models.length === 0) {
-        dropdown.innerHTML = '<div class="model-option empty">No matching models</div>';
-        return;
-    }
+    dropdown.innerHTML = '<div class="model-option empty">No matching models</div>';
+}
+
+// "auto" is not in the catalog, so it leads the list whenever the query matches it
+function prependAutoOption(query) {
+    if (!'auto'.startsWith(query.trim().toLowerCase())) return;
+    const option = document.createElement('div');
+    option.className = 'model-option auto';
+    option.textContent = 'auto';
+    const span = document.createElement('span');
+    span.className = 'model-meta';
+    span.textContent = 'picks per request';
+    option.prepend(span);
+    option.onclick = () => selectModel('auto');
+    document.getElementById('modelDropdown').appendChild(option);
+}
+
+// Adds a page of models, with a "show more" entry while pages remain
+function appendModels(page, query) {
+    const dropdown = document.getElementById('modelDropdown');
+    dropdown.querySelector('.model-option.more')?.remove();
 
-    models.forEach(m => {
+    page.models.forEach(m => {
         const option = document.createElement('div');
         option.className = 'model-option';
         option.textContent = m.id;
+        const meta = modelMeta(m);
+        if (meta) {
+            const span = document.createElement('span');
+            span.className = 'model-meta';
+            span.textContent = meta;
+            option.prepend(span);
+        }
         option.onclick = () => selectModel(m.id);
         dropdown.appendChild(option);
     });
+
+    if (page.has_more) {
+        const shown = dropdown.querySelectorAll('.model-option:not(.auto)').length;
+        const more = document.createElement('div');
+        more.className = 'model-option more';
+        more.textContent = `Show more (${shown} of ${page.total})`;
+        more.onclick = () => searchModels(query, page.page + 1);
+        dropdown.appendChild(more);
+    }
 }
 
-// Search models with debounce
-async function searchModels(query) {
+// Asks the backend for one page of matching models
+async function searchModels(query, page = 0) {
     const dropdown = document.getElementById('modelDropdown');
     const apiKey = document.getElementById('apiKey').value;
 
     // Check if API key is configured
     if (!apiKey.trim()) {
-        updateDropdown(null, false, 'Enter API key first');
+        updateDropdown(false, 'Enter API key first');
         dropdown.classList.add('show');
         return;
     }
 
-    // Show loading state
-    updateDropdown(null, true);
+    // The first search fetches the catalog, which takes a moment
+    const seq = ++modelSearchSeq;
+    if (!modelsLoaded) {
+        updateDropdown(true);
+    }
     dropdown.classList.add('show');
 
-    // Fetch and filter
-    const models = await fetchModels();
-    if (models) {
-        const filtered = filterModels(query, models);
-        updateDropdown(filtered);
-    } else {
-        updateDropdown(null, false, 'Failed to load models');
+    try {
+        const result = await invoke('get_models', { query, page, filters: modelFilters() });
+        if (seq !== modelSearchSeq) return;
+        modelsLoaded = true;
+        fillProviders(result.providers);
+        if (page === 0) {
+            dropdown.innerHTML = '';
+            prependAutoOption(query);
+        }
+        if (result.total === 0) {
+            if (!dropdown.children.length) updateDropdown();
+            return;
+        }
+        appendModels(result, query);
+    } catch (e) {
+        if (seq !== modelSearchSeq) return;
+        console.error('Failed to load models:', e);
+        updateDropdown(false, 'Failed to load models');
     }
 }
 
 // Close dropdown when clicking outside
 document.addEventListener('click', (e) => {
-    if (!e.target.closest('.model-input-wrapper')) {
+    if (!e.target.closest('.model-input-wrapper')
+        && !e.target.closest('.model-sort')
+        && !e.target.closest('.model-filters')) {
         document.getElementById('modelDropdown').classList.remove('show');
     }
 });
 
-// Model search on input - immediate filtering
+// Model search on input, once typing pauses
 document.getElementById('model').addEventListener('input', (e) => {
-    const query = e.target.value;
-    const apiKey = document.getElementById('apiKey').value;
-    const dropdown = document.getElementById('modelDropdown');
-
-    if (!apiKey.trim()) {
-        updateDropdown(null, false, 'Enter API key first');
-        dropdown.classList.add('show');
-        return;
-    }
+    clearTimeout(modelSearchTimer);
+    modelSearchTimer = setTimeout(() => searchModels(e.target.value), 120);
+});
 
-    // If we have cached models, filter immediately
-    if (modelsCache) {
-        const filtered = filterModels(query, modelsCache);
-        updateDropdown(filtered);
-        dropdown.classList.add('show');
-    } else {
-        // Fetch models first time
-        searchModels(query);
-    }
+['modelProvider', 'modelReasoning', 'modelLongContext'].forEach(id => {
+    document.getElementById(id).addEventListener('change', () => {
+        searchModels(document.getElementById('model').value);
+    });
 });
 
 // Show dropdown on focus if there's content or API key
@@ -215,6 +763,8 @@ hotkeyInput.addEventListener('blur', async () => {
     }
     if (!hotkeyInput.value) {
         hotkeyInput.placeholder = 'Click and press keys...';
+    } else {
+        checkHotkey('hotkey', hotkeyInput.value);
     }
 });
 
@@ -233,13 +783,16 @@ document.addEventListener('keydown', (e) => {
 
     const parts = [];
     if (e.ctrlKey) parts.push('Ctrl');
-    if (e.altKey) parts.push('Alt');
+    if (e.altKey) parts.push(IS_MAC ? 'Option' : 'Alt');
     if (e.shiftKey) parts.push('Shift');
-    if (e.metaKey) parts.push('Win');
+    if (e.metaKey) parts.push(IS_MAC ? 'Cmd' : 'Win');
 
     // Get key name
     let key = e.key;
     if (key === ' ') key = 'Space';
+    else if (e.code.startsWith('Numpad')) key = e.code;
+    // Punctuation and shifted digits by key position, whatever character Shift (or Option) produced
+    else if (key.length === 1 && !/[a-z0-9]/i.test(key)) key = e.code.replace(/^(Digit|Key)/, '');
     else if (key.length === 1) key = key.toUpperCase();
     else if (key.startsWith('Arrow')) key = key.replace('Arrow', '');
 
@@ -249,5 +802,40 @@ document.addEventListener('keydown', (e) => {
     hotkeyInput.blur();
 }, { capture: true });
 
+// Only X11 and Wayland have a PRIMARY selection
+if (!navigator.userAgent.includes('Linux')) {
+    document.getElementById('primarySelectionItem').style.display = 'none';
+}
+
+if (IS_MAC) {
+    document.querySelectorAll('option[value="Win"]').forEach(option => { option.textContent = 'Cmd'; });
+    document.querySelectorAll('option[value="Alt"]').forEach(option => { option.textContent = 'Option'; });
+}
+
+// Settings locked by the administrator's policy.json are shown but cannot be changed
+async function loadPolicy() {
+    try {
+        const policy = await invoke('get_policy');
+        for (const field of Object.keys(policy.settings)) {
+            const id = FIELD_INPUTS[field] ?? field.replace(/_([a-z])/g, (_, letter) => letter.toUpperCase());
+            const input = document.getElementById(id);
+            if (input) {
+                input.disabled = true;
+                input.title = 'Set by your administrator';
+            }
+        }
+    } catch (e) {
+        console.warn('Failed to load policy:', e);
+    }
+}
+
 // Load config on startup
-loadConfig();
+loadConfig().then(loadAccountStatus);
+loadPolicy();
+loadLanguages();
+loadUsage();
+
+// config.json was edited outside the app
+window.__TAURI__.event.listen('config-changed', () => {
+    loadConfig();
+});
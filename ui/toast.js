@@ -1,20 +1,33 @@
 const icons = {
     success: '<svg viewBox="0 0 24 24"><path d="M9 16.17L4.83 12l-1.42 1.41L9 19 21 7l-1.41-1.41z"/></svg>',
     error: '<svg viewBox="0 0 24 24"><path d="M19 6.41L17.59 5 12 10.59 6.41 5 5 6.41 10.59 12 5 17.59 6.41 19 12 13.41 17.59 19 19 17.59 13.41 12z"/></svg>',
+    cached: '<svg viewBox="0 0 24 24"><path d="M7 2v11h3v9l7-12h-4l4-8z"/></svg>',
+    budget: '<svg viewBox="0 0 24 24"><path d="M12 2a10 10 0 100 20 10 10 0 000-20zm1 15h-2v-2h2v2zm0-4h-2V7h2v6z"/></svg>',
     processing: '<svg viewBox="0 0 24 24"><circle cx="12" cy="12" r="10" fill="none" stroke="currentColor" stroke-width="2" stroke-dasharray="31.4" stroke-linecap="round"><animateTransform attributeName="transform" type="rotate" from="0 12 12" to="360 12 12" dur="1s" repeatCount="indefinite"/></circle></svg>'
 };
 
 const labels = {
     success: 'Done',
     error: 'Error',
+    cached: 'Done (cached)',
+    budget: 'Budget reached',
     processing: 'Translating...'
 };
 
+// Kind currently shown, so progress ticks only touch the processing toast
+let currentKind = null;
+// Processing title the timer is appended to, e.g. with the cost estimate
+let processingTitle = labels.processing;
+
 function update(kind, title) {
+    currentKind = kind;
     const toast = document.getElementById('toast');
     const icon = document.getElementById('icon');
     const text = document.getElementById('text');
 
+    if (kind === 'processing') {
+        processingTitle = title || labels.processing;
+    }
     toast.className = 'toast ' + kind;
     icon.innerHTML = icons[kind] || icons.success;
     text.textContent = title || labels[kind] || kind;
@@ -33,4 +46,10 @@ if (window.__TAURI__) {
     window.__TAURI__.event.listen('update-toast', (event) => {
         update(event.payload.kind, event.payload.title);
     });
+    window.__TAURI__.event.listen('translation-progress', (event) => {
+        if (currentKind === 'processing') {
+            const seconds = Math.floor(event.payload.elapsed_ms / 1000);
+            document.getElementById('text').textContent = `${processingTitle} ${seconds}s`;
+        }
+    });
 }